@@ -1,22 +1,70 @@
 use std::path::PathBuf;
 
+use crate::export;
+
 /// Fornjot - Experimental CAD System
 #[derive(clap::Parser)]
 pub struct Args {
-    /// The model to open
-    #[clap(short, long)]
-    pub model: Option<PathBuf>,
+    /// The subcommand to run
+    ///
+    /// If no subcommand is specified, the model is viewed.
+    #[clap(subcommand)]
+    pub command: Option<Command>,
 
-    /// Export model to this path
-    #[clap(short, long)]
-    pub export: Option<PathBuf>,
+    /// The model to open
+    ///
+    /// Can be specified multiple times, to view multiple models together.
+    /// Overrides `assembly` and `default_model` from the configuration file.
+    #[clap(short, long, global = true)]
+    pub model: Vec<PathBuf>,
 
     /// Parameters for the model, each in the form `key=value`
-    #[clap(short, long)]
+    ///
+    /// Only supported, if a single model is opened.
+    #[clap(short, long, global = true)]
     pub parameters: Vec<String>,
 
+    /// List the parameters that the model accepts, then exit
+    #[clap(long, global = true)]
+    pub help_parameters: bool,
+
+    /// Compile the model to WebAssembly and run it in a sandbox
+    ///
+    /// While running, the model has no access to the file system, network, or
+    /// other processes. Building it still runs its build scripts and
+    /// procedural macros natively, so this is no protection against untrusted
+    /// models. Requires the `wasm32-unknown-unknown` target to be installed.
+    #[cfg(feature = "wasm")]
+    #[clap(long, global = true)]
+    pub wasm: bool,
+
+    /// Build the model in release mode
+    ///
+    /// Same as `--profile release`.
+    #[clap(long, global = true, conflicts_with = "profile")]
+    pub release: bool,
+
+    /// The Cargo profile to build the model with
+    ///
+    /// Overrides `profile` from the configuration file.
+    #[clap(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Features of the model to enable
+    ///
+    /// Added to `features` from the configuration file.
+    #[clap(long, global = true, use_value_delimiter = true)]
+    pub features: Vec<String>,
+
+    /// Additional argument to pass to `cargo build`, when building the model
+    ///
+    /// Can be specified multiple times. Added to `cargo_args` from the
+    /// configuration file.
+    #[clap(long, global = true, allow_hyphen_values = true)]
+    pub cargo_arg: Vec<String>,
+
     /// Model deviation tolerance
-    #[clap[short, long]]
+    #[clap(short, long, global = true)]
     pub tolerance: Option<f64>,
 }
 
@@ -29,3 +77,33 @@ impl Args {
         <Self as clap::Parser>::parse()
     }
 }
+
+/// The subcommands of the application
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// View the model, reloading it whenever it changes
+    View,
+
+    /// Export the model to a file
+    Export {
+        /// The path to export the model to
+        path: PathBuf,
+
+        /// Format of the exported file
+        ///
+        /// If not specified, the format is determined by the file extension.
+        #[clap(long, arg_enum)]
+        format: Option<export::Format>,
+    },
+
+    /// Print information about the model
+    ///
+    /// Prints the bounding box, the number of faces, edges, and vertices, the
+    /// number of triangles, and the tolerance used to compute them.
+    Info,
+
+    /// Validate the model
+    ///
+    /// Exits with a non-zero status, if validation fails.
+    Validate,
+}
@@ -0,0 +1,150 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use fj_math::Transform;
+use parry3d_f64::math::Isometry;
+use serde::Deserialize;
+
+/// A model that is part of an assembly
+///
+/// Multiple models can be viewed together, each with its own parameters and
+/// placement. The placement is defined like [`fj::Transform`]: A rotation
+/// around `axis` by `angle` (in radians), followed by a translation by
+/// `offset`.
+#[derive(Debug, Deserialize)]
+pub struct Part {
+    /// The path to the model, relative to `default_path`
+    pub model: PathBuf,
+
+    /// The parameters that are passed to the model
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+
+    /// The axis of the rotation
+    #[serde(default = "default_axis")]
+    pub axis: [f64; 3],
+
+    /// The angle of the rotation
+    #[serde(default)]
+    pub angle: f64,
+
+    /// The offset of the translation
+    #[serde(default)]
+    pub offset: [f64; 3],
+}
+
+impl Part {
+    /// Create a part that consists of just the model, without any placement
+    pub fn from_model(model: PathBuf) -> Self {
+        Self {
+            model,
+            parameters: HashMap::new(),
+            axis: default_axis(),
+            angle: 0.,
+            offset: [0.; 3],
+        }
+    }
+
+    /// The transform that places the model in the assembly
+    ///
+    /// Returns `None`, if the model stays where it is. Returns an error, if
+    /// the placement is invalid, for example because the model is rotated
+    /// around a zero-length axis.
+    pub fn placement(&self) -> anyhow::Result<Option<Transform>> {
+        let mut values =
+            self.axis.iter().chain(&self.offset).chain([&self.angle]);
+        if values.any(|value| !value.is_finite()) {
+            anyhow::bail!(
+                "Invalid placement of `{}`: `axis`, `angle`, and `offset` must \
+                be finite",
+                self.model.display()
+            );
+        }
+
+        if self.angle == 0. && self.offset == [0.; 3] {
+            return Ok(None);
+        }
+
+        let axis = nalgebra::Vector::from(self.axis);
+        if self.angle != 0. && axis.norm() == 0. {
+            anyhow::bail!(
+                "Invalid placement of `{}`: Can't rotate around a zero-length \
+                `axis`",
+                self.model.display()
+            );
+        }
+
+        let axis = axis.normalize();
+        let isometry = Isometry::new(
+            nalgebra::Vector::from(self.offset),
+            axis * self.angle,
+        );
+
+        Ok(Some(isometry.into()))
+    }
+}
+
+fn default_axis() -> [f64; 3] {
+    [0., 0., 1.]
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{
+        providers::{Format as _, Toml},
+        Figment,
+    };
+    use fj_math::Point;
+
+    use crate::config::Config;
+
+    use super::Part;
+
+    #[test]
+    fn assembly() {
+        let config: Config = Figment::from(Toml::string(
+            r#"
+            [[assembly]]
+            model = "cuboid"
+            parameters = { x = "3.0" }
+
+            [[assembly]]
+            model = "spacer"
+            offset = [4.0, 0.0, 0.0]
+            "#,
+        ))
+        .extract()
+        .unwrap();
+
+        let [cuboid, spacer] = match config.assembly.as_slice() {
+            [cuboid, spacer] => [cuboid, spacer],
+            parts => panic!("Unexpected parts: {:?}", parts),
+        };
+
+        assert_eq!(cuboid.parameters["x"], "3.0");
+        assert!(cuboid.placement().unwrap().is_none());
+
+        assert_eq!(spacer.axis, [0., 0., 1.]);
+        let placement = spacer.placement().unwrap().unwrap();
+        assert_eq!(
+            placement.transform_point(&Point::from([1., 2., 3.])),
+            Point::from([5., 2., 3.])
+        );
+    }
+    #[test]
+    fn invalid_placement() {
+        let mut part = Part::from_model("cuboid".into());
+        part.offset = [1., 0., 0.];
+        assert!(part.placement().is_ok());
+
+        // Moving the part, without rotating it, doesn't need an axis.
+        part.axis = [0.; 3];
+        assert!(part.placement().is_ok());
+
+        part.angle = 1.;
+        assert!(part.placement().is_err());
+
+        part.axis = [0., 0., 1.];
+        part.offset = [f64::NAN, 0., 0.];
+        assert!(part.placement().is_err());
+    }
+}
@@ -7,11 +7,21 @@ use figment::{
 };
 use serde::Deserialize;
 
+use crate::assembly::Part;
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub default_path: Option<PathBuf>,
     pub default_model: Option<PathBuf>,
     pub target_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub cargo_args: Vec<String>,
+    pub watch_debounce_ms: Option<u64>,
+    #[serde(default)]
+    pub assembly: Vec<Part>,
 }
 
 impl Config {
@@ -0,0 +1,190 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::Point;
+
+use super::outline::{angle, Outline, Segment};
+
+pub fn export(outlines: &[Outline], path: &Path) -> anyhow::Result<()> {
+    if outlines.is_empty() {
+        anyhow::bail!("Can't export empty shape to DXF");
+    }
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write(outlines, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write outlines as an ASCII DXF file
+///
+/// Every segment becomes a `LINE`, `ARC`, or `CIRCLE` entity on layer `0`.
+/// DXF has no concept of faces, so the contours of all outlines are simply
+/// written one after the other.
+///
+/// Apart from a minimal header that declares the DXF version (R12), only the
+/// entities section is written, as that is all that is required. R12 has no
+/// way to declare the drawing units, so the coordinates are written as they
+/// are, which means millimeters.
+pub fn write(outlines: &[Outline], out: &mut impl Write) -> io::Result<()> {
+    let mut writer = Writer { out };
+
+    writer.section("HEADER")?;
+    writer.pair(9, "$ACADVER")?;
+    writer.pair(1, "AC1009")?;
+    writer.pair(0, "ENDSEC")?;
+
+    writer.section("ENTITIES")?;
+    for segment in outlines
+        .iter()
+        .flat_map(|outline| &outline.contours)
+        .flatten()
+    {
+        writer.segment(segment)?;
+    }
+    writer.pair(0, "ENDSEC")?;
+
+    writer.pair(0, "EOF")?;
+
+    Ok(())
+}
+
+struct Writer<'r, W> {
+    out: &'r mut W,
+}
+
+impl<W: Write> Writer<'_, W> {
+    fn pair(&mut self, code: u16, value: impl ToString) -> io::Result<()> {
+        writeln!(self.out, "{:>3}", code)?;
+        writeln!(self.out, "{}", value.to_string())
+    }
+
+    fn section(&mut self, name: &str) -> io::Result<()> {
+        self.pair(0, "SECTION")?;
+        self.pair(2, name)
+    }
+
+    fn entity(&mut self, kind: &str) -> io::Result<()> {
+        self.pair(0, kind)?;
+        self.pair(8, "0")
+    }
+
+    fn point(&mut self, code: u16, point: Point<2>) -> io::Result<()> {
+        let [x, y]: [f64; 2] = point.into();
+
+        self.pair(code, x)?;
+        self.pair(code + 10, y)?;
+        self.pair(code + 20, 0.)
+    }
+
+    fn segment(&mut self, segment: &Segment) -> io::Result<()> {
+        match *segment {
+            Segment::Line { start, end } => {
+                self.entity("LINE")?;
+                self.point(10, start)?;
+                self.point(11, end)?;
+            }
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                end,
+                counter_clockwise,
+            } => {
+                // Arcs always go counter-clockwise in DXF.
+                let (from, to) = if counter_clockwise {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+
+                self.entity("ARC")?;
+                self.point(10, center)?;
+                self.pair(40, f64::from(radius))?;
+                self.pair(50, angle(center, from).to_degrees())?;
+                self.pair(51, angle(center, to).to_degrees())?;
+            }
+            Segment::Circle { center, radius } => {
+                self.entity("CIRCLE")?;
+                self.point(10, center)?;
+                self.pair(40, f64::from(radius))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::export::outline::{Outline, Segment};
+
+    #[test]
+    fn entities() {
+        let outline = Outline {
+            color: [255, 0, 0, 255],
+            contours: vec![
+                vec![
+                    Segment::Line {
+                        start: Point::from([2., 0.]),
+                        end: Point::from([-2., 0.]),
+                    },
+                    Segment::Arc {
+                        center: Point::from([0., 0.]),
+                        radius: Scalar::TWO,
+                        start: Point::from([-2., 0.]),
+                        end: Point::from([2., 0.]),
+                        counter_clockwise: true,
+                    },
+                ],
+                vec![Segment::Circle {
+                    center: Point::from([0., -1.]),
+                    radius: Scalar::from_f64(0.5),
+                }],
+            ],
+        };
+
+        let mut buf = Vec::new();
+        super::write(&[outline], &mut buf).unwrap();
+        let dxf = String::from_utf8(buf).unwrap();
+
+        // DXF consists of pairs of lines: a group code, and a value.
+        let lines: Vec<_> = dxf.lines().collect();
+        assert_eq!(lines.len() % 2, 0);
+        let pairs: Vec<_> = lines
+            .chunks(2)
+            .map(|pair| (pair[0].trim().parse::<u16>().unwrap(), pair[1]))
+            .collect();
+
+        let entities: Vec<_> = pairs
+            .iter()
+            .filter(|(code, _)| *code == 0)
+            .map(|(_, value)| *value)
+            .collect();
+        assert_eq!(
+            entities,
+            [
+                "SECTION", "ENDSEC", "SECTION", "LINE", "ARC", "CIRCLE",
+                "ENDSEC", "EOF"
+            ]
+        );
+
+        // The arc goes counter-clockwise from 180° to 0°, which is the lower
+        // half of the circle.
+        let arc = pairs.iter().position(|(_, value)| *value == "ARC").unwrap();
+        let angles: Vec<_> = pairs[arc..]
+            .iter()
+            .filter(|(code, _)| *code == 50 || *code == 51)
+            .map(|(_, value)| value.parse::<f64>().unwrap())
+            .collect();
+        assert_eq!(angles.len(), 2);
+        assert!((angles[0] - 180.).abs() < 1e-9);
+        assert!(angles[1].abs() < 1e-9);
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    collections::BTreeMap, f32::consts::FRAC_1_SQRT_2, fs, mem::size_of,
+    path::Path,
+};
+
+use fj_math::Triangle;
+use serde_json::{json, Value};
+
+use crate::graphics::{Vertex, Vertices};
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+/// The container that a glTF asset is written to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Container {
+    /// A single binary `.glb` file
+    Binary,
+
+    /// A `.gltf` JSON file, with the buffer in an accompanying `.bin` file
+    Separate,
+}
+
+pub fn export(
+    triangles: &[Triangle<3>],
+    path: &Path,
+    container: Container,
+) -> anyhow::Result<()> {
+    if triangles.is_empty() {
+        anyhow::bail!("Can't export empty shape to glTF");
+    }
+
+    match container {
+        Container::Binary => {
+            let asset = Asset::new(triangles, None);
+            fs::write(path, asset.to_glb())?;
+        }
+        Container::Separate => {
+            let bin_path = path.with_extension("bin");
+
+            // Can't panic. `with_extension` makes sure there is a file name.
+            let bin_file_name = bin_path.file_name().unwrap().to_string_lossy();
+
+            let asset = Asset::new(triangles, Some(&bin_file_name));
+            fs::write(path, serde_json::to_vec_pretty(&asset.json)?)?;
+            fs::write(&bin_path, &asset.buffer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A glTF asset, consisting of the JSON document and a single binary buffer
+pub struct Asset {
+    pub json: Value,
+    pub buffer: Vec<u8>,
+}
+
+impl Asset {
+    /// Build a glTF asset from triangles
+    ///
+    /// The triangles are grouped by color, and each group becomes a primitive
+    /// with its own material. The vertices of each primitive are converted
+    /// using [`Vertices`], just like the ones that are rendered, and are
+    /// written to the buffer as-is.
+    ///
+    /// If `buffer_uri` is `None`, the buffer is expected to be embedded in a
+    /// GLB container.
+    pub fn new(triangles: &[Triangle<3>], buffer_uri: Option<&str>) -> Self {
+        let mut triangles_by_color = BTreeMap::new();
+        for triangle in triangles {
+            triangles_by_color
+                .entry(triangle.color())
+                .or_insert_with(Vec::new)
+                .push(*triangle);
+        }
+
+        let mut buffer = Vec::new();
+        let mut buffer_views = Vec::new();
+        let mut accessors = Vec::new();
+        let mut materials = Vec::new();
+        let mut primitives = Vec::new();
+
+        for (color, triangles) in triangles_by_color {
+            let vertices = Vertices::from(&triangles);
+
+            let (min, max) = bounds(vertices.vertices());
+
+            let vertex_view = buffer_views.len();
+            buffer_views.push(json!({
+                "buffer": 0,
+                "byteOffset": buffer.len(),
+                "byteLength": vertices.vertices().len() * size_of::<Vertex>(),
+                "byteStride": size_of::<Vertex>(),
+                "target": ARRAY_BUFFER,
+            }));
+            buffer.extend_from_slice(bytemuck::cast_slice(vertices.vertices()));
+
+            let index_view = buffer_views.len();
+            buffer_views.push(json!({
+                "buffer": 0,
+                "byteOffset": buffer.len(),
+                "byteLength": vertices.indices().len() * size_of::<u32>(),
+                "target": ELEMENT_ARRAY_BUFFER,
+            }));
+            buffer.extend_from_slice(bytemuck::cast_slice(vertices.indices()));
+
+            let position = accessors.len();
+            accessors.push(json!({
+                "bufferView": vertex_view,
+                "byteOffset": 0,
+                "componentType": FLOAT,
+                "count": vertices.vertices().len(),
+                "type": "VEC3",
+                "min": min,
+                "max": max,
+            }));
+
+            let normal = accessors.len();
+            accessors.push(json!({
+                "bufferView": vertex_view,
+                "byteOffset": size_of::<[f32; 3]>(),
+                "componentType": FLOAT,
+                "count": vertices.vertices().len(),
+                "type": "VEC3",
+            }));
+
+            let indices = accessors.len();
+            accessors.push(json!({
+                "bufferView": index_view,
+                "componentType": UNSIGNED_INT,
+                "count": vertices.indices().len(),
+                "type": "SCALAR",
+            }));
+
+            let material = materials.len();
+            let alpha_mode = if color[3] == 255 { "OPAQUE" } else { "BLEND" };
+            materials.push(json!({
+                "pbrMetallicRoughness": {
+                    "baseColorFactor": color.map(|v| f32::from(v) / 255.0),
+                    "metallicFactor": 0.0,
+                    "roughnessFactor": 1.0,
+                },
+                "alphaMode": alpha_mode,
+            }));
+
+            primitives.push(json!({
+                "attributes": {
+                    "POSITION": position,
+                    "NORMAL": normal,
+                },
+                "indices": indices,
+                "material": material,
+            }));
+        }
+
+        let mut buffer_json = json!({ "byteLength": buffer.len() });
+        if let Some(uri) = buffer_uri {
+            buffer_json["uri"] = json!(uri);
+        }
+
+        // Fornjot uses a z-up coordinate system, while glTF is y-up. Rotate the
+        // root node by -90° around the x-axis to compensate.
+        let rotation = [-FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2];
+
+        let json = json!({
+            "asset": {
+                "version": "2.0",
+                "generator": "Fornjot",
+            },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{
+                "mesh": 0,
+                "rotation": rotation,
+            }],
+            "meshes": [{ "primitives": primitives }],
+            "materials": materials,
+            "accessors": accessors,
+            "bufferViews": buffer_views,
+            "buffers": [buffer_json],
+        });
+
+        Self { json, buffer }
+    }
+
+    /// Encode the asset as a binary GLB container
+    pub fn to_glb(&self) -> Vec<u8> {
+        // Can't fail. `Value` can always be serialized.
+        let mut json = serde_json::to_vec(&self.json).unwrap();
+        pad(&mut json, b' ');
+
+        let mut bin = self.buffer.clone();
+        pad(&mut bin, 0);
+
+        let length = 12 + 8 + json.len() + 8 + bin.len();
+
+        let mut glb = Vec::with_capacity(length);
+
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&bin);
+
+        glb
+    }
+}
+
+/// Pad the chunk to a multiple of 4 bytes, as required by the GLB format
+fn pad(chunk: &mut Vec<u8>, padding: u8) {
+    while chunk.len() % 4 != 0 {
+        chunk.push(padding);
+    }
+}
+
+fn bounds(vertices: &[Vertex]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+
+    for vertex in vertices {
+        for ((min, max), position) in
+            min.iter_mut().zip(&mut max).zip(vertex.position)
+        {
+            *min = min.min(position);
+            *max = max.max(position);
+        }
+    }
+
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Triangle};
+    use serde_json::Value;
+
+    use super::Asset;
+
+    #[test]
+    fn glb_layout() {
+        let asset = Asset::new(&triangles(), None);
+        let glb = asset.to_glb();
+
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32_at(&glb, 4), 2);
+        assert_eq!(u32_at(&glb, 8) as usize, glb.len());
+
+        let json_len = u32_at(&glb, 12) as usize;
+        assert_eq!(json_len % 4, 0);
+        assert_eq!(&glb[16..20], b"JSON");
+        let json: Value = serde_json::from_slice(&glb[20..20 + json_len])
+            .expect("JSON chunk is not valid JSON");
+
+        let bin_start = 20 + json_len;
+        let bin_len = u32_at(&glb, bin_start) as usize;
+        assert_eq!(bin_len % 4, 0);
+        assert_eq!(&glb[bin_start + 4..bin_start + 8], b"BIN\0");
+        assert_eq!(bin_start + 8 + bin_len, glb.len());
+
+        assert_eq!(json, asset.json);
+        assert!(json["buffers"][0].get("uri").is_none());
+        assert!(
+            json["buffers"][0]["byteLength"].as_u64().unwrap() as usize
+                <= bin_len
+        );
+    }
+
+    #[test]
+    fn json_and_buffer_layout() {
+        let asset = Asset::new(&triangles(), Some("model.bin"));
+        let json = &asset.json;
+
+        assert_eq!(json["asset"]["version"], "2.0");
+        assert_eq!(json["buffers"][0]["uri"], "model.bin");
+        assert_eq!(
+            json["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+            asset.buffer.len()
+        );
+
+        // One primitive and material per color.
+        let primitives = json["meshes"][0]["primitives"].as_array().unwrap();
+        let materials = json["materials"].as_array().unwrap();
+        assert_eq!(primitives.len(), 2);
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0]["alphaMode"], "BLEND");
+        assert_eq!(materials[1]["alphaMode"], "OPAQUE");
+
+        let mut num_triangles = 0;
+        for primitive in primitives {
+            let position = accessor(json, &primitive["attributes"]["POSITION"]);
+            let normal = accessor(json, &primitive["attributes"]["NORMAL"]);
+            let indices = accessor(json, &primitive["indices"]);
+
+            assert_eq!(position["type"], "VEC3");
+            assert_eq!(normal["type"], "VEC3");
+            assert_eq!(indices["type"], "SCALAR");
+
+            let num_vertices = position["count"].as_u64().unwrap() as usize;
+            let vertex_data = view(&asset, json, &position);
+            assert_eq!(vertex_data.len(), num_vertices * 40);
+
+            let index_data = view(&asset, json, &indices);
+            let index_data: Vec<u32> = index_data
+                .chunks(4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+                .collect();
+            assert_eq!(
+                index_data.len(),
+                indices["count"].as_u64().unwrap() as usize
+            );
+            assert!(index_data.iter().all(|&i| (i as usize) < num_vertices));
+
+            // Every position must be within the bounds the accessor declares.
+            for vertex in vertex_data.chunks(40) {
+                for i in 0..3 {
+                    let value = f32::from_le_bytes(
+                        vertex[i * 4..i * 4 + 4].try_into().unwrap(),
+                    );
+                    let min = position["min"][i].as_f64().unwrap() as f32;
+                    let max = position["max"][i].as_f64().unwrap() as f32;
+                    assert!(min <= value && value <= max);
+                }
+            }
+
+            num_triangles += index_data.len() / 3;
+        }
+
+        assert_eq!(num_triangles, triangles().len());
+    }
+
+    fn accessor<'r>(json: &'r Value, index: &Value) -> &'r Value {
+        &json["accessors"][index.as_u64().unwrap() as usize]
+    }
+
+    fn view<'r>(asset: &'r Asset, json: &Value, accessor: &Value) -> &'r [u8] {
+        let view = &json["bufferViews"]
+            [accessor["bufferView"].as_u64().unwrap() as usize];
+
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        let length = view["byteLength"].as_u64().unwrap() as usize;
+
+        &asset.buffer[offset..offset + length]
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn triangles() -> Vec<Triangle<3>> {
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let mut green = Triangle::from([a, c, d]);
+        green.set_color([0, 255, 0, 200]);
+
+        vec![Triangle::from([a, b, c]), green, Triangle::from([a, d, b])]
+    }
+}
@@ -0,0 +1,144 @@
+mod dxf;
+mod gltf;
+mod obj;
+mod outline;
+mod ply;
+mod step;
+mod stl;
+mod svg;
+mod three_mf;
+
+use std::path::Path;
+
+use crate::{
+    shape_file::{self, Encoding},
+    shape_processor::ShapeProcessor,
+};
+
+use self::outline::Outline;
+
+/// The file formats that a model can be exported to
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ArgEnum)]
+pub enum Format {
+    /// 3D Manufacturing Format
+    #[clap(name = "3mf")]
+    ThreeMf,
+
+    /// Binary STL
+    Stl,
+
+    /// ASCII STL
+    StlAscii,
+
+    /// Wavefront OBJ, with colors written to an accompanying MTL file
+    Obj,
+
+    /// Polygon File Format, with vertex and face colors
+    Ply,
+
+    /// glTF 2.0, with the buffer in an accompanying `.bin` file
+    Gltf,
+
+    /// Binary glTF 2.0
+    Glb,
+
+    /// STEP (AP214), exported from the exact boundary representation
+    Step,
+
+    /// SVG drawing of a 2D shape
+    Svg,
+
+    /// DXF drawing of a 2D shape
+    Dxf,
+
+    /// The model's CSG tree, serialized as JSON
+    ///
+    /// Can be viewed again, without compiling the model.
+    Json,
+
+    /// The model's CSG tree, serialized as RON
+    ///
+    /// Can be viewed again, without compiling the model.
+    Ron,
+}
+
+impl Format {
+    /// Determine the export format from the extension of a path
+    ///
+    /// Returns `None`, if the path has no extension, or the extension doesn't
+    /// belong to a supported format.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "3mf" => Some(Self::ThreeMf),
+            "stl" => Some(Self::Stl),
+            "obj" => Some(Self::Obj),
+            "ply" => Some(Self::Ply),
+            "gltf" => Some(Self::Gltf),
+            "glb" => Some(Self::Glb),
+            "step" | "stp" => Some(Self::Step),
+            "svg" => Some(Self::Svg),
+            "dxf" => Some(Self::Dxf),
+            "json" => Some(Self::Json),
+            "ron" => Some(Self::Ron),
+            _ => None,
+        }
+    }
+
+    /// Indicate whether the format can only represent 2D shapes
+    pub fn is_2d(&self) -> bool {
+        matches!(self, Self::Svg | Self::Dxf)
+    }
+
+    /// The encoding, if this format is a serialized shape file
+    pub fn shape_file_encoding(&self) -> Option<Encoding> {
+        match self {
+            Self::Json => Some(Encoding::Json),
+            Self::Ron => Some(Encoding::Ron),
+            _ => None,
+        }
+    }
+}
+
+/// Export a shape to a file
+///
+/// Serialized shape files are written from the shape itself. All other
+/// formats require the shape to be processed first: Mesh formats are written
+/// from its triangles, STEP and the 2D formats from its boundary
+/// representation.
+pub fn export(
+    shape: &fj::Shape,
+    shape_processor: &ShapeProcessor,
+    path: &Path,
+    format: Format,
+) -> anyhow::Result<()> {
+    if let Some(encoding) = format.shape_file_encoding() {
+        return shape_file::save(shape, path, encoding);
+    }
+
+    let shape = shape_processor.process(shape)?;
+    let triangles = &shape.triangles;
+
+    match format {
+        Format::ThreeMf => three_mf::export(triangles, path),
+        Format::Stl => stl::export(triangles, path, stl::Encoding::Binary),
+        Format::StlAscii => stl::export(triangles, path, stl::Encoding::Ascii),
+        Format::Obj => obj::export(triangles, path),
+        Format::Ply => ply::export(triangles, path),
+        Format::Gltf => {
+            gltf::export(triangles, path, gltf::Container::Separate)
+        }
+        Format::Glb => gltf::export(triangles, path, gltf::Container::Binary),
+        Format::Step => step::export(shape.brep.clone(), path),
+        Format::Svg => {
+            svg::export(&Outline::from_shape(shape.brep.clone()), path)
+        }
+        Format::Dxf => {
+            dxf::export(&Outline::from_shape(shape.brep.clone()), path)
+        }
+        Format::Json | Format::Ron => {
+            unreachable!("Serialized shape files are written above")
+        }
+    }
+}
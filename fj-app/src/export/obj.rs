@@ -0,0 +1,185 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::Triangle;
+
+use crate::mesh::MeshMaker;
+
+/// Export triangles as a Wavefront OBJ file, along with an MTL file
+///
+/// The MTL file is written next to the OBJ file, with the same file name, but
+/// the extension `.mtl`. It contains one material for each distinct color of
+/// the triangles.
+pub fn export(triangles: &[Triangle<3>], path: &Path) -> anyhow::Result<()> {
+    let mtl_path = path.with_extension("mtl");
+
+    // Can't panic. `with_extension` makes sure there is a file name.
+    let mtl_file_name = mtl_path.file_name().unwrap().to_string_lossy();
+
+    let mut obj = BufWriter::new(File::create(path)?);
+    write_obj(triangles, &mtl_file_name, &mut obj)?;
+    obj.flush()?;
+
+    let mut mtl = BufWriter::new(File::create(&mtl_path)?);
+    write_mtl(triangles, &mut mtl)?;
+    mtl.flush()?;
+
+    Ok(())
+}
+
+/// Write triangles as an OBJ file that refers to the given MTL file
+pub fn write_obj(
+    triangles: &[Triangle<3>],
+    mtl_file_name: &str,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut mesh_maker = MeshMaker::new();
+    for triangle in triangles {
+        for vertex in triangle.points() {
+            mesh_maker.push(vertex);
+        }
+    }
+
+    writeln!(out, "mtllib {}", mtl_file_name)?;
+
+    for vertex in mesh_maker.vertices() {
+        let [x, y, z]: [f64; 3] = vertex.into();
+        writeln!(out, "v {} {} {}", x, y, z)?;
+    }
+
+    // Group the faces by color, so we only need to switch materials once per
+    // color.
+    let indices: Vec<_> = mesh_maker.indices().collect();
+    let mut faces_by_color = BTreeMap::new();
+    for (triangle, indices) in triangles.iter().zip(indices.chunks(3)) {
+        faces_by_color
+            .entry(triangle.color())
+            .or_insert_with(Vec::new)
+            .push(indices);
+    }
+
+    for (color, faces) in faces_by_color {
+        writeln!(out, "usemtl {}", material_name(color))?;
+
+        for face in faces {
+            // OBJ indices start at 1.
+            writeln!(out, "f {} {} {}", face[0] + 1, face[1] + 1, face[2] + 1)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write an MTL file with one material per distinct triangle color
+pub fn write_mtl(
+    triangles: &[Triangle<3>],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut colors: Vec<_> =
+        triangles.iter().map(|triangle| triangle.color()).collect();
+    colors.sort_unstable();
+    colors.dedup();
+
+    for color in colors {
+        let [r, g, b, a] = color.map(|v| f32::from(v) / 255.0);
+
+        writeln!(out, "newmtl {}", material_name(color))?;
+        writeln!(out, "Kd {} {} {}", r, g, b)?;
+        writeln!(out, "d {}", a)?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn material_name([r, g, b, a]: [u8; 4]) -> String {
+    format!("color_{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Triangle};
+
+    #[test]
+    fn obj_round_trip() {
+        let triangles = triangles();
+
+        let mut buf = Vec::new();
+        super::write_obj(&triangles, "model.mtl", &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        let mut material = None;
+
+        for line in text.lines() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("mtllib") => {
+                    assert_eq!(tokens.next(), Some("model.mtl"));
+                }
+                Some("v") => {
+                    let vertex: Vec<f64> =
+                        tokens.map(|v| v.parse().unwrap()).collect();
+                    vertices
+                        .push(Point::from([vertex[0], vertex[1], vertex[2]]));
+                }
+                Some("usemtl") => {
+                    material = tokens.next().map(str::to_owned);
+                }
+                Some("f") => {
+                    let points: Vec<_> = tokens
+                        .map(|i| vertices[i.parse::<usize>().unwrap() - 1])
+                        .collect();
+                    faces.push((
+                        [points[0], points[1], points[2]],
+                        material.clone().unwrap(),
+                    ));
+                }
+                _ => panic!("Unexpected line: {}", line),
+            }
+        }
+
+        // Shared vertices are deduplicated.
+        assert_eq!(vertices.len(), 4);
+
+        for triangle in &triangles {
+            let face =
+                (triangle.points(), super::material_name(triangle.color()));
+            assert!(faces.contains(&face));
+        }
+        assert_eq!(faces.len(), triangles.len());
+    }
+
+    #[test]
+    fn mtl_has_one_material_per_color() {
+        let mut buf = Vec::new();
+        super::write_mtl(&triangles(), &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        let materials: Vec<_> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("newmtl "))
+            .collect();
+        assert_eq!(materials, ["color_00ff00c8", "color_ff0000ff"]);
+
+        assert!(text.contains("Kd 0 1 0\n"));
+        assert!(text.contains("Kd 1 0 0\n"));
+    }
+
+    fn triangles() -> Vec<Triangle<3>> {
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let mut green = Triangle::from([a, c, d]);
+        green.set_color([0, 255, 0, 200]);
+
+        vec![Triangle::from([a, b, c]), green, Triangle::from([a, d, b])]
+    }
+}
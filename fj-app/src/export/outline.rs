@@ -0,0 +1,322 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use fj_kernel::{
+    geometry::Curve,
+    shape::Shape,
+    topology::{Cycle, Face},
+};
+use fj_math::{Point, Scalar};
+
+/// The outline of a 2-dimensional face
+///
+/// This is the common input of the 2D exporters. It only contains the x and y
+/// coordinates of the face's cycles, which is fine, as 2-dimensional shapes
+/// always lie in the x-y plane.
+pub struct Outline {
+    /// The color of the face
+    pub color: [u8; 4],
+
+    /// The cycles that bound the face, as ordered and connected segments
+    pub contours: Vec<Vec<Segment>>,
+}
+
+impl Outline {
+    /// Compute the outlines of all faces of a shape
+    ///
+    /// Faces that use triangle representation are ignored. They only result
+    /// from sweeping, and hence don't occur in 2-dimensional shapes.
+    pub fn from_shape(mut shape: Shape) -> Vec<Self> {
+        let mut outlines = Vec::new();
+
+        for face in shape.topology().faces() {
+            if let Face::Face { cycles, color, .. } = &*face.get() {
+                let contours = cycles
+                    .iter()
+                    .map(|cycle| contour(&cycle.get()))
+                    .filter(|contour| !contour.is_empty())
+                    .collect();
+
+                outlines.push(Self {
+                    color: *color,
+                    contours,
+                });
+            }
+        }
+
+        outlines
+    }
+}
+
+/// A segment of a contour
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+    /// A straight line
+    Line { start: Point<2>, end: Point<2> },
+
+    /// An arc of a circle
+    Arc {
+        center: Point<2>,
+        radius: Scalar,
+        start: Point<2>,
+        end: Point<2>,
+
+        /// Whether the arc goes from `start` to `end` counter-clockwise
+        counter_clockwise: bool,
+    },
+
+    /// A full circle
+    Circle { center: Point<2>, radius: Scalar },
+}
+
+impl Segment {
+    /// The point where the segment starts
+    ///
+    /// Circles start at the point on the circle with the largest x coordinate.
+    pub fn start(&self) -> Point<2> {
+        match *self {
+            Self::Line { start, .. } | Self::Arc { start, .. } => start,
+            Self::Circle { center, radius } => {
+                Point::from([center.u + radius, center.v])
+            }
+        }
+    }
+
+    /// The point where the segment ends
+    pub fn end(&self) -> Point<2> {
+        match *self {
+            Self::Line { end, .. } | Self::Arc { end, .. } => end,
+            Self::Circle { .. } => self.start(),
+        }
+    }
+
+    /// The same segment, traversed in the opposite direction
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Line { start, end } => Self::Line {
+                start: end,
+                end: start,
+            },
+            Self::Arc {
+                center,
+                radius,
+                start,
+                end,
+                counter_clockwise,
+            } => Self::Arc {
+                center,
+                radius,
+                start: end,
+                end: start,
+                counter_clockwise: !counter_clockwise,
+            },
+            circle @ Self::Circle { .. } => circle,
+        }
+    }
+
+    /// The points at which the segment reaches its extent along each axis
+    ///
+    /// Together with the start and end points, these define the bounding box
+    /// of the segment.
+    pub fn extreme_points(&self) -> Vec<Point<2>> {
+        let (center, radius) = match *self {
+            Self::Line { start, end } => return vec![start, end],
+            Self::Arc { center, radius, .. }
+            | Self::Circle { center, radius } => (center, radius),
+        };
+
+        let candidates = [
+            Point::from([center.u + radius, center.v]),
+            Point::from([center.u, center.v + radius]),
+            Point::from([center.u - radius, center.v]),
+            Point::from([center.u, center.v - radius]),
+        ];
+
+        match *self {
+            Self::Arc {
+                start,
+                end,
+                counter_clockwise,
+                ..
+            } => {
+                let (from, to) = if counter_clockwise {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+                let from = angle(center, from);
+                let sweep = (angle(center, to) - from).rem_euclid(TAU);
+
+                let mut points = vec![start, end];
+                for (i, candidate) in candidates.into_iter().enumerate() {
+                    let offset = (FRAC_PI_2 * i as f64 - from).rem_euclid(TAU);
+                    if offset < sweep {
+                        points.push(candidate);
+                    }
+                }
+                points
+            }
+            _ => candidates.to_vec(),
+        }
+    }
+}
+
+/// The angle of `point` around `center`, between `0` and `2π`
+pub fn angle(center: Point<2>, point: Point<2>) -> f64 {
+    let v = point - center;
+    f64::from(v.v).atan2(f64::from(v.u)).rem_euclid(TAU)
+}
+
+/// Convert a cycle into a contour of connected segments
+///
+/// The edges of a [`Cycle`] are neither necessarily ordered, nor oriented
+/// consistently. The segments of the returned contour are, as drawing them as
+/// a single path requires that.
+///
+/// Arcs (circle edges that are bounded by vertices) are assumed to go counter-
+/// clockwise from their first vertex to their second one.
+fn contour(cycle: &Cycle) -> Vec<Segment> {
+    let mut segments = Vec::new();
+
+    for edge in cycle.edges() {
+        let segment = match (edge.curve(), edge.vertices()) {
+            (Curve::Line(_), Some([a, b])) => Segment::Line {
+                start: xy(a.point()),
+                end: xy(b.point()),
+            },
+            (Curve::Circle(circle), Some([a, b])) => Segment::Arc {
+                center: xy(circle.center),
+                radius: circle.radius.magnitude(),
+                start: xy(a.point()),
+                end: xy(b.point()),
+                counter_clockwise: true,
+            },
+            (Curve::Circle(circle), None) => Segment::Circle {
+                center: xy(circle.center),
+                radius: circle.radius.magnitude(),
+            },
+            (Curve::Line(_), None) => {
+                // A continuous line is infinite. It can't bound a face, and it
+                // can't be drawn.
+                continue;
+            }
+        };
+
+        segments.push(segment);
+    }
+
+    let mut contour: Vec<Segment> = Vec::new();
+    while !segments.is_empty() {
+        // If no connected segment is found, the cycle is malformed. Just pick
+        // the next segment, then.
+        let next = contour
+            .last()
+            .and_then(|previous| {
+                let end = previous.end();
+                segments.iter().position(|segment| {
+                    segment.start() == end || segment.end() == end
+                })
+            })
+            .unwrap_or(0);
+        let segment = segments.remove(next);
+
+        let segment = match contour.last() {
+            Some(previous) if segment.start() != previous.end() => {
+                segment.reverse()
+            }
+            _ => segment,
+        };
+
+        contour.push(segment);
+    }
+
+    contour
+}
+
+fn xy(point: Point<3>) -> Point<2> {
+    Point::from([point.x, point.y])
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_kernel::{
+        geometry::Surface,
+        shape::Shape,
+        topology::{Cycle, Face, Vertex},
+    };
+    use fj_math::{Point, Scalar};
+
+    use super::{Outline, Segment};
+
+    #[test]
+    fn contour_is_connected() {
+        let mut shape = Shape::new();
+
+        let [a, b, c] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(|point| {
+                let point = shape.geometry().add_point(Point::from(point));
+                shape.topology().add_vertex(Vertex { point }).unwrap()
+            });
+
+        // Edges are neither ordered, nor oriented consistently.
+        let edges = [[a.clone(), b.clone()], [a, c.clone()], [b, c]]
+            .map(|vertices| {
+                shape.topology().add_line_segment(vertices).unwrap()
+            })
+            .to_vec();
+        let cycle = shape.topology().add_cycle(Cycle { edges }).unwrap();
+
+        let surface = shape.geometry().add_surface(Surface::x_y_plane());
+        shape
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles: vec![cycle],
+                color: [255, 0, 0, 255],
+            })
+            .unwrap();
+
+        let outlines = Outline::from_shape(shape);
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].color, [255, 0, 0, 255]);
+
+        let contour = &outlines[0].contours[0];
+        assert_eq!(contour.len(), 3);
+        for (i, segment) in contour.iter().enumerate() {
+            let next = &contour[(i + 1) % contour.len()];
+            assert_eq!(segment.end(), next.start());
+        }
+    }
+
+    #[test]
+    fn arc_extreme_points() {
+        let center = Point::from([0., 0.]);
+        let radius = Scalar::ONE;
+
+        let quarter = Segment::Arc {
+            center,
+            radius,
+            start: Point::from([1., 0.]),
+            end: Point::from([0., 1.]),
+            counter_clockwise: true,
+        };
+        assert_eq!(
+            quarter.extreme_points(),
+            [
+                Point::from([1., 0.]),
+                Point::from([0., 1.]),
+                Point::from([1., 0.])
+            ]
+        );
+
+        // The same arc in reverse, going the long way around.
+        let three_quarters = Segment::Arc {
+            center,
+            radius,
+            start: Point::from([1., 0.]),
+            end: Point::from([0., 1.]),
+            counter_clockwise: false,
+        };
+        assert_eq!(three_quarters.extreme_points().len(), 2 + 3);
+    }
+}
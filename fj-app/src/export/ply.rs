@@ -0,0 +1,150 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::Triangle;
+
+use crate::mesh::MeshMaker;
+
+pub fn export(triangles: &[Triangle<3>], path: &Path) -> anyhow::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write(triangles, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write triangles as an ASCII PLY file
+///
+/// Colors are written both as vertex and as face properties. Some applications
+/// (like Blender) only understand the former, others (like MeshLab) prefer the
+/// latter.
+///
+/// To make the vertex colors work, vertices are only deduplicated if they have
+/// the same color. Vertices on the border between two differently colored
+/// regions are written once per color.
+pub fn write(
+    triangles: &[Triangle<3>],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut mesh_maker = MeshMaker::new();
+    for triangle in triangles {
+        for vertex in triangle.points() {
+            mesh_maker.push((vertex, triangle.color()));
+        }
+    }
+
+    let num_vertices = mesh_maker.vertices().count();
+
+    writeln!(out, "ply")?;
+    writeln!(out, "format ascii 1.0")?;
+    writeln!(out, "comment exported by Fornjot")?;
+    writeln!(out, "element vertex {}", num_vertices)?;
+    writeln!(out, "property double x")?;
+    writeln!(out, "property double y")?;
+    writeln!(out, "property double z")?;
+    write_color_properties(out)?;
+    writeln!(out, "element face {}", triangles.len())?;
+    writeln!(out, "property list uchar uint vertex_indices")?;
+    write_color_properties(out)?;
+    writeln!(out, "end_header")?;
+
+    for (vertex, [r, g, b, a]) in mesh_maker.vertices() {
+        let [x, y, z]: [f64; 3] = vertex.into();
+        writeln!(out, "{} {} {} {} {} {} {}", x, y, z, r, g, b, a)?;
+    }
+
+    let indices: Vec<_> = mesh_maker.indices().collect();
+    for (triangle, face) in triangles.iter().zip(indices.chunks(3)) {
+        let [r, g, b, a] = triangle.color();
+        writeln!(
+            out,
+            "3 {} {} {} {} {} {} {}",
+            face[0], face[1], face[2], r, g, b, a
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_color_properties(out: &mut impl Write) -> io::Result<()> {
+    for channel in ["red", "green", "blue", "alpha"] {
+        writeln!(out, "property uchar {}", channel)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Triangle};
+
+    #[test]
+    fn round_trip() {
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let mut green = Triangle::from([a, c, d]);
+        green.set_color([0, 255, 0, 200]);
+
+        let triangles =
+            vec![Triangle::from([a, b, c]), green, Triangle::from([a, d, b])];
+
+        let mut buf = Vec::new();
+        super::write(&triangles, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        let (header, body) = text.split_once("end_header\n").unwrap();
+        assert!(header.starts_with("ply\nformat ascii 1.0\n"));
+
+        // `a`, `c`, and `d` are shared between differently colored triangles,
+        // so they have to be written twice.
+        assert!(header.contains("element vertex 7\n"));
+        assert!(header.contains("element face 3\n"));
+
+        let mut lines = body.lines();
+
+        let vertices: Vec<_> = lines
+            .by_ref()
+            .take(7)
+            .map(|line| {
+                let values: Vec<&str> = line.split_whitespace().collect();
+                let point = Point::from(
+                    [0, 1, 2].map(|i| values[i].parse::<f64>().unwrap()),
+                );
+                let color =
+                    [3, 4, 5, 6].map(|i| values[i].parse::<u8>().unwrap());
+                (point, color)
+            })
+            .collect();
+
+        let faces: Vec<_> = lines
+            .map(|line| {
+                let values: Vec<usize> = line
+                    .split_whitespace()
+                    .map(|value| value.parse().unwrap())
+                    .collect();
+                assert_eq!(values[0], 3);
+
+                let points = [1, 2, 3].map(|i| vertices[values[i]].0);
+                let color = [4, 5, 6, 7].map(|i| values[i] as u8);
+
+                for i in [1, 2, 3] {
+                    assert_eq!(vertices[values[i]].1, color);
+                }
+
+                (points, color)
+            })
+            .collect();
+
+        let expected: Vec<_> = triangles
+            .iter()
+            .map(|triangle| (triangle.points(), triangle.color()))
+            .collect();
+        assert_eq!(faces, expected);
+    }
+}
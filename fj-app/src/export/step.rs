@@ -0,0 +1,679 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_kernel::{
+    geometry::{Circle, Curve, RevolvedCurve, Surface, SweptCurve},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+use fj_math::{Point, Triangle, Vector};
+
+/// Export a shape's boundary representation as a STEP (AP214) file
+pub fn export(shape: Shape, path: &Path) -> anyhow::Result<()> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write(shape, &name, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write a shape's boundary representation as a STEP (AP214) file
+///
+/// Vertices, edges, cycles, and faces are mapped to the corresponding STEP
+/// topology entities, and curves and surfaces are written exactly. Faces that
+/// still use triangle representation are written as one planar face per
+/// triangle.
+///
+/// If every edge in the shape bounds exactly two faces, the shape is written
+/// as a solid. Otherwise, it's written as a surface model.
+///
+/// # Limitations
+///
+/// The orientation of faces is not tracked by the kernel, so all faces are
+/// written with the orientation of their surface. Importing applications
+/// might need to fix the orientation.
+pub fn write(
+    mut shape: Shape,
+    name: &str,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut writer = Writer::new();
+
+    let mut faces = Vec::new();
+    for face in shape.topology().faces() {
+        match &*face.get() {
+            Face::Face {
+                surface, cycles, ..
+            } => {
+                let bounds: Vec<_> = cycles
+                    .iter()
+                    .map(|cycle| writer.face_bound(cycle))
+                    .collect();
+                let surface = writer.surface(&surface.get());
+
+                faces.push(writer.add(format!(
+                    "ADVANCED_FACE('',({}),#{},.T.)",
+                    list(&bounds),
+                    surface
+                )));
+            }
+            Face::Triangles(triangles) => {
+                for triangle in triangles {
+                    faces.push(writer.triangle(triangle));
+                }
+            }
+        }
+    }
+
+    let representation_context = writer.add_representation_context();
+    let product_definition_shape = writer.add_product(name);
+
+    let origin =
+        writer.placement(Point::origin(), Vector::unit_z(), Vector::unit_x());
+
+    let representation = if writer.is_closed() {
+        let shell = writer.add(format!("CLOSED_SHELL('',({}))", list(&faces)));
+        let solid = writer.add(format!("MANIFOLD_SOLID_BREP('',#{})", shell));
+        writer.add(format!(
+            "ADVANCED_BREP_SHAPE_REPRESENTATION('',(#{},#{}),#{})",
+            solid, origin, representation_context
+        ))
+    } else {
+        let shell = writer.add(format!("OPEN_SHELL('',({}))", list(&faces)));
+        let model =
+            writer.add(format!("SHELL_BASED_SURFACE_MODEL('',(#{}))", shell));
+        writer.add(format!(
+            "MANIFOLD_SURFACE_SHAPE_REPRESENTATION('',(#{},#{}),#{})",
+            model, origin, representation_context
+        ))
+    };
+    writer.add(format!(
+        "SHAPE_DEFINITION_REPRESENTATION(#{},#{})",
+        product_definition_shape, representation
+    ));
+
+    writeln!(out, "ISO-10303-21;")?;
+    writeln!(out, "HEADER;")?;
+    writeln!(out, "FILE_DESCRIPTION(('Fornjot model'),'2;1');")?;
+    writeln!(
+        out,
+        "FILE_NAME('{}','',(''),(''),'Fornjot','Fornjot','');",
+        escape(name)
+    )?;
+    writeln!(
+        out,
+        "FILE_SCHEMA(('AUTOMOTIVE_DESIGN {{ 1 0 10303 214 1 1 1 1 }}'));"
+    )?;
+    writeln!(out, "ENDSEC;")?;
+    writeln!(out, "DATA;")?;
+    for (i, entity) in writer.entities.iter().enumerate() {
+        // Entity instance names start at 1.
+        writeln!(out, "#{}={};", i + 1, entity)?;
+    }
+    writeln!(out, "ENDSEC;")?;
+    writeln!(out, "END-ISO-10303-21;")?;
+
+    Ok(())
+}
+
+/// The instance name of a STEP entity
+type Id = usize;
+
+/// A STEP edge, along with the vertices that bound it
+#[derive(Clone, Copy)]
+struct EdgeCurve {
+    id: Id,
+    start: Id,
+    end: Id,
+}
+
+struct Writer {
+    entities: Vec<String>,
+
+    vertices: HashMap<Handle<Vertex>, Id>,
+    edges: HashMap<Handle<Edge>, EdgeCurve>,
+
+    // Faces in triangle representation don't have any topology. We need to
+    // create it from their points.
+    facet_vertices: HashMap<Point<3>, Id>,
+    facet_edges: HashMap<[Point<3>; 2], EdgeCurve>,
+
+    /// How many face bounds each edge is used in
+    edge_uses: HashMap<Id, usize>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            entities: Vec::new(),
+            vertices: HashMap::new(),
+            edges: HashMap::new(),
+            facet_vertices: HashMap::new(),
+            facet_edges: HashMap::new(),
+            edge_uses: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, entity: String) -> Id {
+        self.entities.push(entity);
+        self.entities.len()
+    }
+
+    fn is_closed(&self) -> bool {
+        !self.edge_uses.is_empty()
+            && self.edge_uses.values().all(|&uses| uses == 2)
+    }
+
+    fn point(&mut self, point: Point<3>) -> Id {
+        self.add(format!(
+            "CARTESIAN_POINT('',({},{},{}))",
+            real(point.x),
+            real(point.y),
+            real(point.z)
+        ))
+    }
+
+    fn direction(&mut self, direction: Vector<3>) -> Id {
+        let direction = direction.normalize();
+        self.add(format!(
+            "DIRECTION('',({},{},{}))",
+            real(direction.x),
+            real(direction.y),
+            real(direction.z)
+        ))
+    }
+
+    fn vector(&mut self, vector: Vector<3>) -> Id {
+        let direction = self.direction(vector);
+        self.add(format!(
+            "VECTOR('',#{},{})",
+            direction,
+            real(vector.magnitude())
+        ))
+    }
+
+    fn placement(
+        &mut self,
+        origin: Point<3>,
+        axis: Vector<3>,
+        ref_direction: Vector<3>,
+    ) -> Id {
+        let origin = self.point(origin);
+        let axis = self.direction(axis);
+        let ref_direction = self.direction(ref_direction);
+        self.add(format!(
+            "AXIS2_PLACEMENT_3D('',#{},#{},#{})",
+            origin, axis, ref_direction
+        ))
+    }
+
+    fn vertex_point(&mut self, point: Point<3>) -> Id {
+        let point = self.point(point);
+        self.add(format!("VERTEX_POINT('',#{})", point))
+    }
+
+    fn vertex(&mut self, vertex: &Handle<Vertex>) -> Id {
+        if let Some(&id) = self.vertices.get(vertex) {
+            return id;
+        }
+
+        let id = self.vertex_point(vertex.get().point());
+        self.vertices.insert(vertex.clone(), id);
+        id
+    }
+
+    fn line(&mut self, a: Point<3>, b: Point<3>) -> Id {
+        let origin = self.point(a);
+        let vector = self.vector(b - a);
+        self.add(format!("LINE('',#{},#{})", origin, vector))
+    }
+
+    fn circle(&mut self, circle: &Circle) -> Id {
+        let placement =
+            self.placement(circle.center, circle.normal, circle.radius);
+        self.add(format!(
+            "CIRCLE('',#{},{})",
+            placement,
+            real(circle.radius.magnitude())
+        ))
+    }
+
+    fn edge(&mut self, edge: &Handle<Edge>) -> EdgeCurve {
+        if let Some(&edge_curve) = self.edges.get(edge) {
+            return edge_curve;
+        }
+
+        let edge_ref = edge.get();
+        let (start, end, curve) = match &edge_ref.vertices {
+            Some([a, b]) => {
+                let start = self.vertex(a);
+                let end = self.vertex(b);
+
+                let curve = match edge_ref.curve() {
+                    // A line segment is fully defined by its vertices. Rely on
+                    // those, instead of the curve, so the edge is guaranteed to
+                    // connect them.
+                    Curve::Line(_) => {
+                        self.line(a.get().point(), b.get().point())
+                    }
+                    Curve::Circle(circle) => self.circle(&circle),
+                };
+
+                (start, end, curve)
+            }
+            None => {
+                // The edge is continuous, but STEP requires edges to be
+                // bounded. Bound it by a single vertex at the start of the
+                // curve.
+                let curve = edge_ref.curve();
+                let point = curve.point_curve_to_model(&Point::from([0.]));
+                let vertex = self.vertex_point(point);
+
+                let curve = match curve {
+                    Curve::Line(line) => {
+                        self.line(line.origin, line.origin + line.direction)
+                    }
+                    Curve::Circle(circle) => self.circle(&circle),
+                };
+
+                (vertex, vertex, curve)
+            }
+        };
+
+        let id = self
+            .add(format!("EDGE_CURVE('',#{},#{},#{},.T.)", start, end, curve));
+
+        let edge_curve = EdgeCurve { id, start, end };
+        self.edges.insert(edge.clone(), edge_curve);
+        edge_curve
+    }
+
+    fn face_bound(&mut self, cycle: &Handle<Cycle>) -> Id {
+        let edges: Vec<_> = cycle
+            .get()
+            .edges
+            .iter()
+            .map(|edge| self.edge(edge))
+            .collect();
+        self.bound(edges)
+    }
+
+    /// Write a face bound from edges that form a cycle
+    ///
+    /// The edges of a [`Cycle`] are not necessarily ordered, nor oriented
+    /// consistently. STEP requires both, so this method puts the edges in
+    /// order, reversing them where necessary.
+    fn bound(&mut self, mut edges: Vec<EdgeCurve>) -> Id {
+        let mut oriented_edges = Vec::new();
+        let mut current = None;
+
+        while !edges.is_empty() {
+            // If no connected edge is found, the cycle is malformed. There's
+            // not much we can do about that here, so just pick the next edge.
+            let next = current
+                .and_then(|vertex| {
+                    edges.iter().position(|edge: &EdgeCurve| {
+                        edge.start == vertex || edge.end == vertex
+                    })
+                })
+                .unwrap_or(0);
+            let edge = edges.remove(next);
+
+            let same_sense = match current {
+                Some(vertex) => edge.start == vertex,
+                None => true,
+            };
+            current = Some(if same_sense { edge.end } else { edge.start });
+
+            *self.edge_uses.entry(edge.id).or_insert(0) += 1;
+
+            oriented_edges.push(self.add(format!(
+                "ORIENTED_EDGE('',*,*,#{},{})",
+                edge.id,
+                logical(same_sense)
+            )));
+        }
+
+        let edge_loop =
+            self.add(format!("EDGE_LOOP('',({}))", list(&oriented_edges)));
+        self.add(format!("FACE_BOUND('',#{},.T.)", edge_loop))
+    }
+
+    fn surface(&mut self, surface: &Surface) -> Id {
+        match surface {
+            Surface::SweptCurve(swept) => self.swept_curve(swept),
+            Surface::RevolvedCurve(revolved) => self.revolved_curve(revolved),
+        }
+    }
+
+    fn swept_curve(&mut self, swept: &SweptCurve) -> Id {
+        match swept.curve {
+            Curve::Line(line) => {
+                let normal = line.direction.cross(&swept.path);
+                let placement =
+                    self.placement(line.origin, normal, line.direction);
+                self.add(format!("PLANE('',#{})", placement))
+            }
+            Curve::Circle(circle)
+                if swept.path.cross(&circle.normal)
+                    == Vector::from([0.; 3]) =>
+            {
+                let placement =
+                    self.placement(circle.center, swept.path, circle.radius);
+                self.add(format!(
+                    "CYLINDRICAL_SURFACE('',#{},{})",
+                    placement,
+                    real(circle.radius.magnitude())
+                ))
+            }
+            Curve::Circle(circle) => {
+                let curve = self.circle(&circle);
+                let vector = self.vector(swept.path);
+                self.add(format!(
+                    "SURFACE_OF_LINEAR_EXTRUSION('',#{},#{})",
+                    curve, vector
+                ))
+            }
+        }
+    }
+
+    fn revolved_curve(&mut self, revolved: &RevolvedCurve) -> Id {
+        let curve = match revolved.curve {
+            Curve::Line(line) => {
+                self.line(line.origin, line.origin + line.direction)
+            }
+            Curve::Circle(circle) => self.circle(&circle),
+        };
+
+        let origin = self.point(revolved.axis.origin);
+        let direction = self.direction(revolved.axis.direction);
+        let axis =
+            self.add(format!("AXIS1_PLACEMENT('',#{},#{})", origin, direction));
+
+        self.add(format!("SURFACE_OF_REVOLUTION('',#{},#{})", curve, axis))
+    }
+
+    fn triangle(&mut self, triangle: &Triangle<3>) -> Id {
+        let [a, b, c] = triangle.points();
+
+        let edges = [[a, b], [b, c], [c, a]]
+            .map(|segment| self.facet_edge(segment))
+            .to_vec();
+        let bound = self.bound(edges);
+
+        let normal = (b - a).cross(&(c - a));
+        let placement = self.placement(a, normal, b - a);
+        let plane = self.add(format!("PLANE('',#{})", placement));
+
+        self.add(format!("ADVANCED_FACE('',(#{}),#{},.T.)", bound, plane))
+    }
+
+    fn facet_vertex(&mut self, point: Point<3>) -> Id {
+        if let Some(&id) = self.facet_vertices.get(&point) {
+            return id;
+        }
+
+        let id = self.vertex_point(point);
+        self.facet_vertices.insert(point, id);
+        id
+    }
+
+    fn facet_edge(&mut self, [a, b]: [Point<3>; 2]) -> EdgeCurve {
+        // Neighboring triangles share edges, but in opposite directions.
+        let key = if a < b { [a, b] } else { [b, a] };
+
+        if let Some(&edge_curve) = self.facet_edges.get(&key) {
+            return edge_curve;
+        }
+
+        let [start, end] = key.map(|point| self.facet_vertex(point));
+        let line = self.line(key[0], key[1]);
+        let id = self
+            .add(format!("EDGE_CURVE('',#{},#{},#{},.T.)", start, end, line));
+
+        let edge_curve = EdgeCurve { id, start, end };
+        self.facet_edges.insert(key, edge_curve);
+        edge_curve
+    }
+
+    fn add_representation_context(&mut self) -> Id {
+        // Fornjot models don't have units yet. Millimeters are the most
+        // reasonable assumption for mechanical parts.
+        let length_unit = self.add(
+            "(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.))".into(),
+        );
+        let angle_unit = self.add(
+            "(NAMED_UNIT(*) PLANE_ANGLE_UNIT() SI_UNIT($,.RADIAN.))".into(),
+        );
+        let solid_angle_unit = self.add(
+            "(NAMED_UNIT(*) SI_UNIT($,.STERADIAN.) SOLID_ANGLE_UNIT())".into(),
+        );
+        let uncertainty = self.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE(1.E-07),#{},\
+            'distance_accuracy_value','')",
+            length_unit
+        ));
+
+        self.add(format!(
+            "(GEOMETRIC_REPRESENTATION_CONTEXT(3) \
+            GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT((#{})) \
+            GLOBAL_UNIT_ASSIGNED_CONTEXT((#{},#{},#{})) \
+            REPRESENTATION_CONTEXT('',''))",
+            uncertainty, length_unit, angle_unit, solid_angle_unit
+        ))
+    }
+
+    fn add_product(&mut self, name: &str) -> Id {
+        let name = escape(name);
+
+        let application_context =
+            self.add("APPLICATION_CONTEXT('automotive design')".into());
+        self.add(format!(
+            "APPLICATION_PROTOCOL_DEFINITION('international standard',\
+            'automotive_design',2000,#{})",
+            application_context
+        ));
+        let product_context = self.add(format!(
+            "PRODUCT_CONTEXT('',#{},'mechanical')",
+            application_context
+        ));
+        let product = self.add(format!(
+            "PRODUCT('{}','{}','',(#{}))",
+            name, name, product_context
+        ));
+        let formation = self
+            .add(format!("PRODUCT_DEFINITION_FORMATION('','',#{})", product));
+        let definition_context = self.add(format!(
+            "PRODUCT_DEFINITION_CONTEXT('part definition',#{},'design')",
+            application_context
+        ));
+        let definition = self.add(format!(
+            "PRODUCT_DEFINITION('design','',#{},#{})",
+            formation, definition_context
+        ));
+
+        self.add(format!("PRODUCT_DEFINITION_SHAPE('','',#{})", definition))
+    }
+}
+
+/// Format a real number, as required by STEP
+///
+/// STEP requires real numbers to contain a decimal point, even if they are
+/// written in exponential notation.
+fn real(value: impl Into<f64>) -> String {
+    let value = format!("{:?}", value.into());
+
+    match value.split_once('e') {
+        Some((mantissa, exponent)) if mantissa.contains('.') => {
+            format!("{}E{}", mantissa, exponent)
+        }
+        Some((mantissa, exponent)) => format!("{}.E{}", mantissa, exponent),
+        None => value,
+    }
+}
+
+fn logical(value: bool) -> &'static str {
+    if value {
+        ".T."
+    } else {
+        ".F."
+    }
+}
+
+fn list(ids: &[Id]) -> String {
+    ids.iter()
+        .map(|id| format!("#{}", id))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape(string: &str) -> String {
+    string.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use fj_kernel::{
+        algorithms::sweep_shape,
+        geometry::Surface,
+        shape::Shape,
+        topology::{Cycle, Face, Vertex},
+    };
+    use fj_math::{Point, Scalar, Vector};
+
+    #[test]
+    fn real() {
+        assert_eq!(super::real(1.), "1.0");
+        assert_eq!(super::real(-0.5), "-0.5");
+        assert_eq!(super::real(1e-7), "1.E-7");
+        assert_eq!(super::real(1.5e20), "1.5E20");
+    }
+
+    #[test]
+    fn swept_triangle_is_solid() {
+        let swept = sweep_shape(
+            triangle(),
+            Vector::from([0., 0., 1.]),
+            Scalar::ONE,
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+        let step = write(swept);
+
+        assert_eq!(count(&step, "ADVANCED_FACE("), 5);
+        assert_eq!(count(&step, "EDGE_CURVE("), 9);
+        assert_eq!(count(&step, "VERTEX_POINT("), 6);
+        assert_eq!(count(&step, "PLANE("), 5);
+        assert_eq!(count(&step, "CLOSED_SHELL("), 1);
+        assert_eq!(count(&step, "MANIFOLD_SOLID_BREP("), 1);
+    }
+
+    #[test]
+    fn sketch_is_surface() {
+        let step = write(triangle());
+
+        assert_eq!(count(&step, "ADVANCED_FACE("), 1);
+        assert_eq!(count(&step, "OPEN_SHELL("), 1);
+        assert_eq!(count(&step, "SHELL_BASED_SURFACE_MODEL("), 1);
+    }
+
+    #[test]
+    fn circle_is_exact() {
+        let mut shape = Shape::new();
+        let edge = shape.topology().add_circle(Scalar::ONE).unwrap();
+        let cycle = shape
+            .topology()
+            .add_cycle(Cycle { edges: vec![edge] })
+            .unwrap();
+        let surface = shape.geometry().add_surface(Surface::x_y_plane());
+        shape
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles: vec![cycle],
+                color: [255, 0, 0, 255],
+            })
+            .unwrap();
+
+        let step = write(shape);
+
+        assert_eq!(count(&step, "CIRCLE("), 1);
+        assert_eq!(count(&step, "EDGE_CURVE("), 1);
+    }
+
+    fn write(shape: Shape) -> String {
+        let mut buf = Vec::new();
+        super::write(shape, "test.step", &mut buf).unwrap();
+        let step = String::from_utf8(buf).unwrap();
+
+        assert!(step.starts_with("ISO-10303-21;\nHEADER;\n"));
+        assert!(step.ends_with("ENDSEC;\nEND-ISO-10303-21;\n"));
+
+        // Every referenced entity must be defined.
+        let data = step.split("DATA;\n").nth(1).unwrap();
+        let mut defined = HashSet::new();
+        let mut referenced = HashSet::new();
+        for line in data.lines().filter(|line| line.starts_with('#')) {
+            let (name, entity) = line.split_once('=').unwrap();
+            defined.insert(name.to_owned());
+
+            for reference in entity.split('#').skip(1) {
+                let digits: String = reference
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                referenced.insert(format!("#{}", digits));
+            }
+        }
+        assert!(referenced.is_subset(&defined));
+
+        step
+    }
+
+    fn count(step: &str, entity: &str) -> usize {
+        step.lines()
+            .filter(|line| line.contains(&format!("={}", entity)))
+            .count()
+    }
+
+    fn triangle() -> Shape {
+        let mut shape = Shape::new();
+
+        let vertices =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(|point| {
+                let point = shape.geometry().add_point(Point::from(point));
+                shape.topology().add_vertex(Vertex { point }).unwrap()
+            });
+
+        let mut edges = Vec::new();
+        for i in 0..3 {
+            let a = vertices[i].clone();
+            let b = vertices[(i + 1) % 3].clone();
+            edges.push(shape.topology().add_line_segment([a, b]).unwrap());
+        }
+
+        let cycle = shape.topology().add_cycle(Cycle { edges }).unwrap();
+        let surface = shape.geometry().add_surface(Surface::x_y_plane());
+        shape
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles: vec![cycle],
+                color: [255, 0, 0, 255],
+            })
+            .unwrap();
+
+        shape
+    }
+}
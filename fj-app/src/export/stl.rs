@@ -0,0 +1,204 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::{Scalar, Triangle, Vector};
+
+/// The encoding of an STL file
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Ascii,
+    Binary,
+}
+
+pub fn export(
+    triangles: &[Triangle<3>],
+    path: &Path,
+    encoding: Encoding,
+) -> anyhow::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    match encoding {
+        Encoding::Ascii => write_ascii(triangles, &mut file)?,
+        Encoding::Binary => write_binary(triangles, &mut file)?,
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write triangles as an ASCII STL file
+pub fn write_ascii(
+    triangles: &[Triangle<3>],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(out, "solid fornjot")?;
+
+    for triangle in triangles {
+        let [nx, ny, nz] = normal(triangle).components.map(Scalar::into_f32);
+        writeln!(out, "  facet normal {:e} {:e} {:e}", nx, ny, nz)?;
+        writeln!(out, "    outer loop")?;
+        for vertex in triangle.points() {
+            let [x, y, z]: [f32; 3] = vertex.into();
+            writeln!(out, "      vertex {:e} {:e} {:e}", x, y, z)?;
+        }
+        writeln!(out, "    endloop")?;
+        writeln!(out, "  endfacet")?;
+    }
+
+    writeln!(out, "endsolid fornjot")?;
+
+    Ok(())
+}
+
+/// Write triangles as a binary STL file
+pub fn write_binary(
+    triangles: &[Triangle<3>],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    // The header is not used for anything, but it must not start with "solid",
+    // or some readers will mistake the file for an ASCII STL file.
+    let mut header = [0u8; 80];
+    let text = b"Binary STL exported by Fornjot";
+    header[..text.len()].copy_from_slice(text);
+    out.write_all(&header)?;
+
+    let num_triangles = u32::try_from(triangles.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Too many triangles for binary STL",
+        )
+    })?;
+    out.write_all(&num_triangles.to_le_bytes())?;
+
+    for triangle in triangles {
+        let normal: [f32; 3] = normal(triangle).into();
+        for component in normal {
+            out.write_all(&component.to_le_bytes())?;
+        }
+
+        for vertex in triangle.points() {
+            let vertex: [f32; 3] = vertex.into();
+            for component in vertex {
+                out.write_all(&component.to_le_bytes())?;
+            }
+        }
+
+        // Attribute byte count. Unused by the standard.
+        out.write_all(&0u16.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn normal(triangle: &Triangle<3>) -> Vector<3> {
+    let [a, b, c] = triangle.points();
+
+    // Can't divide by zero, as `Triangle` makes sure it doesn't degenerate
+    // into a line or a point.
+    (b - a).cross(&(c - a)).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Triangle};
+
+    #[test]
+    fn binary_round_trip() {
+        let triangles = triangles();
+
+        let mut buf = Vec::new();
+        super::write_binary(&triangles, &mut buf).unwrap();
+
+        assert_eq!(buf.len(), 80 + 4 + 50 * triangles.len());
+        assert!(!buf.starts_with(b"solid"));
+
+        let num_triangles = u32::from_le_bytes(buf[80..84].try_into().unwrap());
+        assert_eq!(num_triangles as usize, triangles.len());
+
+        let floats: Vec<_> = buf[84..]
+            .chunks(50)
+            .map(|record| {
+                record[..48]
+                    .chunks(4)
+                    .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        for (triangle, floats) in triangles.iter().zip(floats) {
+            assert_eq!(&floats[..3], &expected_normal(triangle));
+            assert_eq!(&floats[3..], &expected_vertices(triangle));
+        }
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let triangles = triangles();
+
+        let mut buf = Vec::new();
+        super::write_ascii(&triangles, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        let mut lines = text.lines().map(str::trim);
+        assert_eq!(lines.next(), Some("solid fornjot"));
+
+        for triangle in &triangles {
+            let normal = parse_floats(lines.next(), "facet normal");
+            assert_eq!(normal, expected_normal(triangle));
+
+            assert_eq!(lines.next(), Some("outer loop"));
+            let mut vertices = Vec::new();
+            for _ in 0..3 {
+                vertices.extend(parse_floats(lines.next(), "vertex"));
+            }
+            assert_eq!(vertices, expected_vertices(triangle));
+            assert_eq!(lines.next(), Some("endloop"));
+            assert_eq!(lines.next(), Some("endfacet"));
+        }
+
+        assert_eq!(lines.next(), Some("endsolid fornjot"));
+        assert_eq!(lines.next(), None);
+    }
+
+    fn triangles() -> Vec<Triangle<3>> {
+        vec![
+            Triangle::from([
+                Point::from([0., 0., 0.]),
+                Point::from([1., 0., 0.]),
+                Point::from([0., 1., 0.]),
+            ]),
+            Triangle::from([
+                Point::from([0., 0., 0.]),
+                Point::from([0., 0., 2.]),
+                Point::from([0., 3., 0.]),
+            ]),
+        ]
+    }
+
+    fn expected_normal(triangle: &Triangle<3>) -> Vec<f32> {
+        let normal: [f32; 3] = super::normal(triangle).into();
+        normal.to_vec()
+    }
+
+    fn expected_vertices(triangle: &Triangle<3>) -> Vec<f32> {
+        triangle
+            .points()
+            .into_iter()
+            .flat_map(<[f32; 3]>::from)
+            .collect()
+    }
+
+    fn parse_floats(line: Option<&str>, prefix: &str) -> Vec<f32> {
+        let line = line.unwrap();
+        assert!(line.starts_with(prefix), "Unexpected line: {}", line);
+
+        line[prefix.len()..]
+            .split_whitespace()
+            .map(|float| float.parse().unwrap())
+            .collect()
+    }
+}
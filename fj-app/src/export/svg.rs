@@ -0,0 +1,215 @@
+use std::{
+    f64::consts::{PI, TAU},
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::{Aabb, Point};
+
+use super::outline::{angle, Outline, Segment};
+
+pub fn export(outlines: &[Outline], path: &Path) -> anyhow::Result<()> {
+    if outlines.is_empty() {
+        anyhow::bail!("Can't export empty shape to SVG");
+    }
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write(outlines, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write outlines as an SVG file
+///
+/// Each outline becomes a single path, which consists of one closed subpath
+/// per contour. The paths are stroked in the color of their face, and are not
+/// filled, which is what laser cutters expect.
+///
+/// Units are millimeters. The y-axis of SVG points down, so the drawing is
+/// flipped vertically, to keep it the right way up.
+pub fn write(outlines: &[Outline], out: &mut impl Write) -> io::Result<()> {
+    let points = outlines
+        .iter()
+        .flat_map(|outline| &outline.contours)
+        .flatten()
+        .flat_map(|segment| segment.extreme_points());
+    let aabb = Aabb::<2>::from_points(points);
+
+    let [min_x, min_y]: [f64; 2] = aabb.min.into();
+    let [max_x, max_y]: [f64; 2] = aabb.max.into();
+    let width = max_x - min_x;
+    let height = max_y - min_y;
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" \
+        width=\"{w}mm\" height=\"{h}mm\" viewBox=\"{x} {y} {w} {h}\">",
+        x = min_x,
+        y = -max_y,
+        w = width,
+        h = height,
+    )?;
+    writeln!(out, r#"<g transform="scale(1,-1)">"#)?;
+
+    for outline in outlines {
+        let [r, g, b, _] = outline.color;
+
+        writeln!(
+            out,
+            "<path d=\"{}\" fill=\"none\" \
+            stroke=\"#{:02x}{:02x}{:02x}\" stroke-width=\"0.1\" \
+            fill-rule=\"evenodd\"/>",
+            path_data(outline),
+            r,
+            g,
+            b
+        )?;
+    }
+
+    writeln!(out, "</g>")?;
+    writeln!(out, "</svg>")?;
+
+    Ok(())
+}
+
+fn path_data(outline: &Outline) -> String {
+    let mut d = String::new();
+
+    for contour in &outline.contours {
+        // Can't panic. Outlines don't contain empty contours.
+        let start = contour[0].start();
+        write_command(&mut d, "M", &[], start);
+
+        for segment in contour {
+            match *segment {
+                Segment::Line { end, .. } => {
+                    write_command(&mut d, "L", &[], end);
+                }
+                Segment::Arc {
+                    center,
+                    radius,
+                    start,
+                    end,
+                    counter_clockwise,
+                } => {
+                    let (from, to) = if counter_clockwise {
+                        (start, end)
+                    } else {
+                        (end, start)
+                    };
+                    let sweep = (angle(center, to) - angle(center, from))
+                        .rem_euclid(TAU);
+
+                    let radius = f64::from(radius);
+                    let large_arc = sweep > PI;
+
+                    write_command(
+                        &mut d,
+                        "A",
+                        &[
+                            radius,
+                            radius,
+                            0.,
+                            flag(large_arc),
+                            flag(counter_clockwise),
+                        ],
+                        end,
+                    );
+                }
+                Segment::Circle { center, radius } => {
+                    // A single arc can't describe a full circle, as its start
+                    // and end point would be identical. Use two half-circles
+                    // instead.
+                    let radius = f64::from(radius);
+                    let opposite = Point::from([
+                        f64::from(center.u) - radius,
+                        f64::from(center.v),
+                    ]);
+
+                    for point in [opposite, segment.end()] {
+                        write_command(
+                            &mut d,
+                            "A",
+                            &[radius, radius, 0., 0., 1.],
+                            point,
+                        );
+                    }
+                }
+            }
+        }
+
+        d.push('Z');
+    }
+
+    d
+}
+
+fn write_command(d: &mut String, command: &str, args: &[f64], point: Point<2>) {
+    let [x, y]: [f64; 2] = point.into();
+
+    // Can't fail. Writing to a `String` always succeeds.
+    let _ = write!(d, "{}", command);
+    for arg in args {
+        let _ = write!(d, "{} ", arg);
+    }
+    let _ = write!(d, "{} {} ", x, y);
+}
+
+fn flag(value: bool) -> f64 {
+    if value {
+        1.
+    } else {
+        0.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::export::outline::{Outline, Segment};
+
+    #[test]
+    fn plate_with_hole() {
+        let outline = Outline {
+            color: [255, 0, 0, 255],
+            contours: vec![
+                square(),
+                vec![Segment::Circle {
+                    center: Point::from([2., 2.]),
+                    radius: Scalar::ONE,
+                }],
+            ],
+        };
+
+        let mut buf = Vec::new();
+        super::write(&[outline], &mut buf).unwrap();
+        let svg = String::from_utf8(buf).unwrap();
+
+        assert!(svg.contains(r#"width="4mm" height="4mm" viewBox="0 -4 4 4""#));
+        assert!(svg.contains(r##"stroke="#ff0000""##));
+
+        let d = svg.split(r#"d=""#).nth(1).unwrap();
+        let d = d.split('"').next().unwrap();
+        assert_eq!(
+            d,
+            "M0 0 L4 0 L4 4 L0 4 L0 0 Z\
+            M3 2 A1 1 0 0 1 1 2 A1 1 0 0 1 3 2 Z"
+        );
+    }
+
+    fn square() -> Vec<Segment> {
+        let points = [[0., 0.], [4., 0.], [4., 4.], [0., 4.]].map(Point::from);
+
+        (0..4)
+            .map(|i| Segment::Line {
+                start: points[i],
+                end: points[(i + 1) % 4],
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use fj_math::Triangle;
+
+use crate::mesh::MeshMaker;
+
+pub fn export(triangles: &[Triangle<3>], path: &Path) -> anyhow::Result<()> {
+    let mut mesh_maker = MeshMaker::new();
+
+    for triangle in triangles {
+        for vertex in triangle.points() {
+            mesh_maker.push(vertex);
+        }
+    }
+
+    let vertices = mesh_maker.vertices().map(|vertex| vertex.into()).collect();
+
+    let indices: Vec<_> = mesh_maker.indices().collect();
+    let triangles = indices
+        .chunks(3)
+        .map(|triangle| {
+            [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ]
+        })
+        .collect();
+
+    let mesh = threemf::TriangleMesh {
+        vertices,
+        triangles,
+    };
+
+    threemf::write(path.to_path_buf(), &mesh)?;
+
+    Ok(())
+}
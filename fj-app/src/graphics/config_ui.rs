@@ -7,6 +7,8 @@ use wgpu_glyph::{
     GlyphBrush, GlyphBrushBuilder, Section, Text,
 };
 
+use crate::worker::ModelStatus;
+
 use super::draw_config::DrawConfig;
 
 #[derive(Debug)]
@@ -44,6 +46,7 @@ impl ConfigUi {
         Ok(Self { glyph_brush, texts })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn draw(
         &mut self,
         device: &wgpu::Device,
@@ -52,6 +55,7 @@ impl ConfigUi {
         surface_config: &wgpu::SurfaceConfiguration,
         aabb: &Aabb<3>,
         draw_config: &DrawConfig,
+        model_status: &ModelStatus,
     ) -> Result<(), String> {
         let mut section = Section::new().with_screen_position((50.0, 50.0));
 
@@ -81,6 +85,27 @@ impl ConfigUi {
             .with_scale(50.0);
         section = section.add_text(text);
 
+        if model_status.rebuilding {
+            let text = Text::new("\nRebuilding model...")
+                .with_color([0.0, 0.0, 0.6, 1.0])
+                .with_scale(40.0);
+            section = section.add_text(text);
+        }
+
+        /* Render the error that occurred while reloading the model, if any */
+        let error;
+        if let Some(model_error) = &model_status.error {
+            error = format!(
+                "\n\n{}\n(Showing the last version of the model \
+                that loaded successfully, if any.)",
+                model_error
+            );
+            let text = Text::new(&error)
+                .with_color([0.8, 0.0, 0.0, 1.0])
+                .with_scale(40.0);
+            section = section.add_text(text);
+        }
+
         self.glyph_brush.queue(section);
         self.glyph_brush.draw_queued(
             device,
@@ -2,6 +2,7 @@ mod config_ui;
 mod draw_config;
 mod drawables;
 mod geometries;
+mod parameter_ui;
 mod pipelines;
 mod renderer;
 mod shaders;
@@ -12,6 +13,7 @@ mod vertices;
 pub use self::{
     draw_config::DrawConfig,
     renderer::{DrawError, Renderer},
+    vertices::{Vertex, Vertices},
 };
 
 const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
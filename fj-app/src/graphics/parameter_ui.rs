@@ -0,0 +1,82 @@
+use wgpu::util::StagingBelt;
+use wgpu_glyph::{
+    ab_glyph::{FontArc, InvalidFont},
+    GlyphBrush, GlyphBrushBuilder, HorizontalAlign, Layout, Section, Text,
+};
+
+use crate::parameter_panel::ParameterPanel;
+
+/// Renders the parameter panel, in the top-right corner of the window
+#[derive(Debug)]
+pub struct ParameterUi {
+    glyph_brush: GlyphBrush<()>,
+}
+
+impl ParameterUi {
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+    ) -> Result<Self, InvalidFont> {
+        let font =
+            FontArc::try_from_slice(include_bytes!("fonts/B612-Bold.ttf"))?;
+        let glyph_brush = GlyphBrushBuilder::using_font(font)
+            .initial_cache_size((512, 512))
+            .build(device, color_format);
+
+        Ok(Self { glyph_brush })
+    }
+
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        surface_config: &wgpu::SurfaceConfiguration,
+        panel: &ParameterPanel,
+    ) -> Result<(), String> {
+        let mut lines = vec![(
+            "Parameters (select with up/down, change with left/right, \
+            edit with enter)\n"
+                .to_owned(),
+            0.75,
+        )];
+        for (name, value, selected) in panel.lines() {
+            let line = if selected && panel.is_editing() {
+                format!("{} = {}_\n", name, value)
+            } else if selected {
+                format!("> {} = {}\n", name, value)
+            } else {
+                format!("{} = {}\n", name, value)
+            };
+            let alpha = if selected { 1.0 } else { 0.75 };
+
+            lines.push((line, alpha));
+        }
+        if let Some(error) = panel.error() {
+            lines.push((format!("{}\n", error), 1.0));
+        }
+
+        let mut section = Section::new()
+            .with_screen_position((surface_config.width as f32 - 50.0, 50.0))
+            .with_layout(Layout::default().h_align(HorizontalAlign::Right));
+        for (line, alpha) in &lines {
+            let text = Text::new(line)
+                .with_color([0.0, 0.0, 0.0, *alpha])
+                .with_scale(40.0);
+
+            section = section.add_text(text);
+        }
+
+        self.glyph_brush.queue(section);
+        self.glyph_brush.draw_queued(
+            device,
+            &mut StagingBelt::new(1024),
+            encoder,
+            view,
+            surface_config.width,
+            surface_config.height,
+        )?;
+
+        Ok(())
+    }
+}
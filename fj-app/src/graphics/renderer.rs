@@ -7,12 +7,15 @@ use wgpu::util::DeviceExt as _;
 use wgpu_glyph::ab_glyph::InvalidFont;
 use winit::dpi::PhysicalSize;
 
-use crate::{camera::Camera, window::Window};
+use crate::{
+    camera::Camera, parameter_panel::ParameterPanel, window::Window,
+    worker::ModelStatus,
+};
 
 use super::{
     config_ui::ConfigUi, draw_config::DrawConfig, drawables::Drawables,
-    geometries::Geometries, pipelines::Pipelines, transform::Transform,
-    uniforms::Uniforms, vertices::Vertices, DEPTH_FORMAT,
+    geometries::Geometries, parameter_ui::ParameterUi, pipelines::Pipelines,
+    transform::Transform, uniforms::Uniforms, vertices::Vertices, DEPTH_FORMAT,
 };
 
 #[derive(Debug)]
@@ -31,6 +34,7 @@ pub struct Renderer {
     pipelines: Pipelines,
 
     config_ui: ConfigUi,
+    parameter_ui: ParameterUi,
 }
 
 impl Renderer {
@@ -132,6 +136,7 @@ impl Renderer {
             Pipelines::new(&device, &bind_group_layout, color_format);
 
         let config_ui = ConfigUi::new(&device, color_format)?;
+        let parameter_ui = ParameterUi::new(&device, color_format)?;
 
         Ok(Self {
             surface,
@@ -148,6 +153,7 @@ impl Renderer {
             pipelines,
 
             config_ui,
+            parameter_ui,
         })
     }
 
@@ -175,6 +181,8 @@ impl Renderer {
         &mut self,
         camera: &Camera,
         config: &DrawConfig,
+        parameter_panel: Option<&ParameterPanel>,
+        model_status: &ModelStatus,
     ) -> Result<(), DrawError> {
         let aspect_ratio = self.surface_config.width as f64
             / self.surface_config.height as f64;
@@ -235,9 +243,22 @@ impl Renderer {
                 &self.surface_config,
                 &self.geometries.aabb,
                 config,
+                model_status,
             )
             .map_err(DrawError::Text)?;
 
+        if let Some(parameter_panel) = parameter_panel {
+            self.parameter_ui
+                .draw(
+                    &self.device,
+                    &mut encoder,
+                    &color_view,
+                    &self.surface_config,
+                    parameter_panel,
+                )
+                .map_err(DrawError::Text)?;
+        }
+
         let command_buffer = encoder.finish();
         self.queue.submit(Some(command_buffer));
 
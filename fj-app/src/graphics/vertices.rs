@@ -27,6 +27,15 @@ impl Vertices {
         self.indices.as_slice()
     }
 
+    /// Add the vertices and indices of another instance to this one
+    pub fn append(&mut self, other: Self) {
+        let offset = self.vertices.len() as Index;
+
+        self.vertices.extend(other.vertices);
+        self.indices
+            .extend(other.indices.into_iter().map(|index| index + offset));
+    }
+
     pub fn push_line(
         &mut self,
         line: [Point<f64, 3>; 2],
@@ -1,19 +1,27 @@
 mod args;
+mod assembly;
 mod camera;
 mod config;
+mod export;
 mod graphics;
 mod input;
 mod mesh;
+mod parameter_panel;
+mod parameters;
+mod shape_file;
+mod shape_processor;
+mod source;
 mod window;
+mod worker;
 
-use std::path::PathBuf;
-use std::{collections::HashMap, time::Instant};
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, Instant},
+};
 
-use fj_debug::DebugInfo;
-use fj_host::Model;
-use fj_kernel::algorithms::triangulate;
-use fj_math::{Aabb, Scalar, Triangle};
-use fj_operations::ToShape as _;
+use fj_host::{BuildOptions, Model};
+use fj_math::{Aabb, Point};
 use futures::executor::block_on;
 use tracing::trace;
 use tracing_subscriber::fmt::format;
@@ -24,12 +32,16 @@ use winit::{
 };
 
 use crate::{
-    args::Args,
+    args::{Args, Command},
+    assembly::Part,
     camera::Camera,
     config::Config,
     graphics::{DrawConfig, Renderer},
-    mesh::MeshMaker,
+    parameter_panel::ParameterPanel,
+    shape_processor::{ProcessedShape, Scene, ShapeProcessor},
+    source::Source,
     window::Window,
+    worker::{ModelStatus, Update, Worker},
 };
 
 fn main() -> anyhow::Result<()> {
@@ -49,77 +61,258 @@ fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     let config = Config::load()?;
 
-    let mut path = config.default_path.unwrap_or_else(|| PathBuf::from(""));
-    match args.model.or(config.default_model) {
-        Some(model) => {
-            path.push(model);
-        }
-        None => {
-            anyhow::bail!(
-                "No model specified, and no default model configured.\n\
-                Specify a model by passing `--model path/to/model`."
-            );
+    let mut parts = if !args.model.is_empty() {
+        args.model.into_iter().map(Part::from_model).collect()
+    } else if !config.assembly.is_empty() {
+        config.assembly
+    } else if let Some(model) = config.default_model {
+        vec![Part::from_model(model)]
+    } else {
+        anyhow::bail!(
+            "No model specified, and no default model configured.\n\
+            Specify a model by passing `--model path/to/model`."
+        );
+    };
+
+    let parameters = parameters::parse(args.parameters)?;
+    if !parameters.is_empty() {
+        match parts.as_mut_slice() {
+            [part] => part.parameters.extend(parameters),
+            _ => {
+                anyhow::bail!(
+                    "Can't pass parameters to multiple models.\n\
+                    Specify the parameters of each model in the `assembly` \
+                    section of `fj.toml` instead."
+                );
+            }
         }
     }
 
-    let model = Model::from_path(path, config.target_dir)?;
+    let build_options = BuildOptions {
+        profile: if args.release {
+            Some("release".to_owned())
+        } else {
+            args.profile.or(config.profile)
+        },
+        features: [config.features, args.features].concat(),
+        cargo_args: [config.cargo_args, args.cargo_arg].concat(),
+    };
+
+    let default_path = config.default_path.unwrap_or_default();
+
+    let mut models = Vec::new();
+    for part in parts {
+        let path = default_path.join(&part.model);
+
+        let source = if shape_file::Encoding::from_path(&path).is_some() {
+            if args.help_parameters || !part.parameters.is_empty() {
+                anyhow::bail!(
+                    "Serialized shape files have no parameters.\n\
+                    Pass the parameters when exporting the shape file instead."
+                );
+            }
+
+            Source::ShapeFile(path)
+        } else {
+            let mut model = Model::from_path(path, config.target_dir.clone())?
+                .with_build_options(build_options.clone());
+            if let Some(debounce) = config.watch_debounce_ms {
+                model = model.with_debounce(Duration::from_millis(debounce));
+            }
+            #[cfg(feature = "wasm")]
+            let model = if args.wasm {
+                model.with_backend(fj_host::Backend::Wasm)
+            } else {
+                model
+            };
+
+            Source::Model(model)
+        };
+
+        if !part.parameters.is_empty() {
+            if let Some(schema) = source.parameters()? {
+                if let Err(errors) =
+                    parameters::validate(&part.parameters, &schema)
+                {
+                    for error in &errors {
+                        eprintln!("{}", error);
+                    }
+
+                    anyhow::bail!(
+                        "Invalid parameters for model `{}`.\n\
+                        Pass `--help-parameters` to list the parameters that \
+                        the model accepts.",
+                        part.model.display()
+                    );
+                }
+            }
+        }
+
+        models.push((source, part));
+    }
 
-    let mut parameters = HashMap::new();
-    for parameter in args.parameters {
-        let mut parameter = parameter.splitn(2, '=');
+    if args.help_parameters {
+        for (source, part) in &models {
+            if models.len() > 1 {
+                println!("Model `{}`:", part.model.display());
+            }
 
-        let key = parameter
-            .next()
-            .expect("model parameter: key not found")
-            .to_owned();
-        let value = parameter
-            .next()
-            .expect("model parameter: value not found")
-            .to_owned();
+            match source.parameters()? {
+                Some(schema) => parameters::print_help(&schema),
+                None => println!("The model doesn't declare its parameters."),
+            }
+        }
 
-        parameters.insert(key, value);
+        return Ok(());
     }
 
     let shape_processor = ShapeProcessor::new(args.tolerance)?;
 
-    if let Some(path) = args.export {
-        let shape = model.load_once(&parameters)?;
-        let shape = shape_processor.process(&shape);
+    match args.command {
+        None | Some(Command::View) => view(models, shape_processor),
+        Some(Command::Export { path, format }) => {
+            let (source, parameters) = single_model(models)?;
+            export_model(&source, &parameters, &shape_processor, &path, format)
+        }
+        Some(Command::Info) => {
+            let (source, parameters) = single_model(models)?;
+            info(&source, &parameters, &shape_processor)
+        }
+        Some(Command::Validate) => {
+            let (source, parameters) = single_model(models)?;
+            validate(&source, &parameters, &shape_processor)
+        }
+    }
+}
 
-        let mut mesh_maker = MeshMaker::new();
+/// Make sure only a single model was specified, and return it
+///
+/// Only viewing supports multiple models. The other commands work with a model
+/// by itself, so the placement of an assembly part is ignored for them.
+fn single_model(
+    models: Vec<(Source, Part)>,
+) -> anyhow::Result<(Source, HashMap<String, String>)> {
+    let [(source, part)]: [_; 1] = models.try_into().map_err(|_| {
+        anyhow::anyhow!(
+            "Multiple models can only be viewed together.\n\
+            Export, inspect, or validate them one at a time, by passing a \
+            single `--model`."
+        )
+    })?;
 
-        for triangle in shape.triangles {
-            for vertex in triangle.points() {
-                mesh_maker.push(vertex);
-            }
+    Ok((source, part.parameters))
+}
+
+fn export_model(
+    source: &Source,
+    parameters: &HashMap<String, String>,
+    shape_processor: &ShapeProcessor,
+    path: &Path,
+    format: Option<export::Format>,
+) -> anyhow::Result<()> {
+    let format = match format.or_else(|| export::Format::from_path(path)) {
+        Some(format) => format,
+        None => {
+            anyhow::bail!(
+                "Can't determine export format from path: {}\n\
+                Use a known file extension (`.3mf`, `.stl`, `.obj`, `.ply`, \
+                `.gltf`, `.glb`, `.step`, `.svg`, `.dxf`, `.json`, `.ron`), \
+                or specify the format using `--format`.",
+                path.display()
+            );
         }
+    };
+
+    let shape = source.load_once(parameters)?;
+    if format.is_2d() && !matches!(shape, fj::Shape::Shape2d(_)) {
+        anyhow::bail!(
+            "Can't export 3D model as a 2D drawing.\n\
+            Only 2D models (sketches, circles, and their differences) can be \
+            exported to SVG or DXF."
+        );
+    }
 
-        let vertices =
-            mesh_maker.vertices().map(|vertex| vertex.into()).collect();
-
-        let indices: Vec<_> = mesh_maker.indices().collect();
-        let triangles = indices
-            .chunks(3)
-            .map(|triangle| {
-                [
-                    triangle[0] as usize,
-                    triangle[1] as usize,
-                    triangle[2] as usize,
-                ]
-            })
-            .collect();
-
-        let mesh = threemf::TriangleMesh {
-            vertices,
-            triangles,
-        };
+    export::export(&shape, shape_processor, path, format)
+}
+
+fn info(
+    source: &Source,
+    parameters: &HashMap<String, String>,
+    shape_processor: &ShapeProcessor,
+) -> anyhow::Result<()> {
+    let shape = source.load_once(parameters)?;
+    let shape = shape_processor.process(&shape)?;
+
+    let mut brep = shape.brep.clone();
+    let min: [f64; 3] = shape.aabb.min.into();
+    let max: [f64; 3] = shape.aabb.max.into();
+
+    println!("Bounding box: {:?} - {:?}", min, max);
+    println!("Faces: {}", brep.topology().faces().count());
+    println!("Edges: {}", brep.topology().edges().count());
+    println!("Vertices: {}", brep.topology().vertices().count());
+    println!("Triangles: {}", shape.triangles.len());
+    println!("Tolerance: {}", shape.tolerance.into_f64());
+
+    Ok(())
+}
 
-        threemf::write(path, &mesh)?;
+fn validate(
+    source: &Source,
+    parameters: &HashMap<String, String>,
+    shape_processor: &ShapeProcessor,
+) -> anyhow::Result<()> {
+    let shape = source.load_once(parameters)?;
+    let shape = shape_processor.process(&shape)?;
+
+    if let Err(errors) = shape.brep.validate() {
+        for error in &errors {
+            eprintln!("{}", error);
+        }
 
-        return Ok(());
+        anyhow::bail!("Model is invalid: {} problem(s) found", errors.len());
     }
 
-    let watcher = model.load_and_watch(parameters)?;
+    println!("Model is valid");
+
+    Ok(())
+}
+
+fn view(
+    models: Vec<(Source, Part)>,
+    shape_processor: ShapeProcessor,
+) -> anyhow::Result<()> {
+    // Only show the parameter panel, if a single model is viewed, and it
+    // declares its parameters. Otherwise there's nothing to show.
+    let mut parameter_panel = match models.as_slice() {
+        [(source, part)] => source
+            .parameters()?
+            .filter(|schema| !schema.is_empty())
+            .map(|schema| ParameterPanel::new(schema, &part.parameters)),
+        _ => None,
+    };
+
+    // Errors need to say which model they're about, if there are multiple.
+    let labels: Vec<_> = models
+        .iter()
+        .map(|(_, part)| match models.len() {
+            1 => String::new(),
+            _ => format!("{}: ", part.model.display()),
+        })
+        .collect();
+
+    let workers = models
+        .into_iter()
+        .map(|(source, part)| {
+            let placement = part.placement()?;
+            Worker::start(
+                source,
+                part.parameters,
+                placement,
+                shape_processor.clone(),
+            )
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
     let event_loop = EventLoop::new();
     let window = Window::new(&event_loop);
@@ -131,8 +324,15 @@ fn main() -> anyhow::Result<()> {
 
     let mut draw_config = DrawConfig::default();
 
-    let mut shape = None;
+    // Each model is loaded independently. The shapes are combined into a
+    // scene, which is what gets displayed.
+    let mut shapes: Vec<Option<ProcessedShape>> =
+        workers.iter().map(|_| None).collect();
+    let mut statuses: Vec<_> =
+        workers.iter().map(|_| ModelStatus::default()).collect();
+    let mut scene = None;
     let mut camera = None;
+    let mut model_status = ModelStatus::default();
 
     event_loop.run(move |event, _, control_flow| {
         trace!("Handling event: {:?}", event);
@@ -141,15 +341,49 @@ fn main() -> anyhow::Result<()> {
 
         let now = Instant::now();
 
-        if let Some(new_shape) = watcher.receive() {
-            let new_shape = shape_processor.process(&new_shape);
-            new_shape.update_geometry(&mut renderer);
+        let mut scene_changed = false;
+        let mut status_changed = false;
 
-            if camera.is_none() {
-                camera = Some(Camera::new(&new_shape.aabb));
+        for (i, worker) in workers.iter().enumerate() {
+            while let Some(update) = worker.receive() {
+                match update {
+                    Update::Rebuilding => {
+                        statuses[i].rebuilding = true;
+                    }
+                    Update::Shape(new_shape) => {
+                        shapes[i] = Some(new_shape);
+                        statuses[i] = ModelStatus::default();
+                        scene_changed = true;
+                    }
+                    Update::Error(err) => {
+                        let err = format!("{}{}", labels[i], err);
+                        eprintln!("{}", err);
+                        statuses[i] = ModelStatus {
+                            error: Some(err),
+                            rebuilding: false,
+                        };
+                    }
+                }
+
+                status_changed = true;
             }
+        }
+
+        if scene_changed {
+            scene = Scene::update(shapes.iter().flatten(), &mut renderer);
+        }
+        if status_changed {
+            model_status = ModelStatus::combine(&statuses);
+        }
 
-            shape = Some(new_shape);
+        // Wait until every model has been loaded (or failed to), before
+        // setting up the camera. Otherwise it might not show all of them.
+        let all_loaded = shapes
+            .iter()
+            .zip(&statuses)
+            .all(|(shape, status)| shape.is_some() || status.error.is_some());
+        if let (None, Some(scene), true) = (&camera, &scene, all_loaded) {
+            camera = Some(Camera::new(&scene.aabb));
         }
 
         match event {
@@ -169,7 +403,32 @@ fn main() -> anyhow::Result<()> {
                 event: WindowEvent::KeyboardInput { input, .. },
                 ..
             } => {
-                input_handler.handle_keyboard_input(input, &mut actions);
+                // The parameter panel gets the first chance to handle keyboard
+                // input. Anything it doesn't handle is passed on.
+                let handled = parameter_panel
+                    .as_mut()
+                    .and_then(|panel| panel.handle_keyboard_input(input));
+
+                match (handled, &parameter_panel) {
+                    (Some(true), Some(panel)) => {
+                        // There's only a parameter panel, if there's only a
+                        // single model.
+                        workers[0].set_parameters(panel.values().clone());
+                    }
+                    (Some(_), _) => {}
+                    (None, _) => {
+                        input_handler
+                            .handle_keyboard_input(input, &mut actions);
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(c),
+                ..
+            } => {
+                if let Some(panel) = &mut parameter_panel {
+                    panel.handle_character(c);
+                }
             }
             Event::WindowEvent {
                 event: WindowEvent::CursorMoved { position, .. },
@@ -184,11 +443,11 @@ fn main() -> anyhow::Result<()> {
                 event: WindowEvent::MouseInput { state, button, .. },
                 ..
             } => {
-                if let (Some(shape), Some(camera)) = (&shape, &camera) {
+                if let (Some(scene), Some(camera)) = (&scene, &camera) {
                     let focus_point = camera.focus_point(
                         &window,
                         input_handler.cursor(),
-                        &shape.triangles,
+                        &scene.triangles,
                     );
 
                     input_handler.handle_mouse_input(
@@ -208,27 +467,47 @@ fn main() -> anyhow::Result<()> {
                 let delta_t = now.duration_since(previous_time);
                 previous_time = now;
 
-                if let (Some(shape), Some(camera)) = (&shape, &mut camera) {
+                if let (Some(scene), Some(camera)) = (&scene, &mut camera) {
                     input_handler.update(
                         delta_t.as_secs_f64(),
                         now,
                         camera,
                         &window,
-                        &shape.triangles,
+                        &scene.triangles,
                     );
                 }
 
                 window.inner().request_redraw();
             }
             Event::RedrawRequested(_) => {
-                if let (Some(shape), Some(camera)) = (&shape, &mut camera) {
-                    camera.update_planes(&shape.aabb);
-
-                    match renderer.draw(camera, &draw_config) {
-                        Ok(()) => {}
-                        Err(err) => {
-                            panic!("Draw error: {}", err);
-                        }
+                if let (Some(scene), Some(camera)) = (&scene, &mut camera) {
+                    camera.update_planes(&scene.aabb);
+                }
+
+                // If the model has never loaded successfully, there's no
+                // camera yet. We still need to draw, to show that the model is
+                // being built, or the error that occurred doing so.
+                let fallback_camera;
+                let camera = match &camera {
+                    Some(camera) => camera,
+                    None => {
+                        fallback_camera = Camera::new(&Aabb {
+                            min: Point::origin(),
+                            max: Point::origin(),
+                        });
+                        &fallback_camera
+                    }
+                };
+
+                match renderer.draw(
+                    camera,
+                    &draw_config,
+                    parameter_panel.as_ref(),
+                    &model_status,
+                ) {
+                    Ok(()) => {}
+                    Err(err) => {
+                        panic!("Draw error: {}", err);
                     }
                 }
             }
@@ -249,81 +528,3 @@ fn main() -> anyhow::Result<()> {
         }
     });
 }
-
-struct ShapeProcessor {
-    tolerance: Option<Scalar>,
-}
-
-impl ShapeProcessor {
-    fn new(tolerance: Option<f64>) -> anyhow::Result<Self> {
-        if let Some(tolerance) = tolerance {
-            if tolerance <= 0. {
-                anyhow::bail!(
-                    "Invalid user defined model deviation tolerance: {}.\n\
-                    Tolerance must be larger than zero",
-                    tolerance
-                );
-            }
-        }
-
-        let tolerance = tolerance.map(Scalar::from_f64);
-
-        Ok(Self { tolerance })
-    }
-
-    fn process(&self, shape: &fj::Shape) -> ProcessedShape {
-        let aabb = shape.bounding_volume();
-
-        let tolerance = match self.tolerance {
-            None => {
-                // Compute a reasonable default for the tolerance value. To do
-                // this, we just look at the smallest non-zero extent of the
-                // bounding box and divide that by some value.
-                let mut min_extent = Scalar::MAX;
-                for extent in aabb.size().components {
-                    if extent > Scalar::ZERO && extent < min_extent {
-                        min_extent = extent;
-                    }
-                }
-
-                // `tolerance` must not be zero, or we'll run into trouble.
-                let tolerance = min_extent / Scalar::from_f64(1000.);
-                assert!(tolerance > Scalar::ZERO);
-
-                tolerance
-            }
-            Some(user_defined_tolerance) => user_defined_tolerance,
-        };
-
-        let mut debug_info = DebugInfo::new();
-        let mut triangles = Vec::new();
-        triangulate(
-            shape.to_shape(tolerance, &mut debug_info),
-            tolerance,
-            &mut triangles,
-            &mut debug_info,
-        );
-
-        ProcessedShape {
-            aabb,
-            triangles,
-            debug_info,
-        }
-    }
-}
-
-struct ProcessedShape {
-    aabb: Aabb<3>,
-    triangles: Vec<Triangle<3>>,
-    debug_info: DebugInfo,
-}
-
-impl ProcessedShape {
-    fn update_geometry(&self, renderer: &mut Renderer) {
-        renderer.update_geometry(
-            (&self.triangles).into(),
-            (&self.debug_info).into(),
-            self.aabb,
-        );
-    }
-}
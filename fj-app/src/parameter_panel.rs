@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use fj::{ParameterKind, Parameters};
+use winit::event::{ElementState, KeyboardInput, VirtualKeyCode};
+
+use crate::parameters;
+
+/// On-screen panel that lets the user edit the parameters of a model
+///
+/// The panel is controlled with the keyboard: Up/Down select a parameter,
+/// Left/Right decrease/increase its value in steps, Enter starts editing the
+/// value as text (and applies it, if pressed again), Escape cancels editing.
+#[derive(Debug)]
+pub struct ParameterPanel {
+    schema: Parameters,
+    values: HashMap<String, String>,
+    selected: usize,
+    editing: Option<String>,
+    error: Option<String>,
+}
+
+impl ParameterPanel {
+    /// Create a panel for the parameters the model declares
+    ///
+    /// `arguments` are the parameters passed on the command line. Parameters
+    /// that weren't passed start out with their default value.
+    pub fn new(
+        schema: Parameters,
+        arguments: &HashMap<String, String>,
+    ) -> Self {
+        let values = schema
+            .iter()
+            .map(|parameter| {
+                let value = arguments
+                    .get(parameter.name())
+                    .cloned()
+                    .unwrap_or_else(|| parameter.default().to_string());
+                (parameter.name().to_owned(), value)
+            })
+            .collect();
+
+        Self {
+            schema,
+            values,
+            selected: 0,
+            editing: None,
+            error: None,
+        }
+    }
+
+    /// Access the current values of all parameters
+    pub fn values(&self) -> &HashMap<String, String> {
+        &self.values
+    }
+
+    /// Iterate over the parameters, with their current value
+    ///
+    /// Yields the name, the value as it should be displayed, and whether the
+    /// parameter is selected.
+    pub fn lines(&self) -> impl Iterator<Item = (&str, &str, bool)> {
+        self.schema.iter().enumerate().map(|(i, parameter)| {
+            let selected = i == self.selected;
+            let value = match &self.editing {
+                Some(buffer) if selected => buffer.as_str(),
+                _ => self.values[parameter.name()].as_str(),
+            };
+
+            (parameter.name(), value, selected)
+        })
+    }
+
+    /// Indicate whether the value of the selected parameter is being edited
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Access the error that resulted from the last edit, if any
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Handle keyboard input
+    ///
+    /// Returns `None`, if the panel didn't handle the input, and it should be
+    /// passed on to the regular input handler. Returns `Some(true)`, if the
+    /// input changed a parameter value.
+    pub fn handle_keyboard_input(
+        &mut self,
+        input: KeyboardInput,
+    ) -> Option<bool> {
+        let key = match input {
+            KeyboardInput {
+                state: ElementState::Pressed,
+                virtual_keycode: Some(key),
+                ..
+            } => key,
+            // While editing, the panel has exclusive use of the keyboard.
+            _ if self.is_editing() => return Some(false),
+            _ => return None,
+        };
+
+        if self.editing.is_some() {
+            return Some(self.handle_editing_key(key));
+        }
+
+        match key {
+            VirtualKeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                Some(false)
+            }
+            VirtualKeyCode::Down => {
+                let num_parameters = self.schema.iter().count();
+                self.selected =
+                    (self.selected + 1).min(num_parameters.saturating_sub(1));
+                Some(false)
+            }
+            VirtualKeyCode::Left => Some(self.step(-1.)),
+            VirtualKeyCode::Right => Some(self.step(1.)),
+            VirtualKeyCode::Return => {
+                self.editing = Some(String::new());
+                self.error = None;
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+
+    /// Handle a character that was typed
+    ///
+    /// Only has an effect while a value is being edited.
+    pub fn handle_character(&mut self, c: char) {
+        if let Some(buffer) = &mut self.editing {
+            if c.is_ascii_digit() || matches!(c, '.' | '-' | 'e' | 'E') {
+                buffer.push(c);
+            }
+        }
+    }
+
+    fn handle_editing_key(&mut self, key: VirtualKeyCode) -> bool {
+        match key {
+            VirtualKeyCode::Back => {
+                if let Some(buffer) = &mut self.editing {
+                    buffer.pop();
+                }
+                false
+            }
+            VirtualKeyCode::Escape => {
+                self.editing = None;
+                false
+            }
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                let value = self.editing.take().unwrap_or_default();
+                self.set(value)
+            }
+            _ => false,
+        }
+    }
+
+    /// Increase or decrease the selected value by one step
+    fn step(&mut self, direction: f64) -> bool {
+        let parameter = match self.schema.iter().nth(self.selected) {
+            Some(parameter) => parameter,
+            None => return false,
+        };
+        let current = &self.values[parameter.name()];
+
+        let value = match parameter.kind() {
+            ParameterKind::Float => {
+                let current = current.parse::<f64>().unwrap_or(0.);
+
+                let default = parameter.default().to_f64().abs();
+                let step = if default == 0. { 0.1 } else { default / 10. };
+
+                clamp(
+                    current + direction * step,
+                    parameter.min(),
+                    parameter.max(),
+                )
+                .to_string()
+            }
+            ParameterKind::Integer => {
+                let current = current.parse::<i64>().unwrap_or(0);
+                let value = clamp(
+                    (current + direction as i64) as f64,
+                    parameter.min().map(f64::ceil),
+                    parameter.max().map(f64::floor),
+                );
+                (value as i64).to_string()
+            }
+        };
+
+        self.set(value)
+    }
+
+    /// Set the selected value, if it is valid
+    fn set(&mut self, value: String) -> bool {
+        let name = match self.schema.iter().nth(self.selected) {
+            Some(parameter) => parameter.name().to_owned(),
+            None => return false,
+        };
+
+        let arguments = HashMap::from([(name.clone(), value.clone())]);
+        if let Err(errors) = parameters::validate(&arguments, &self.schema) {
+            self.error = errors.first().map(ToString::to_string);
+            return false;
+        }
+
+        self.error = None;
+        let previous = self.values.insert(name, value.clone());
+        previous.as_ref() != Some(&value)
+    }
+}
+
+fn clamp(value: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+    let value = min.map_or(value, |min| value.max(min));
+    max.map_or(value, |max| value.min(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fj::{Parameter, Parameters};
+    use winit::event::VirtualKeyCode;
+
+    use super::ParameterPanel;
+
+    #[test]
+    fn step() {
+        let mut panel = panel();
+
+        // The step size for floats is derived from the default value.
+        assert!(panel.step(1.));
+        assert_eq!(panel.values()["radius"], "1.1");
+
+        // Values are clamped to the range of the parameter.
+        panel.selected = 1;
+        assert!(panel.step(1.));
+        assert_eq!(panel.values()["points"], "5");
+        assert!(!panel.step(1.));
+        assert_eq!(panel.values()["points"], "5");
+    }
+
+    #[test]
+    fn edit() {
+        let mut panel = panel();
+
+        panel.editing = Some(String::new());
+        for c in "2.5x".chars() {
+            panel.handle_character(c);
+        }
+        assert!(panel.handle_editing_key(VirtualKeyCode::Return));
+        assert_eq!(panel.values()["radius"], "2.5");
+        assert!(!panel.is_editing());
+
+        // Invalid values are rejected, and the previous value is kept.
+        panel.editing = Some("-1".into());
+        assert!(!panel.handle_editing_key(VirtualKeyCode::Return));
+        assert_eq!(panel.values()["radius"], "2.5");
+        assert!(panel.error().is_some());
+    }
+
+    fn panel() -> ParameterPanel {
+        let mut schema = Parameters::new();
+        schema
+            .add(Parameter::float("radius", 1.).with_min(0.))
+            .add(Parameter::integer("points", 4).with_min(3.).with_max(5.));
+
+        let arguments = HashMap::from([("points".into(), "4".into())]);
+        ParameterPanel::new(schema, &arguments)
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use fj::{ParameterKind, Parameters};
+use thiserror::Error;
+
+/// Parse model parameters from the command line
+///
+/// Each parameter is expected in the form `key=value`.
+pub fn parse(
+    parameters: Vec<String>,
+) -> Result<HashMap<String, String>, ParameterError> {
+    let mut parsed = HashMap::new();
+
+    for parameter in parameters {
+        match parameter.split_once('=') {
+            Some((key, value)) => {
+                parsed.insert(key.to_owned(), value.to_owned());
+            }
+            None => return Err(ParameterError::Syntax(parameter)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Validate parameters against the parameters that a model declares
+///
+/// Returns all problems that were found, not just the first one.
+pub fn validate(
+    arguments: &HashMap<String, String>,
+    parameters: &Parameters,
+) -> Result<(), Vec<ParameterError>> {
+    let mut errors = Vec::new();
+
+    // Sort the names, so the errors are reported in a consistent order.
+    let mut names: Vec<_> = arguments.keys().collect();
+    names.sort();
+
+    for name in names {
+        let value = &arguments[name];
+
+        let parameter = match parameters.get(name) {
+            Some(parameter) => parameter,
+            None => {
+                errors.push(ParameterError::Unknown {
+                    name: name.clone(),
+                    known: parameters
+                        .iter()
+                        .map(|parameter| parameter.name().to_owned())
+                        .collect(),
+                });
+                continue;
+            }
+        };
+
+        let number = match parameter.parse(value) {
+            Some(number) => number.to_f64(),
+            None => {
+                errors.push(ParameterError::Type {
+                    name: name.clone(),
+                    value: value.clone(),
+                    kind: parameter.kind(),
+                });
+                continue;
+            }
+        };
+
+        let below_min = parameter.min().map_or(false, |min| number < min);
+        let above_max = parameter.max().map_or(false, |max| number > max);
+        if below_min || above_max {
+            errors.push(ParameterError::Range {
+                name: name.clone(),
+                value: value.clone(),
+                range: range(parameter.min(), parameter.max()),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Print the parameters that a model declares
+pub fn print_help(parameters: &Parameters) {
+    if parameters.is_empty() {
+        println!("The model has no parameters.");
+        return;
+    }
+
+    println!("Parameters:");
+    for parameter in parameters.iter() {
+        println!();
+        println!(
+            "  {} ({}, default: {})",
+            parameter.name(),
+            parameter.kind(),
+            parameter.default()
+        );
+
+        if parameter.min().is_some() || parameter.max().is_some() {
+            println!(
+                "      Range: {}",
+                range(parameter.min(), parameter.max())
+            );
+        }
+        if let Some(description) = parameter.description() {
+            println!("      {}", description);
+        }
+    }
+}
+
+fn range(min: Option<f64>, max: Option<f64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{} to {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => "any value".to_owned(),
+    }
+}
+
+/// A problem with a model parameter passed on the command line
+#[derive(Debug, Error, PartialEq)]
+pub enum ParameterError {
+    /// The parameter is not in the form `key=value`
+    #[error("Invalid parameter `{0}`: expected `key=value`")]
+    Syntax(String),
+
+    /// The model doesn't declare a parameter with that name
+    #[error(
+        "Unknown parameter `{name}`; the model accepts: {}",
+        .known.join(", ")
+    )]
+    Unknown { name: String, known: Vec<String> },
+
+    /// The value can't be parsed as the declared type
+    #[error("Invalid value `{value}` for parameter `{name}`: expected {kind}")]
+    Type {
+        name: String,
+        value: String,
+        kind: ParameterKind,
+    },
+
+    /// The value is outside of the declared range
+    #[error("Value `{value}` for parameter `{name}` out of range: {range}")]
+    Range {
+        name: String,
+        value: String,
+        range: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fj::{Parameter, ParameterKind, Parameters};
+
+    use super::ParameterError;
+
+    #[test]
+    fn parse() {
+        let parameters =
+            super::parse(vec!["a=1".into(), "b=x=y".into()]).unwrap();
+        assert_eq!(parameters["a"], "1");
+        assert_eq!(parameters["b"], "x=y");
+
+        assert_eq!(
+            super::parse(vec!["a".into()]),
+            Err(ParameterError::Syntax("a".into()))
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let mut parameters = Parameters::new();
+        parameters
+            .add(Parameter::float("radius", 1.).with_min(0.))
+            .add(Parameter::integer("points", 5).with_min(3.).with_max(10.));
+
+        let valid = arguments(&[("radius", "2.5"), ("points", "3")]);
+        assert_eq!(super::validate(&valid, &parameters), Ok(()));
+
+        let invalid =
+            arguments(&[("points", "3.5"), ("radius", "-1"), ("height", "1")]);
+        assert_eq!(
+            super::validate(&invalid, &parameters),
+            Err(vec![
+                ParameterError::Unknown {
+                    name: "height".into(),
+                    known: vec!["radius".into(), "points".into()],
+                },
+                ParameterError::Type {
+                    name: "points".into(),
+                    value: "3.5".into(),
+                    kind: ParameterKind::Integer,
+                },
+                ParameterError::Range {
+                    name: "radius".into(),
+                    value: "-1".into(),
+                    range: "at least 0".into(),
+                },
+            ])
+        );
+
+        // Values that aren't finite would slip through the range check.
+        for value in ["NaN", "inf", "-inf"] {
+            let invalid = arguments(&[("radius", value)]);
+            assert_eq!(
+                super::validate(&invalid, &parameters),
+                Err(vec![ParameterError::Type {
+                    name: "radius".into(),
+                    value: value.into(),
+                    kind: ParameterKind::Float,
+                }])
+            );
+        }
+    }
+
+    fn arguments(arguments: &[(&str, &str)]) -> HashMap<String, String> {
+        arguments
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
@@ -0,0 +1,116 @@
+use std::{fs, path::Path};
+
+use anyhow::Context as _;
+
+use crate::export::Format;
+
+/// The encodings that a serialized shape file can use
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Json,
+    Ron,
+}
+
+impl Encoding {
+    /// Determine the encoding from the extension of a path
+    ///
+    /// Returns `None`, if the path is not a serialized shape file.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Format::from_path(path)?.shape_file_encoding()
+    }
+}
+
+/// Load a shape from a serialized shape file
+///
+/// Shape files contain the CSG tree that a model returns (an [`fj::Shape`]),
+/// so they can be viewed without compiling the model.
+pub fn load(path: &Path) -> anyhow::Result<fj::Shape> {
+    let encoding = Encoding::from_path(path).with_context(|| {
+        format!("Not a serialized shape file: {}", path.display())
+    })?;
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Error reading {}", path.display()))?;
+
+    deserialize(&contents, encoding)
+        .with_context(|| format!("Error loading shape from {}", path.display()))
+}
+
+/// Save a shape to a serialized shape file
+pub fn save(
+    shape: &fj::Shape,
+    path: &Path,
+    encoding: Encoding,
+) -> anyhow::Result<()> {
+    fs::write(path, serialize(shape, encoding)?)?;
+    Ok(())
+}
+
+fn serialize(shape: &fj::Shape, encoding: Encoding) -> anyhow::Result<String> {
+    let serialized = match encoding {
+        Encoding::Json => serde_json::to_string_pretty(shape)?,
+        Encoding::Ron => {
+            ron::ser::to_string_pretty(shape, ron::ser::PrettyConfig::new())?
+        }
+    };
+
+    Ok(serialized)
+}
+
+fn deserialize(
+    serialized: &str,
+    encoding: Encoding,
+) -> anyhow::Result<fj::Shape> {
+    let shape = match encoding {
+        Encoding::Json => serde_json::from_str(serialized)?,
+        Encoding::Ron => ron::from_str(serialized)?,
+    };
+
+    Ok(shape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    #[test]
+    fn round_trip() {
+        let sketch =
+            fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]])
+                .with_color([0, 255, 0, 255]);
+        let circle = fj::Circle::from_radius(0.25);
+        let difference =
+            fj::Difference2d::from_objects(sketch.into(), circle.into());
+        let shape: fj::Shape =
+            fj::Sweep::from_shape_and_length(difference.into(), 2.).into();
+
+        for encoding in [Encoding::Json, Encoding::Ron] {
+            let serialized = super::serialize(&shape, encoding).unwrap();
+            let shape = super::deserialize(&serialized, encoding).unwrap();
+
+            let sweep = match shape {
+                fj::Shape::Shape3d(fj::Shape3d::Sweep(sweep)) => sweep,
+                shape => panic!("Unexpected shape: {:?}", shape),
+            };
+            assert_eq!(
+                sweep.path().to_segments(),
+                [fj::PathSegment::Line([0., 0., 2.])]
+            );
+
+            let difference = match sweep.shape() {
+                fj::Shape2d::Difference(difference) => difference,
+                shape => panic!("Unexpected shape: {:?}", shape),
+            };
+            match (difference.a(), difference.b()) {
+                (fj::Shape2d::Sketch(sketch), fj::Shape2d::Circle(circle)) => {
+                    assert_eq!(
+                        sketch.to_points(),
+                        [[0., 0.], [1., 0.], [0., 1.]]
+                    );
+                    assert_eq!(sketch.color(), [0, 255, 0, 255]);
+                    assert_eq!(circle.radius(), 0.25);
+                }
+                shapes => panic!("Unexpected shapes: {:?}", shapes),
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use fj_debug::DebugInfo;
+use fj_kernel::{algorithms::triangulate, shape::Shape};
+use fj_math::{Aabb, Point, Scalar, Transform, Triangle, Vector};
+use fj_operations::ToShape as _;
+use parry3d_f64::query::Ray;
+
+use crate::graphics::{Renderer, Vertices};
+
+#[derive(Clone)]
+pub struct ShapeProcessor {
+    tolerance: Option<Scalar>,
+}
+
+impl ShapeProcessor {
+    pub fn new(tolerance: Option<f64>) -> anyhow::Result<Self> {
+        if let Some(tolerance) = tolerance {
+            if tolerance <= 0. {
+                anyhow::bail!(
+                    "Invalid user defined model deviation tolerance: {}.\n\
+                    Tolerance must be larger than zero",
+                    tolerance
+                );
+            }
+        }
+
+        let tolerance = tolerance.map(Scalar::from_f64);
+
+        Ok(Self { tolerance })
+    }
+
+    pub fn process(
+        &self,
+        shape: &fj::Shape,
+    ) -> Result<ProcessedShape, fj_operations::Error> {
+        let aabb = shape.bounding_volume();
+
+        let tolerance = match self.tolerance {
+            None => {
+                // Compute a reasonable default for the tolerance value. To do
+                // this, we just look at the smallest non-zero extent of the
+                // bounding box and divide that by some value.
+                let mut min_extent = Scalar::MAX;
+                for extent in aabb.size().components {
+                    if extent > Scalar::ZERO && extent < min_extent {
+                        min_extent = extent;
+                    }
+                }
+
+                // `tolerance` must not be zero, or we'll run into trouble.
+                let tolerance = min_extent / Scalar::from_f64(1000.);
+                assert!(tolerance > Scalar::ZERO);
+
+                tolerance
+            }
+            Some(user_defined_tolerance) => user_defined_tolerance,
+        };
+
+        let mut debug_info = DebugInfo::new();
+        let brep = shape.to_shape(tolerance, &mut debug_info)?;
+
+        let mut triangles = Vec::new();
+        triangulate(brep.clone(), tolerance, &mut triangles, &mut debug_info);
+
+        Ok(ProcessedShape {
+            aabb,
+            tolerance,
+            brep,
+            triangles,
+            debug_info,
+        })
+    }
+}
+
+pub struct ProcessedShape {
+    pub aabb: Aabb<3>,
+    pub tolerance: Scalar,
+    pub brep: Shape,
+    pub triangles: Vec<Triangle<3>>,
+    pub debug_info: DebugInfo,
+}
+
+impl ProcessedShape {
+    /// Move the shape to where it is placed in an assembly
+    pub fn transform(&mut self, transform: &Transform) {
+        self.brep.geometry().transform(transform);
+
+        for triangle in &mut self.triangles {
+            *triangle = transform.transform_triangle(triangle);
+        }
+
+        // The bounding box needs to stay axis-aligned, so we can't just
+        // transform its corners.
+        self.aabb = Aabb::<3>::from_points(
+            self.aabb
+                .vertices()
+                .iter()
+                .map(|vertex| transform.transform_point(vertex)),
+        );
+
+        for check in &mut self.debug_info.triangle_edge_checks {
+            check.ray = Ray::new(
+                transform
+                    .transform_point(&Point::from(check.ray.origin))
+                    .to_na(),
+                transform
+                    .transform_vector(&Vector::from(check.ray.dir))
+                    .to_na(),
+            );
+        }
+    }
+}
+
+/// The shapes of all models that are being viewed, combined
+pub struct Scene {
+    pub aabb: Aabb<3>,
+    pub triangles: Vec<Triangle<3>>,
+}
+
+impl Scene {
+    /// Combine the shapes, and update the renderer's geometry to match
+    ///
+    /// Returns `None`, if there are no shapes.
+    pub fn update<'r>(
+        shapes: impl IntoIterator<Item = &'r ProcessedShape>,
+        renderer: &mut Renderer,
+    ) -> Option<Self> {
+        let mut scene: Option<Self> = None;
+        let mut lines = Vertices::empty();
+
+        for shape in shapes {
+            let scene = scene.get_or_insert_with(|| Self {
+                aabb: shape.aabb,
+                triangles: Vec::new(),
+            });
+
+            scene.aabb = scene.aabb.merged(&shape.aabb);
+            scene.triangles.extend(&shape.triangles);
+            lines.append((&shape.debug_info).into());
+        }
+
+        if let Some(scene) = &scene {
+            renderer.update_geometry(
+                (&scene.triangles).into(),
+                lines,
+                scene.aabb,
+            );
+        }
+
+        scene
+    }
+}
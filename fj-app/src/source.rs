@@ -0,0 +1,40 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use fj_host::Model;
+
+use crate::shape_file;
+
+/// Where a shape comes from
+pub enum Source {
+    /// A model, which needs to be compiled and evaluated
+    Model(Model),
+
+    /// A serialized shape file, which can be loaded directly
+    ShapeFile(PathBuf),
+}
+
+impl Source {
+    /// Load the shape once
+    ///
+    /// The arguments are passed to the model. Shape files don't have
+    /// parameters, so they are ignored in that case.
+    pub fn load_once(
+        &self,
+        arguments: &HashMap<String, String>,
+    ) -> anyhow::Result<fj::Shape> {
+        match self {
+            Self::Model(model) => Ok(model.load_once(arguments)?),
+            Self::ShapeFile(path) => shape_file::load(path),
+        }
+    }
+
+    /// Query the parameters that the shape accepts
+    ///
+    /// Returns `None`, if the parameters are not declared.
+    pub fn parameters(&self) -> anyhow::Result<Option<fj::Parameters>> {
+        match self {
+            Self::Model(model) => Ok(model.parameters()?),
+            Self::ShapeFile(_) => Ok(None),
+        }
+    }
+}
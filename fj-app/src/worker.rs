@@ -0,0 +1,238 @@
+use std::{collections::HashMap, sync::mpsc, thread, time::Duration};
+
+use fj_host::Watcher;
+use fj_math::Transform;
+
+use crate::{
+    shape_file,
+    shape_processor::{ProcessedShape, ShapeProcessor},
+    source::Source,
+};
+
+/// How often the worker checks for changes, while there's nothing to do
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Loads and processes shapes on a background thread
+///
+/// Compiling and evaluating a model, and processing the resulting shape, can
+/// take a long time. Doing this on a separate thread keeps the window
+/// responsive in the meantime.
+pub struct Worker {
+    parameters: mpsc::Sender<HashMap<String, String>>,
+    updates: mpsc::Receiver<Update>,
+}
+
+impl Worker {
+    /// Start the worker
+    ///
+    /// Models are watched, and reloaded whenever they change. Shape files are
+    /// only loaded once.
+    ///
+    /// If a placement is provided, the shapes are transformed accordingly.
+    pub fn start(
+        source: Source,
+        parameters: HashMap<String, String>,
+        placement: Option<Transform>,
+        shape_processor: ShapeProcessor,
+    ) -> anyhow::Result<Self> {
+        let processor = Processor {
+            shape_processor,
+            placement,
+        };
+
+        let (parameters_tx, parameters_rx) = mpsc::channel();
+        let (updates_tx, updates_rx) = mpsc::channel();
+
+        match source {
+            Source::Model(model) => {
+                let watcher = model.load_and_watch(parameters)?;
+
+                thread::spawn(move || {
+                    watch(watcher, processor, parameters_rx, updates_tx)
+                });
+            }
+            Source::ShapeFile(path) => {
+                // Loading a shape file is fast, and any error is better
+                // reported right away.
+                let shape = shape_file::load(&path)?;
+
+                thread::spawn(move || {
+                    let update = processor.process(&shape);
+
+                    // If this fails, the application is shutting down.
+                    let _ = updates_tx.send(update);
+                });
+            }
+        }
+
+        Ok(Self {
+            parameters: parameters_tx,
+            updates: updates_rx,
+        })
+    }
+
+    /// Change the parameters that are passed to the model
+    pub fn set_parameters(&self, parameters: HashMap<String, String>) {
+        // If this fails, the worker thread has stopped, either because it
+        // panicked, or because it had nothing more to do. Nothing we can do
+        // about either here.
+        let _ = self.parameters.send(parameters);
+    }
+
+    /// Receive the next update from the worker, if one is available
+    ///
+    /// Doesn't block.
+    pub fn receive(&self) -> Option<Update> {
+        self.updates.try_recv().ok()
+    }
+}
+
+/// The state of the model, as far as the user interface is concerned
+#[derive(Debug, Default)]
+pub struct ModelStatus {
+    /// The error that occurred while last reloading the model, if any
+    ///
+    /// While there is one, it's shown on top of the last shape that loaded
+    /// fine.
+    pub error: Option<String>,
+
+    /// Whether the model is currently being rebuilt
+    pub rebuilding: bool,
+}
+
+impl ModelStatus {
+    /// Combine the status of multiple models into one
+    pub fn combine<'r>(
+        statuses: impl IntoIterator<Item = &'r ModelStatus>,
+    ) -> Self {
+        let mut combined = Self::default();
+        let mut errors = Vec::new();
+
+        for status in statuses {
+            combined.rebuilding |= status.rebuilding;
+            errors.extend(status.error.as_deref());
+        }
+
+        if !errors.is_empty() {
+            combined.error = Some(errors.join("\n\n"));
+        }
+
+        combined
+    }
+}
+
+/// An update from the worker
+pub enum Update {
+    /// The model has changed, and is being rebuilt
+    Rebuilding,
+
+    /// A new shape has been loaded and processed
+    Shape(ProcessedShape),
+
+    /// An error occurred while reloading the model, or processing its shape
+    Error(String),
+}
+
+/// Processes shapes and moves them into place
+struct Processor {
+    shape_processor: ShapeProcessor,
+    placement: Option<Transform>,
+}
+
+impl Processor {
+    fn process(&self, shape: &fj::Shape) -> Update {
+        let mut shape = match self.shape_processor.process(shape) {
+            Ok(shape) => shape,
+            Err(err) => return Update::Error(describe_error_chain(&err)),
+        };
+
+        if let Some(placement) = &self.placement {
+            shape.transform(placement);
+        }
+
+        Update::Shape(shape)
+    }
+}
+
+fn watch(
+    mut watcher: Watcher,
+    processor: Processor,
+    parameters: mpsc::Receiver<HashMap<String, String>>,
+    updates: mpsc::Sender<Update>,
+) {
+    loop {
+        // Only the latest parameters are relevant. Skip any others that have
+        // piled up while we were busy.
+        let mut latest = None;
+        loop {
+            match parameters.try_recv() {
+                Ok(parameters) => latest = Some(parameters),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => return,
+            }
+        }
+        if let Some(parameters) = latest {
+            watcher.set_parameters(parameters);
+        }
+
+        if !watcher.has_changed() {
+            match parameters.recv_timeout(POLL_INTERVAL) {
+                Ok(parameters) => watcher.set_parameters(parameters),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+
+            continue;
+        }
+
+        // If sending fails, the application is shutting down.
+        if updates.send(Update::Rebuilding).is_err() {
+            return;
+        }
+
+        let update = match watcher.receive() {
+            Some(Ok(shape)) => processor.process(&shape),
+            Some(Err(err)) => Update::Error(describe_error(&err)),
+            None => continue,
+        };
+
+        if updates.send(update).is_err() {
+            return;
+        }
+    }
+}
+
+/// Describe an error that occurred while reloading the model
+///
+/// Compiler errors are listed one by one, with their location. Warnings are
+/// left out, as they'd only distract from the errors.
+fn describe_error(err: &fj_host::Error) -> String {
+    match err {
+        fj_host::Error::Compile { diagnostics } => {
+            let mut description = String::from("Error compiling model");
+
+            for diagnostic in diagnostics {
+                if diagnostic.is_error() {
+                    description.push_str("\n\n");
+                    description.push_str(&diagnostic.to_string());
+                }
+            }
+
+            description
+        }
+        err => describe_error_chain(err),
+    }
+}
+
+/// Describe an error, followed by the errors that caused it
+fn describe_error_chain(err: &dyn std::error::Error) -> String {
+    let mut description = err.to_string();
+
+    let mut source = err.source();
+    while let Some(err) = source {
+        description.push_str(&format!(": {}", err));
+        source = err.source();
+    }
+
+    description
+}
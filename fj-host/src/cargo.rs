@@ -0,0 +1,240 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::Error;
+
+/// The information about a model that Cargo provides
+#[derive(Debug)]
+pub struct Metadata {
+    /// The name of the model's library, as it appears in file names
+    pub lib_name: String,
+
+    /// The directory that Cargo compiles the model into
+    pub target_dir: PathBuf,
+
+    /// The directory that contains the source code of the model's library
+    pub src_dir: PathBuf,
+
+    /// The directories of the model's path dependencies
+    pub path_dependencies: Vec<PathBuf>,
+}
+
+impl Metadata {
+    /// Query the metadata of the package with the given manifest
+    ///
+    /// Runs `cargo metadata`, which takes workspaces, `[lib] name`, and the
+    /// target directory configuration into account.
+    pub fn query(manifest_path: &Path) -> Result<Self, Error> {
+        let output = Command::new("cargo")
+            .arg("metadata")
+            .args(["--format-version", "1", "--no-deps"])
+            .arg("--manifest-path")
+            .arg(manifest_path)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::Metadata(
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+
+        let metadata: serde_json::Value =
+            serde_json::from_slice(&output.stdout)
+                .map_err(|err| Error::Metadata(err.to_string()))?;
+
+        Self::from_json(&metadata, manifest_path)
+    }
+
+    fn from_json(
+        metadata: &serde_json::Value,
+        manifest_path: &Path,
+    ) -> Result<Self, Error> {
+        // A workspace can contain many packages. Only the one the manifest
+        // belongs to is relevant.
+        let package = metadata["packages"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|package| {
+                package["manifest_path"]
+                    .as_str()
+                    .map_or(false, |path| same_path(path, manifest_path))
+            })
+            .ok_or_else(|| {
+                Error::Metadata(format!(
+                    "No package found for manifest `{}`",
+                    manifest_path.display()
+                ))
+            })?;
+
+        let lib = package["targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|target| is_cdylib(target))
+            .ok_or_else(|| {
+                Error::Metadata(format!(
+                    "Package `{}` has no library with crate type `cdylib`",
+                    package["name"].as_str().unwrap_or_default()
+                ))
+            })?;
+
+        // Cargo does the same, when naming the library file.
+        let lib_name =
+            lib["name"].as_str().unwrap_or_default().replace('-', "_");
+
+        let src_dir = lib["src_path"]
+            .as_str()
+            .and_then(|src_path| Path::new(src_path).parent())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| {
+                Error::Metadata("Library source path missing".to_owned())
+            })?;
+
+        let path_dependencies = package["dependencies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|dependency| dependency["path"].as_str())
+            .map(PathBuf::from)
+            .collect();
+
+        let target_dir = metadata["target_directory"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                Error::Metadata("Target directory missing".to_owned())
+            })?;
+
+        Ok(Self {
+            lib_name,
+            target_dir,
+            src_dir,
+            path_dependencies,
+        })
+    }
+}
+
+/// Find the library that Cargo built for a package
+///
+/// `output` is the output of `cargo build --message-format=json`. Looks for
+/// the `compiler-artifact` message of the `cdylib` target of the package with
+/// the given manifest, and returns the file with the given extension from it.
+pub fn find_artifact(
+    output: &str,
+    manifest_path: &Path,
+    extension: &str,
+) -> Option<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|message: &serde_json::Value| {
+            message["reason"] == "compiler-artifact"
+                && is_cdylib(&message["target"])
+                && message["manifest_path"]
+                    .as_str()
+                    .map_or(false, |path| same_path(path, manifest_path))
+        })
+        .flat_map(|message| {
+            message["filenames"].as_array().cloned().unwrap_or_default()
+        })
+        .filter_map(|file| file.as_str().map(PathBuf::from))
+        .find(|file| file.extension().map_or(false, |ext| ext == extension))
+}
+
+fn is_cdylib(target: &serde_json::Value) -> bool {
+    target["crate_types"]
+        .as_array()
+        .map_or(false, |types| types.iter().any(|ty| ty == "cdylib"))
+}
+
+fn same_path(a: impl AsRef<Path>, b: impl AsRef<Path>) -> bool {
+    let (a, b) = (a.as_ref(), b.as_ref());
+
+    // Cargo's paths might not be canonical, and neither might ours.
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use serde_json::json;
+
+    use super::Metadata;
+
+    #[test]
+    fn metadata() {
+        let metadata = json!({
+            "packages": [
+                {
+                    "name": "other",
+                    "manifest_path": "/workspace/other/Cargo.toml",
+                    "targets": [{ "name": "other", "crate_types": ["lib"] }],
+                },
+                {
+                    "name": "my-model",
+                    "manifest_path": "/workspace/model/Cargo.toml",
+                    "targets": [{
+                        "name": "my-model",
+                        "crate_types": ["cdylib"],
+                        "src_path": "/workspace/model/src/lib.rs",
+                    }],
+                    "dependencies": [
+                        { "name": "fj", "path": "/workspace/fj" },
+                        { "name": "serde" },
+                    ],
+                },
+            ],
+            "target_directory": "/workspace/target",
+        });
+
+        let metadata = Metadata::from_json(
+            &metadata,
+            Path::new("/workspace/model/Cargo.toml"),
+        )
+        .unwrap();
+        assert_eq!(metadata.lib_name, "my_model");
+        assert_eq!(metadata.target_dir, PathBuf::from("/workspace/target"));
+        assert_eq!(metadata.src_dir, PathBuf::from("/workspace/model/src"));
+        assert_eq!(
+            metadata.path_dependencies,
+            [PathBuf::from("/workspace/fj")]
+        );
+    }
+
+    #[test]
+    fn find_artifact() {
+        let artifact = |manifest_path: &str, crate_type: &str| {
+            json!({
+                "reason": "compiler-artifact",
+                "manifest_path": manifest_path,
+                "target": { "crate_types": [crate_type] },
+                "filenames": [
+                    format!("/target/debug/lib{}.so", crate_type),
+                    format!("/target/debug/lib{}.rlib", crate_type),
+                ],
+            })
+            .to_string()
+        };
+        let output = [
+            artifact("/fj/Cargo.toml", "lib"),
+            artifact("/model/Cargo.toml", "cdylib"),
+        ]
+        .join("\n");
+
+        assert_eq!(
+            super::find_artifact(&output, Path::new("/model/Cargo.toml"), "so"),
+            Some(PathBuf::from("/target/debug/libcdylib.so"))
+        );
+        assert_eq!(
+            super::find_artifact(&output, Path::new("/fj/Cargo.toml"), "so"),
+            None
+        );
+    }
+}
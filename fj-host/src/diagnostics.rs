@@ -0,0 +1,153 @@
+use std::fmt;
+
+/// A diagnostic message that was emitted while compiling a model
+///
+/// Extracted from the JSON messages that Cargo emits, if it is invoked with
+/// `--message-format=json`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The level of the diagnostic, for example "error" or "warning"
+    pub level: String,
+
+    /// The primary message of the diagnostic
+    pub message: String,
+
+    /// The location the diagnostic refers to, in the form `file:line:column`
+    pub location: Option<String>,
+
+    /// The diagnostic, as the compiler would print it to the terminal
+    pub rendered: Option<String>,
+}
+
+impl Diagnostic {
+    /// Parse the diagnostics from Cargo's JSON output
+    ///
+    /// Cargo emits one JSON message per line. Lines that aren't compiler
+    /// messages (or that aren't valid JSON) are ignored.
+    pub fn parse_cargo_output(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter_map(|message: serde_json::Value| {
+                if message["reason"] != "compiler-message" {
+                    return None;
+                }
+
+                Self::from_json(&message["message"])
+            })
+            .collect()
+    }
+
+    fn from_json(message: &serde_json::Value) -> Option<Self> {
+        let level = message["level"].as_str()?.to_owned();
+        let text = message["message"].as_str()?.to_owned();
+        let rendered = message["rendered"].as_str().map(str::to_owned);
+
+        let location = message["spans"]
+            .as_array()
+            .and_then(|spans| {
+                spans
+                    .iter()
+                    .find(|span| span["is_primary"] == true)
+                    .or_else(|| spans.first())
+            })
+            .and_then(|span| {
+                Some(format!(
+                    "{}:{}:{}",
+                    span["file_name"].as_str()?,
+                    span["line_start"].as_u64()?,
+                    span["column_start"].as_u64()?,
+                ))
+            });
+
+        Some(Self {
+            level,
+            message: text,
+            location,
+            rendered,
+        })
+    }
+
+    /// Indicate whether this diagnostic is an error
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.level, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, "\n  --> {}", location)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Diagnostic;
+
+    #[test]
+    fn parse_cargo_output() {
+        let error = json!({
+            "reason": "compiler-message",
+            "message": {
+                "level": "error",
+                "message": "cannot find value `x` in this scope",
+                "rendered": "error[E0425]: cannot find value `x`\n",
+                "spans": [{
+                    "file_name": "src/lib.rs",
+                    "line_start": 7,
+                    "column_start": 5,
+                    "is_primary": true,
+                }],
+            },
+        });
+        let aborting = json!({
+            "reason": "compiler-message",
+            "message": {
+                "level": "error",
+                "message": "aborting due to previous error",
+                "rendered": null,
+                "spans": [],
+            },
+        });
+        let output = [
+            json!({ "reason": "compiler-artifact" }).to_string(),
+            error.to_string(),
+            "not JSON".to_owned(),
+            aborting.to_string(),
+            json!({ "reason": "build-finished", "success": false }).to_string(),
+        ]
+        .join("\n");
+
+        let diagnostics = Diagnostic::parse_cargo_output(&output);
+        assert_eq!(
+            diagnostics,
+            [
+                Diagnostic {
+                    level: "error".into(),
+                    message: "cannot find value `x` in this scope".into(),
+                    location: Some("src/lib.rs:7:5".into()),
+                    rendered: Some(
+                        "error[E0425]: cannot find value `x`\n".into()
+                    ),
+                },
+                Diagnostic {
+                    level: "error".into(),
+                    message: "aborting due to previous error".into(),
+                    location: None,
+                    rendered: None,
+                },
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error: cannot find value `x` in this scope\n  --> src/lib.rs:7:5"
+        );
+    }
+}
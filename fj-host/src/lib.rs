@@ -5,70 +5,136 @@
 
 #![deny(missing_docs)]
 
+mod cargo;
+mod diagnostics;
 mod platform;
+mod process;
+mod watch;
+
+#[cfg(feature = "wasm")]
+mod wasm;
 
 use std::{
-    collections::{HashMap, HashSet},
-    ffi::OsStr,
-    io,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::CStr,
+    io::{self, Read as _},
+    os::raw::c_char,
     path::PathBuf,
-    process::Command,
+    process::{Command, Stdio},
     sync::mpsc,
     thread,
+    time::Duration,
 };
 
-use notify::Watcher as _;
 use thiserror::Error;
 
-use self::platform::HostPlatform;
+use self::{cargo::Metadata, platform::HostPlatform, watch::Package};
+
+pub use self::diagnostics::Diagnostic;
 
 /// Represents a Fornjot model
 pub struct Model {
-    src_path: PathBuf,
-    lib_path: PathBuf,
+    lib_name: String,
+    packages: Vec<Package>,
+    target_dir: PathBuf,
+    target_dir_override: bool,
     manifest_path: PathBuf,
+    backend: Backend,
+    build_options: BuildOptions,
+    debounce: Duration,
+
+    // The library that was produced, the last time the model was compiled.
+    artifact_path: RefCell<Option<PathBuf>>,
+
+    // Whether the library is up to date, because this instance compiled the
+    // model and no change has been detected since.
+    compiled: Cell<bool>,
 }
 
 impl Model {
     /// Initialize the model from a path
     ///
+    /// The name of the model's library and the target directory it is compiled
+    /// into are queried from Cargo. This works for models in a workspace, and
+    /// with a custom `[lib] name`.
+    ///
     /// Optionally, the target directory where plugin files are compiled to can
-    /// be provided. If it is not provided, the target directory is assumed to
-    /// be located within the model path.
+    /// be provided. If it is, it overrides the one that Cargo would choose.
     pub fn from_path(
         path: PathBuf,
         target_dir: Option<PathBuf>,
-    ) -> io::Result<Self> {
-        let name = {
-            // Can't panic. It only would, if the path ends with "..", and we
-            // are canonicalizing it here to prevent that.
-            let canonical = path.canonicalize()?;
-            let file_name = canonical.file_name().unwrap();
-
-            file_name.to_string_lossy().replace('-', "_")
-        };
-
-        let src_path = path.join("src");
+    ) -> Result<Self, Error> {
+        let manifest_path = path.join("Cargo.toml");
 
-        let lib_path = {
-            let file = HostPlatform::lib_file_name(&name);
-            let target_dir = target_dir.unwrap_or_else(|| path.join("target"));
-            target_dir.join("debug").join(file)
-        };
+        let metadata = Metadata::query(&manifest_path)?;
+
+        // The paths in watch events are absolute, and so are the ones Cargo
+        // gives us. The one we got passed might not be.
+        let dir = path.canonicalize()?;
+
+        // Changes to local dependencies change the model too, so we watch
+        // them along with it.
+        let mut packages = vec![Package {
+            dir,
+            src_dir: metadata.src_dir,
+        }];
+        packages.extend(metadata.path_dependencies.into_iter().map(|dir| {
+            Package {
+                src_dir: dir.join("src"),
+                dir,
+            }
+        }));
 
-        let manifest_path = path.join("Cargo.toml");
+        let target_dir_override = target_dir.is_some();
+        let target_dir = target_dir.unwrap_or(metadata.target_dir);
 
         Ok(Self {
-            src_path,
-            lib_path,
+            lib_name: metadata.lib_name,
+            packages,
+            target_dir,
+            target_dir_override,
             manifest_path,
+            backend: Backend::Native,
+            build_options: BuildOptions::default(),
+            debounce: Duration::from_millis(100),
+            artifact_path: RefCell::new(None),
+            compiled: Cell::new(false),
         })
     }
 
+    /// Select the backend that runs the model
+    ///
+    /// Uses [`Backend::Native`], unless specified otherwise.
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Set the options that are used when compiling the model
+    ///
+    /// Uses the default options, unless specified otherwise.
+    pub fn with_build_options(mut self, build_options: BuildOptions) -> Self {
+        self.build_options = build_options;
+        self
+    }
+
+    /// Set how long to wait for further changes, before reloading the model
+    ///
+    /// Saving a file often results in multiple changes in short succession.
+    /// [`Model::load_and_watch`] only reloads the model, once no change has
+    /// been detected for this long. Uses 100 milliseconds, unless specified
+    /// otherwise.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
     /// Load the model once
     ///
     /// The passed arguments are provided to the model. Returns the shape that
-    /// the model returns.
+    /// the model returns. The model is compiled first, unless this instance
+    /// has already compiled it (for example, to query its parameters).
     ///
     /// Please refer to [`Model::load_and_watch`], if you want to watch the
     /// model for changes, reloading it continually.
@@ -76,15 +142,25 @@ impl Model {
         &self,
         arguments: &HashMap<String, String>,
     ) -> Result<fj::Shape, Error> {
-        let manifest_path = self.manifest_path.display().to_string();
-
-        let status = Command::new("cargo")
-            .arg("build")
-            .args(["--manifest-path", &manifest_path])
-            .status()?;
+        self.compile_once()?;
+        self.evaluate(arguments)
+    }
 
-        if !status.success() {
-            return Err(Error::Compile);
+    /// Evaluate the model, without compiling it first
+    ///
+    /// This is much faster than [`Model::load_once`], and useful, if only the
+    /// arguments have changed since the model was last compiled. Fails, if the
+    /// model has never been compiled.
+    pub fn evaluate(
+        &self,
+        arguments: &HashMap<String, String>,
+    ) -> Result<fj::Shape, Error> {
+        match self.backend {
+            Backend::Native => {}
+            #[cfg(feature = "wasm")]
+            Backend::Wasm => {
+                return wasm::evaluate(&self.artifact_path(), arguments)
+            }
         }
 
         // So, strictly speaking this is all unsound:
@@ -103,18 +179,201 @@ impl Model {
         // I don't know of a way to fix this. We should take this as motivation
         // to switch to a better technique:
         // https://github.com/hannobraun/Fornjot/issues/71
+        //
+        // At least a panic in the model doesn't take the host down with it.
+        // See `catch_panic` for details.
         let shape = unsafe {
-            let lib = libloading::Library::new(&self.lib_path)?;
+            let lib = libloading::Library::new(&self.artifact_path())?;
+            check_version(&lib)?;
+
             let model: libloading::Symbol<ModelFn> = lib.get(b"model")?;
-            model(arguments)
+            catch_panic(&lib, || model(arguments))?
         };
 
         Ok(shape)
     }
 
+    /// Query the parameters that the model accepts
+    ///
+    /// Compiles the model, unless this instance has already compiled it, then
+    /// calls the `parameters` function it exports. Returns `None`, if the model
+    /// doesn't export such a function, meaning it doesn't declare its
+    /// parameters.
+    ///
+    /// Models that run in WebAssembly can't declare their parameters yet, so
+    /// this always returns `None` for them.
+    pub fn parameters(&self) -> Result<Option<fj::Parameters>, Error> {
+        self.compile_once()?;
+
+        if self.backend != Backend::Native {
+            return Ok(None);
+        }
+
+        // This is unsound, for the same reasons that loading the model is. See
+        // comment in `evaluate`.
+        let parameters = unsafe {
+            let lib = libloading::Library::new(&self.artifact_path())?;
+            check_version(&lib)?;
+
+            let parameters_fn: libloading::Symbol<ParametersFn> =
+                match lib.get(b"parameters") {
+                    Ok(parameters_fn) => parameters_fn,
+                    Err(_) => return Ok(None),
+                };
+
+            let mut parameters = fj::Parameters::new();
+            catch_panic(&lib, || parameters_fn(&mut parameters))?;
+            parameters
+        };
+
+        Ok(Some(parameters))
+    }
+
+    /// Compile the model, unless this instance has already compiled it
+    fn compile_once(&self) -> Result<(), Error> {
+        if !self.compiled.get() {
+            self.compile(|| false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compile the model
+    ///
+    /// `cancel` is checked periodically while Cargo is running. If it returns
+    /// `true`, Cargo is killed and the compilation is cancelled.
+    fn compile(
+        &self,
+        mut cancel: impl FnMut() -> bool,
+    ) -> Result<Compilation, Error> {
+        self.compiled.set(false);
+
+        let manifest_path = self.manifest_path.display().to_string();
+
+        // Cargo's progress output still goes to stderr, but the compiler's
+        // diagnostics are emitted as JSON on stdout, so we can pass them on to
+        // the caller.
+        let mut command = Command::new("cargo");
+        command
+            .arg("build")
+            .args(["--manifest-path", &manifest_path])
+            .arg("--message-format=json-diagnostic-rendered-ansi");
+
+        if self.target_dir_override {
+            command.arg("--target-dir").arg(&self.target_dir);
+        }
+        if let Some(profile) = &self.build_options.profile {
+            command.args(["--profile", profile]);
+        }
+        if !self.build_options.features.is_empty() {
+            command
+                .args(["--features", &self.build_options.features.join(",")]);
+        }
+        command.args(&self.build_options.cargo_args);
+
+        #[cfg(feature = "wasm")]
+        if self.backend == Backend::Wasm {
+            // The `wasm` feature of `fj` provides the glue code that the host
+            // calls into.
+            command.args(["--target", wasm::TARGET, "--features", "fj/wasm"]);
+        }
+
+        process::new_process_group(&mut command);
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+
+        // Cargo blocks, if its output isn't read. So we need to read it on
+        // another thread, while we wait for Cargo to finish here.
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = thread::spawn(move || {
+            let mut output = Vec::new();
+            stdout.read_to_end(&mut output).map(|_| output)
+        });
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if cancel() {
+                // Killing only Cargo would leave the compiler processes it
+                // started running. The process might have exited in the
+                // meantime, in which case this fails. Doesn't matter, as we're
+                // not interested in its result anymore.
+                let _ = process::kill_tree(&mut child);
+                child.wait()?;
+                return Ok(Compilation::Cancelled);
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        let output = reader.join().expect("Reader thread panicked")?;
+        let output_json = String::from_utf8_lossy(&output);
+        let diagnostics = Diagnostic::parse_cargo_output(&output_json);
+
+        // The diagnostics no longer end up in the terminal by themselves, but
+        // they are still useful there.
+        for diagnostic in &diagnostics {
+            if let Some(rendered) = &diagnostic.rendered {
+                eprint!("{}", rendered);
+            }
+        }
+
+        if !status.success() {
+            return Err(Error::Compile { diagnostics });
+        }
+
+        // Cargo tells us exactly where the library ended up. If it doesn't
+        // for some reason, we can still fall back to the expected path.
+        let expected_path = self.expected_artifact_path();
+        let extension = expected_path
+            .extension()
+            .map(|extension| extension.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        *self.artifact_path.borrow_mut() =
+            cargo::find_artifact(&output_json, &self.manifest_path, &extension);
+        self.compiled.set(true);
+
+        Ok(Compilation::Finished)
+    }
+
+    /// The path of the library that compiling the model produced
+    fn artifact_path(&self) -> PathBuf {
+        self.artifact_path
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| self.expected_artifact_path())
+    }
+
+    /// The path that compiling the model is expected to produce a library at
+    ///
+    /// Used, if the model hasn't been compiled by this instance, and Cargo
+    /// hasn't told us where the library is.
+    fn expected_artifact_path(&self) -> PathBuf {
+        let profile_dir = self.build_options.profile_dir();
+
+        match self.backend {
+            Backend::Native => self
+                .target_dir
+                .join(profile_dir)
+                .join(HostPlatform::lib_file_name(&self.lib_name)),
+            #[cfg(feature = "wasm")]
+            Backend::Wasm => self
+                .target_dir
+                .join(wasm::TARGET)
+                .join(profile_dir)
+                .join(format!("{}.wasm", self.lib_name)),
+        }
+    }
+
     /// Load the model, then watch it for changes
     ///
-    /// Whenever a change is detected, the model is being reloaded.
+    /// Whenever a change is detected, the model is being reloaded. Changes to
+    /// the model's source code and manifest are detected, as well as changes
+    /// to any of its local (path) dependencies. Files that are ignored by Git
+    /// don't trigger a reload.
     ///
     /// Consumes this instance of `Model` and returns a [`Watcher`], which can
     /// be queried for changes to the model.
@@ -123,115 +382,115 @@ impl Model {
         parameters: HashMap<String, String>,
     ) -> Result<Watcher, Error> {
         let (tx, rx) = mpsc::sync_channel(0);
-        let tx2 = tx.clone();
-
-        let watch_path = self.src_path.clone();
-
-        let mut watcher = notify::recommended_watcher(
-            move |event: notify::Result<notify::Event>| {
-                // Unfortunately the `notify` documentation doesn't say when
-                // this might happen, so no idea if it needs to be handled.
-                let event = event.expect("Error handling watch event");
-
-                // Various acceptable ModifyKind kinds. Varies across platforms
-                // (e.g. MacOs vs. Windows10)
-                if let notify::EventKind::Modify(
-                    notify::event::ModifyKind::Any,
-                )
-                | notify::EventKind::Modify(
-                    notify::event::ModifyKind::Data(
-                        notify::event::DataChange::Any,
-                    ),
-                )
-                | notify::EventKind::Modify(
-                    notify::event::ModifyKind::Data(
-                        notify::event::DataChange::Content,
-                    ),
-                ) = event.kind
-                {
-                    let file_ext = event
-                        .paths
-                        .get(0)
-                        .expect("File path missing in watch event")
-                        .extension();
-
-                    let black_list = HashSet::from([
-                        OsStr::new("swp"),
-                        OsStr::new("tmp"),
-                        OsStr::new("swx"),
-                    ]);
-
-                    if let Some(ext) = file_ext {
-                        if black_list.contains(ext) {
-                            return;
-                        }
-                    }
-
-                    // This will panic, if the other end is disconnected, which
-                    // is probably the result of a panic on that thread, or the
-                    // application is being shut down.
-                    //
-                    // Either way, not much we can do about it here, except
-                    // maybe to provide a better error message in the future.
-                    tx.send(()).unwrap();
-                }
-            },
-        )?;
 
-        watcher.watch(&watch_path, notify::RecursiveMode::Recursive)?;
+        let watcher = watch::watch(&self.packages, self.debounce, tx)?;
 
         // To prevent a race condition between the initial load and the start of
-        // watching, we'll trigger the initial load here, after having started
-        // watching.
-        //
-        // Will panic, if the receiving end has panicked. Not much we can do
-        // about that, if it happened.
-        thread::spawn(move || tx2.send(()).unwrap());
-
+        // watching, the initial load is only triggered now, after having
+        // started watching. If this instance has already compiled the model,
+        // the initial load doesn't compile it again.
         Ok(Watcher {
-            _watcher: Box::new(watcher),
+            _watcher: watcher,
             channel: rx,
             model: self,
             parameters,
+            model_changed: true,
+            parameters_changed: false,
         })
     }
 }
 
+/// The outcome of a compilation that didn't fail
+enum Compilation {
+    Finished,
+    Cancelled,
+}
+
+/// Options for compiling a model
+#[derive(Clone, Debug, Default)]
+pub struct BuildOptions {
+    /// The Cargo profile to build the model with
+    ///
+    /// Uses Cargo's default (the `dev` profile), if `None`. Building in the
+    /// `release` profile takes longer, but makes heavy models evaluate faster.
+    pub profile: Option<String>,
+
+    /// The features of the model to enable
+    pub features: Vec<String>,
+
+    /// Additional arguments that are passed to `cargo build`
+    pub cargo_args: Vec<String>,
+}
+
+impl BuildOptions {
+    /// The directory within the target directory that the profile builds into
+    fn profile_dir(&self) -> &str {
+        match self.profile.as_deref() {
+            None | Some("dev") | Some("test") => "debug",
+            Some("bench") => "release",
+            Some(profile) => profile,
+        }
+    }
+}
+
+/// The backend that runs a model
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Backend {
+    /// Compile the model into a dynamic library, and load that into the host
+    ///
+    /// This is fast, but the model has the same access to the system as the
+    /// host does. See [`Model::evaluate`] for more caveats.
+    Native,
+
+    /// Compile the model to WebAssembly, and run it in a sandbox
+    ///
+    /// Requires the `wasm32-unknown-unknown` target to be installed. While it
+    /// runs, the model has no access to anything outside of the sandbox.
+    ///
+    /// This doesn't make it safe to run untrusted models. The model is still
+    /// compiled by a native Cargo build, which runs the build scripts and
+    /// procedural macros of the model and its dependencies without any
+    /// restrictions.
+    #[cfg(feature = "wasm")]
+    Wasm,
+}
+
 /// Watches a model for changes, reloading it continually
+///
+/// A `Watcher` can be sent to another thread, so the model can be reloaded
+/// there, without blocking the thread that uses the shape.
 pub struct Watcher {
-    _watcher: Box<dyn notify::Watcher>,
+    _watcher: Box<dyn notify::Watcher + Send>,
     channel: mpsc::Receiver<()>,
     model: Model,
     parameters: HashMap<String, String>,
+    model_changed: bool,
+    parameters_changed: bool,
 }
 
 impl Watcher {
-    /// Receive an updated shape that the reloaded model created
+    /// Change the parameters that are passed to the model
     ///
-    /// Returns `None`, if the model has not changed since the last time this
-    /// method was called.
-    pub fn receive(&self) -> Option<fj::Shape> {
+    /// The model is re-evaluated with the new parameters on the next call to
+    /// [`Watcher::receive`]. Since the model code hasn't changed, it is not
+    /// recompiled.
+    pub fn set_parameters(&mut self, parameters: HashMap<String, String>) {
+        self.parameters = parameters;
+        self.parameters_changed = true;
+    }
+
+    /// Check whether the model or its parameters have changed
+    ///
+    /// Returns `true`, if the next call to [`Watcher::receive`] is going to
+    /// reload the model. Doesn't block.
+    pub fn has_changed(&mut self) -> bool {
         match self.channel.try_recv() {
             Ok(()) => {
-                let shape = match self.model.load_once(&self.parameters) {
-                    Ok(shape) => shape,
-                    Err(Error::Compile) => {
-                        // It would be better to display an error in the UI,
-                        // where the user can actually see it. Issue:
-                        // https://github.com/hannobraun/fornjot/issues/30
-                        println!("Error compiling model");
-                        return None;
-                    }
-                    Err(err) => {
-                        panic!("Error reloading model: {:?}", err);
-                    }
-                };
-
-                Some(shape)
+                self.model_changed = true;
+                self.model.compiled.set(false);
             }
             Err(mpsc::TryRecvError::Empty) => {
                 // Nothing to receive from the channel.
-                None
             }
             Err(mpsc::TryRecvError::Disconnected) => {
                 // The other end has disconnected. This is probably the result
@@ -240,15 +499,164 @@ impl Watcher {
                 panic!();
             }
         }
+
+        self.model_changed || self.parameters_changed
+    }
+
+    /// Receive an updated shape that the reloaded model created
+    ///
+    /// Returns `None`, if neither the model nor its parameters have changed
+    /// since the last time this method was called. Returns an error, if the
+    /// model could not be reloaded, for example because it failed to compile.
+    /// Callers can keep using the last shape they received in that case.
+    ///
+    /// If the model changes again while it is being compiled, the compilation
+    /// is cancelled and started over, as its result would be stale anyway.
+    pub fn receive(&mut self) -> Option<Result<fj::Shape, Error>> {
+        if !self.has_changed() {
+            return None;
+        }
+
+        let model_changed = self.model_changed;
+        self.model_changed = false;
+        self.parameters_changed = false;
+
+        // If only the parameters have changed, there's no need to recompile
+        // the model.
+        if model_changed && !self.model.compiled.get() {
+            loop {
+                let channel = &self.channel;
+                match self.model.compile(|| channel.try_recv().is_ok()) {
+                    Ok(Compilation::Finished) => break,
+                    Ok(Compilation::Cancelled) => continue,
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+        }
+
+        Some(self.model.evaluate(&self.parameters))
     }
 }
 
+/// Check that the model was built against the same version of `fj` as the host
+///
+/// The model and the host pass `fj` types to each other directly, so this
+/// needs to be checked, before any other function of the model is called. As
+/// unreleased versions of `fj` can change without their version changing, the
+/// hash of its source code is compared too. The model's functions use the Rust
+/// ABI, so it also needs to be built by the same compiler as the host.
+///
+/// Models that run in WebAssembly don't need this check, as the shape crosses
+/// the boundary in serialized form.
+///
+/// # Safety
+///
+/// The library must be a model library. If it exports a symbol named
+/// `fj_abi_version`, `fj_source_hash`, or `fj_rustc_version` that is not the
+/// function exported by `fj`, calling it is undefined behavior.
+unsafe fn check_version(lib: &libloading::Library) -> Result<(), Error> {
+    let host = fj::Version::CURRENT;
+
+    // Models built against a version of `fj` older than the one that
+    // introduced this function don't export it.
+    let model = match lib.get::<AbiVersionFn>(b"fj_abi_version") {
+        Ok(abi_version) => Some(abi_version()),
+        Err(_) => None,
+    };
+
+    if model != Some(host) {
+        return Err(Error::IncompatibleVersion { host, model });
+    }
+
+    let version = host;
+    let host = fj::SOURCE_HASH;
+
+    let model = match lib.get::<SourceHashFn>(b"fj_source_hash") {
+        Ok(source_hash) => Some(source_hash()),
+        Err(_) => None,
+    };
+
+    if model != Some(host) {
+        return Err(Error::IncompatibleSource {
+            version,
+            host,
+            model,
+        });
+    }
+
+    let host = fj::RUSTC_VERSION;
+
+    // Models built before `fj` exported this function don't export it. They
+    // might also use the C ABI for their functions, so we can't call them.
+    let model = match lib.get::<RustcVersionFn>(b"fj_rustc_version") {
+        Ok(rustc_version) => Some(
+            CStr::from_ptr(rustc_version())
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        Err(_) => None,
+    };
+
+    if model.as_deref() != Some(host) {
+        return Err(Error::IncompatibleCompiler {
+            host: host.to_owned(),
+            model,
+        });
+    }
+
+    Ok(())
+}
+
+/// Call into the model, catching any panic that occurs there
+///
+/// The model library has its own copy of the standard library, which is the
+/// one that raises a panic in the model. The host's copy can't catch it: Trying
+/// to do so either aborts the process, or leaves the host's copy believing that
+/// the thread is still panicking. So `f` is called through `fj_catch_panic`,
+/// which `fj` exports from every model, and which catches the panic in the
+/// model.
+///
+/// This is why the model functions use the Rust ABI instead of `extern "C"`:
+/// Unwinding out of an `extern "C"` function is undefined behavior, and the
+/// `"C-unwind"` ABI, which would allow it, isn't available on stable Rust yet.
+/// The Rust ABI is only stable for a given compiler version, but the host and
+/// the model need to be compiled by the same compiler anyway (they pass Rust
+/// types to each other). [`check_version`] makes sure that they are.
+///
+/// Only panics are caught. If the model aborts, or crashes in some other way,
+/// it still takes the host down with it.
+///
+/// # Safety
+///
+/// `lib` must be a model library, for which [`check_version`] has succeeded.
+unsafe fn catch_panic<T>(
+    lib: &libloading::Library,
+    f: impl FnOnce() -> T,
+) -> Result<T, Error> {
+    let catch_panic: libloading::Symbol<CatchPanicFn> =
+        lib.get(b"fj_catch_panic")?;
+
+    let mut f = Some(f);
+    let mut output = None;
+    catch_panic(&mut || output = f.take().map(|f| f()))
+        .map_err(Error::ModelPanicked)?;
+
+    Ok(output.expect("Function returned without producing output"))
+}
+
 /// An error that can occur when loading or reloading a model
 #[derive(Debug, Error)]
 pub enum Error {
     /// Model failed to compile
     #[error("Error compiling model")]
-    Compile,
+    Compile {
+        /// The diagnostics that the compiler emitted
+        diagnostics: Vec<Diagnostic>,
+    },
+
+    /// Error querying the model's metadata from Cargo
+    #[error("Error querying model metadata: {0}")]
+    Metadata(String),
 
     /// I/O error while loading the model
     #[error("I/O error while loading model")]
@@ -258,10 +666,149 @@ pub enum Error {
     #[error("Error loading model from dynamic library")]
     LibLoading(#[from] libloading::Error),
 
+    /// The model was built against a different version of `fj` than the host
+    #[error(
+        "Model was built against an incompatible version of `fj`\n\
+        host: {host}, model: {}\n\
+        Update the `fj` dependency of the model to match the host.",
+        .model.map_or("unknown".to_owned(), |model| model.to_string())
+    )]
+    IncompatibleVersion {
+        /// The version of `fj` that the host was built against
+        host: fj::Version,
+
+        /// The version of `fj` that the model was built against
+        ///
+        /// `None`, if the model is too old to report it.
+        model: Option<fj::Version>,
+    },
+
+    /// The model was built against a different build of the same version of
+    /// `fj` as the host
+    ///
+    /// This happens, if the source code of `fj` changed without its version
+    /// changing, which is the case between releases.
+    #[error(
+        "Model was built against an incompatible build of `fj` {version}\n\
+        host: {host:016x}, model: {}\n\
+        Build the model against the same `fj` as the host.",
+        .model.map_or("unknown".to_owned(), |model| format!("{:016x}", model))
+    )]
+    IncompatibleSource {
+        /// The version of `fj` that both were built against
+        version: fj::Version,
+
+        /// The hash of the `fj` source code that the host was built against
+        host: u64,
+
+        /// The hash of the `fj` source code that the model was built against
+        ///
+        /// `None`, if the model is too old to report it.
+        model: Option<u64>,
+    },
+
+    /// The model was built by a different compiler than the host
+    #[error(
+        "Model was built by a different compiler than the host\n\
+        host: {host}, model: {}\n\
+        Build the model with the same version of Rust as the host.",
+        .model.as_deref().unwrap_or("unknown")
+    )]
+    IncompatibleCompiler {
+        /// The compiler version that built the host
+        host: String,
+
+        /// The compiler version that built the model
+        ///
+        /// `None`, if the model is too old to report it.
+        model: Option<String>,
+    },
+
+    /// The model panicked
+    ///
+    /// Carries the panic message, if it could be extracted.
+    #[error("Model panicked: {0}")]
+    ModelPanicked(String),
+
+    /// Error running the model in the WebAssembly runtime
+    #[cfg(feature = "wasm")]
+    #[error("Error running model in WebAssembly runtime")]
+    Wasm(#[source] anyhow::Error),
+
     /// Error while watching the model code for changes
     #[error("Error watching model for changes")]
     Notify(#[from] notify::Error),
 }
 
-type ModelFn =
-    unsafe extern "C" fn(args: &HashMap<String, String>) -> fj::Shape;
+type ModelFn = unsafe fn(args: &HashMap<String, String>) -> fj::Shape;
+type ParametersFn = unsafe fn(parameters: &mut fj::Parameters);
+type CatchPanicFn = unsafe fn(f: &mut dyn FnMut()) -> Result<(), String>;
+type AbiVersionFn = unsafe extern "C" fn() -> fj::Version;
+type SourceHashFn = unsafe extern "C" fn() -> u64;
+type RustcVersionFn = unsafe extern "C" fn() -> *const c_char;
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, panic, path::Path, thread};
+
+    use super::{Error, Model};
+
+    #[test]
+    fn model_panicked() {
+        let dir = std::env::temp_dir()
+            .join(format!("fj-host-model-panicked-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+
+        let fj = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fj");
+        fs::write(
+            dir.join("Cargo.toml"),
+            format!(
+                "[package]\n\
+                name = \"panicking\"\n\
+                version = \"0.1.0\"\n\
+                edition = \"2021\"\n\
+                [lib]\n\
+                crate-type = [\"cdylib\"]\n\
+                [dependencies.fj]\n\
+                path = '{}'\n\
+                [workspace]\n",
+                fj.display()
+            ),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("src/lib.rs"),
+            "#[no_mangle]\n\
+            pub fn model(\n\
+                args: &std::collections::HashMap<String, String>,\n\
+            ) -> fj::Shape {\n\
+                if args.contains_key(\"panic\") {\n\
+                    panic!(\"Model panicked on purpose\");\n\
+                }\n\
+                fj::Circle::from_radius(1.).into()\n\
+            }\n",
+        )
+        .unwrap();
+
+        let model = Model::from_path(dir.clone(), None).unwrap();
+        let panicking: HashMap<_, _> =
+            [("panic".to_owned(), String::new())].into_iter().collect();
+
+        // Panicking more than once must not be a problem.
+        for _ in 0..2 {
+            let result = model.load_once(&panicking);
+            assert!(matches!(
+                result,
+                Err(Error::ModelPanicked(message))
+                    if message == "Model panicked on purpose"
+            ));
+        }
+
+        // The host is still healthy, and so is the model.
+        assert!(!thread::panicking());
+        assert!(panic::catch_unwind(|| panic!("Host panic")).is_err());
+        assert!(model.evaluate(&HashMap::new()).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
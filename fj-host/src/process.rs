@@ -0,0 +1,118 @@
+use std::{
+    io,
+    process::{Child, Command},
+};
+
+/// Make the process that the command spawns start its own process group
+///
+/// Cargo spawns compiler processes, which would keep running if only Cargo was
+/// killed. With its own process group, [`kill_tree`] can kill all of them.
+///
+/// As a side effect, the process doesn't receive the signal from pressing
+/// Ctrl+C in the terminal. Cargo still stops, once the host has exited and it
+/// can no longer write its output.
+pub fn new_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt as _;
+
+        // Safe, as `setpgid` is async-signal-safe, and nothing else happens
+        // in the closure.
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setpgid(0, 0) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = command;
+    }
+}
+
+/// Kill a process, and all processes that it started
+///
+/// On Unix, this requires the process to have been spawned with
+/// [`new_process_group`].
+pub fn kill_tree(child: &mut Child) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        // The process group has the same ID as the process that started it.
+        // Passing the negated ID signals every process in the group.
+        let result = unsafe { libc::kill(-(child.id() as i32), libc::SIGKILL) };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    {
+        // Windows has no process groups that could be killed at once, but
+        // `taskkill` can kill a whole tree of processes.
+        let status = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &child.id().to_string()])
+            .output()?
+            .status;
+        if !status.success() {
+            return child.kill();
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        child.kill()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{
+        io::{BufRead as _, BufReader},
+        process::{Command, Stdio},
+        thread,
+        time::Duration,
+    };
+
+    #[test]
+    fn kill_tree() {
+        // Starts a child process, like Cargo starts the compiler.
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "sleep 60 & echo $!; wait"])
+            .stdout(Stdio::piped());
+        super::new_process_group(&mut command);
+        let mut child = command.spawn().unwrap();
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let grandchild: u32 = line.trim().parse().unwrap();
+
+        super::kill_tree(&mut child).unwrap();
+        child.wait().unwrap();
+
+        // Killing is asynchronous, so give it a moment. Once killed, the
+        // process becomes a zombie, until it is reaped.
+        let mut alive = true;
+        for _ in 0..100 {
+            let output = Command::new("ps")
+                .args(["-o", "stat=", "-p", &grandchild.to_string()])
+                .output()
+                .unwrap();
+            let state = String::from_utf8_lossy(&output.stdout);
+            alive = !state.trim().is_empty() && !state.starts_with('Z');
+            if !alive {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!alive);
+    }
+}
@@ -0,0 +1,166 @@
+use std::{collections::HashMap, path::Path};
+
+use wasmtime::{
+    Config, Engine, Instance, Module, Store, StoreLimitsBuilder, Trap,
+};
+
+use crate::Error;
+
+/// The target that models are compiled to
+pub const TARGET: &str = "wasm32-unknown-unknown";
+
+/// The amount of fuel a model gets for one evaluation
+///
+/// Every WebAssembly instruction that the model executes consumes fuel, so
+/// this limits how long a model can run. The number is somewhat arbitrary, but
+/// should be plenty for any reasonable model.
+const FUEL: u64 = 10_000_000_000;
+
+/// The maximum amount of memory a model can use, in bytes
+const MAX_MEMORY: usize = 1024 * 1024 * 1024;
+
+/// Evaluate a model that was compiled to WebAssembly
+///
+/// The model runs in a fresh instance, that provides it with no imports. This
+/// means the model has no access to the file system, the network, or anything
+/// else outside of its own memory. How long it can run, and how much memory it
+/// can use, is limited too.
+///
+/// The module is compiled on every call. This is slower than it needs to be,
+/// but keeps every evaluation independent of the ones before it.
+pub fn evaluate(
+    path: &Path,
+    arguments: &HashMap<String, String>,
+) -> Result<fj::Shape, Error> {
+    evaluate_with_fuel(path, arguments, FUEL)
+}
+
+fn evaluate_with_fuel(
+    path: &Path,
+    arguments: &HashMap<String, String>,
+    fuel: u64,
+) -> Result<fj::Shape, Error> {
+    run(path, arguments, fuel).map_err(|err| match err.downcast::<Trap>() {
+        // A Rust panic aborts on WebAssembly, which causes a trap. So does
+        // running out of fuel or memory.
+        Ok(trap) => Error::ModelPanicked(trap.to_string()),
+        Err(err) => Error::Wasm(err),
+    })
+}
+
+fn run(
+    path: &Path,
+    arguments: &HashMap<String, String>,
+    fuel: u64,
+) -> anyhow::Result<fj::Shape> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+
+    let engine = Engine::new(&config)?;
+    let module = Module::from_file(&engine, path)?;
+
+    let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    store.add_fuel(fuel)?;
+
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| anyhow::anyhow!("Model doesn't export its memory"))?;
+    let alloc =
+        instance.get_typed_func::<u32, u32, _>(&mut store, "fj_alloc")?;
+    let model = instance
+        .get_typed_func::<(u32, u32), u64, _>(&mut store, "fj_model")?;
+
+    // The buffers allocated here are never freed. The instance is discarded
+    // after this function returns, along with all of its memory.
+    let args = serde_json::to_vec(arguments)?;
+    let args_len = args.len() as u32;
+    let args_ptr = alloc.call(&mut store, args_len)?;
+    memory.write(&mut store, args_ptr as usize, &args)?;
+
+    let shape = model.call(&mut store, (args_ptr, args_len))?;
+    let shape_ptr = (shape >> 32) as usize;
+    let shape_len = (shape & 0xffff_ffff) as usize;
+
+    // The location comes from the model, so it can't be trusted. Reading it
+    // in place, instead of copying it into a buffer of the given length first,
+    // means the model can't make the host allocate arbitrary amounts of memory.
+    let shape = memory
+        .data(&store)
+        .get(shape_ptr..)
+        .and_then(|data| data.get(..shape_len))
+        .ok_or_else(|| {
+            anyhow::anyhow!("Model returned shape outside of its memory")
+        })?;
+
+    let shape = serde_json::from_slice(shape)?;
+    Ok(shape)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use crate::Error;
+
+    use super::{evaluate_with_fuel, FUEL};
+
+    #[test]
+    fn trap() {
+        let result = evaluate("trap", "unreachable", FUEL);
+        assert!(matches!(result, Err(Error::ModelPanicked(_))));
+    }
+
+    #[test]
+    fn fuel_exhausted() {
+        let result =
+            evaluate("fuel_exhausted", "(loop (br 0)) (i64.const 0)", 1000);
+        assert!(matches!(result, Err(Error::ModelPanicked(_))));
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        // The pointer is in the upper 32 bits of the result, the length in the
+        // lower ones. The memory of the module is 64 KiB large.
+        for (name, location) in [
+            ("out_of_bounds_pointer", 0x0010_0000_0000_0010_u64),
+            ("out_of_bounds_length", 0x0000_0000_ffff_ffff),
+            ("out_of_bounds_overflow", 0xffff_ffff_ffff_ffff),
+        ] {
+            let result = evaluate(
+                name,
+                &format!("(i64.const {})", location as i64),
+                FUEL,
+            );
+            assert!(matches!(result, Err(Error::Wasm(_))));
+        }
+    }
+
+    /// Evaluate a module, whose `fj_model` function has the given body
+    fn evaluate(name: &str, body: &str, fuel: u64) -> Result<fj::Shape, Error> {
+        let module = format!(
+            "(module
+                (memory (export \"memory\") 1)
+                (func (export \"fj_alloc\") (param i32) (result i32)
+                    i32.const 0)
+                (func (export \"fj_model\") (param i32 i32) (result i64)
+                    {}))",
+            body
+        );
+
+        let path = std::env::temp_dir().join(format!(
+            "fj-host-wasm-{}-{}.wat",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, module).unwrap();
+
+        let result = evaluate_with_fuel(&path, &HashMap::new(), fuel);
+
+        fs::remove_file(&path).unwrap();
+        result
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use ignore::gitignore::Gitignore;
+use notify::{
+    event::{EventKind, ModifyKind},
+    RecursiveMode, Watcher as _,
+};
+
+use crate::Error;
+
+/// A package whose files are watched for changes
+#[derive(Clone, Debug)]
+pub struct Package {
+    /// The directory that contains the package's `Cargo.toml`
+    pub dir: PathBuf,
+
+    /// The directory that contains the package's source code
+    pub src_dir: PathBuf,
+}
+
+/// Watch packages for changes
+///
+/// Watches the source directory and the manifest of each package. Files that
+/// are ignored by Git (or are temporary files created by editors) are
+/// ignored.
+///
+/// Events are debounced: Only once no further events have come in for the
+/// duration of `debounce`, a single message is sent through `tx`. Editors
+/// often produce multiple events per save, and this makes sure that only
+/// triggers a single reload.
+pub fn watch(
+    packages: &[Package],
+    debounce: Duration,
+    tx: mpsc::SyncSender<()>,
+) -> Result<Box<dyn notify::Watcher + Send>, Error> {
+    let filter = Filter::new(packages);
+    let (events_tx, events_rx) = mpsc::channel();
+
+    let mut watcher = notify::recommended_watcher(
+        move |event: notify::Result<notify::Event>| {
+            // Unfortunately the `notify` documentation doesn't say when this
+            // might happen, so no idea if it needs to be handled.
+            let event = event.expect("Error handling watch event");
+
+            // Various event kinds indicate a change, depending on the platform
+            // and the editor. Changes to metadata (like access times) don't
+            // change the model.
+            let relevant_kind = matches!(
+                event.kind,
+                EventKind::Create(_)
+                    | EventKind::Remove(_)
+                    | EventKind::Modify(
+                        ModifyKind::Any
+                            | ModifyKind::Data(_)
+                            | ModifyKind::Name(_)
+                    )
+            );
+
+            if relevant_kind
+                && event.paths.iter().any(|path| filter.is_relevant(path))
+            {
+                // If this fails, the debouncing thread has stopped, meaning the
+                // watcher is being shut down. Nothing to do then.
+                let _ = events_tx.send(());
+            }
+        },
+    )?;
+
+    for package in packages {
+        watcher.watch(&package.src_dir, RecursiveMode::Recursive)?;
+
+        // Editors often replace files on save, instead of writing to them. If
+        // we watched `Cargo.toml` directly, we'd miss the changes after that.
+        watcher.watch(&package.dir, RecursiveMode::NonRecursive)?;
+    }
+
+    thread::spawn(move || {
+        while events_rx.recv().is_ok() {
+            loop {
+                match events_rx.recv_timeout(debounce) {
+                    Ok(()) => continue,
+                    Err(mpsc::RecvTimeoutError::Timeout) => break,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            // This will fail, if the other end is disconnected, which is
+            // probably the result of a panic on that thread, or the
+            // application is being shut down. Either way, we're done here.
+            if tx.send(()).is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok(Box::new(watcher))
+}
+
+/// Decides which changed files are relevant to the model
+struct Filter {
+    manifests: HashSet<PathBuf>,
+    src_dirs: Vec<PathBuf>,
+    gitignores: Vec<Gitignore>,
+}
+
+impl Filter {
+    fn new(packages: &[Package]) -> Self {
+        let manifests = packages
+            .iter()
+            .map(|package| package.dir.join("Cargo.toml"))
+            .collect();
+        let src_dirs = packages
+            .iter()
+            .map(|package| package.src_dir.clone())
+            .collect();
+
+        // Collect the `.gitignore` files of every package, and of all its
+        // parent directories, up to the root of the repository.
+        let mut gitignore_dirs = HashSet::new();
+        for package in packages {
+            for dir in package.dir.ancestors() {
+                gitignore_dirs.insert(dir.to_path_buf());
+
+                if dir.join(".git").exists() {
+                    break;
+                }
+            }
+        }
+        let gitignores = gitignore_dirs
+            .into_iter()
+            .map(|dir| dir.join(".gitignore"))
+            .filter(|path| path.is_file())
+            .map(|path| {
+                // Any errors are about single patterns, and the rest of the
+                // file is still used. We can live with that.
+                let (gitignore, _) = Gitignore::new(path);
+                gitignore
+            })
+            .collect();
+
+        Self {
+            manifests,
+            src_dirs,
+            gitignores,
+        }
+    }
+
+    fn is_relevant(&self, path: &Path) -> bool {
+        let is_manifest = self.manifests.contains(path);
+        let is_source = self.src_dirs.iter().any(|dir| path.starts_with(dir));
+
+        (is_manifest || is_source) && !self.is_ignored(path)
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        // Temporary files that editors create. They're usually not listed in
+        // `.gitignore`.
+        let temporary =
+            [OsStr::new("swp"), OsStr::new("swx"), OsStr::new("tmp")];
+        if let Some(extension) = path.extension() {
+            if temporary.contains(&extension) {
+                return true;
+            }
+        }
+
+        self.gitignores.iter().any(|gitignore| {
+            path.starts_with(gitignore.path())
+                && gitignore
+                    .matched_path_or_any_parents(path, path.is_dir())
+                    .is_ignore()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{Filter, Package};
+
+    #[test]
+    fn filter() {
+        let dir = std::env::temp_dir()
+            .join(format!("fj-host-watch-filter-{}", std::process::id()));
+        let package_dir = dir.join("model");
+        fs::create_dir_all(package_dir.join("src")).unwrap();
+        fs::create_dir(dir.join(".git")).unwrap();
+        fs::write(dir.join(".gitignore"), "*.bak\n").unwrap();
+
+        let filter = Filter::new(&[Package {
+            dir: package_dir.clone(),
+            src_dir: package_dir.join("src"),
+        }]);
+        let relevant = |path: &str| filter.is_relevant(&package_dir.join(path));
+
+        assert!(relevant("Cargo.toml"));
+        assert!(relevant("src/lib.rs"));
+        assert!(!relevant("README.md"));
+        assert!(!relevant("src/lib.rs.bak"));
+        assert!(!relevant("src/.lib.rs.swp"));
+        assert!(!filter.is_relevant(&PathBuf::from("/elsewhere/src/lib.rs")));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -2,7 +2,10 @@ use std::collections::HashSet;
 
 use fj_math::{Point, Scalar, Segment};
 
-use crate::topology::{Cycle, Face, Vertex};
+use crate::{
+    geometry::{Circle, Curve},
+    topology::{Cycle, Edge, Face, Vertex},
+};
 
 /// The approximation of a face
 #[derive(Debug, PartialEq)]
@@ -101,10 +104,19 @@ pub fn approximate_cycle(cycle: &Cycle, tolerance: Scalar) -> Vec<Point<3>> {
     let mut points = Vec::new();
 
     for edge in cycle.edges() {
-        let mut edge_points = Vec::new();
-        edge.curve().approx(tolerance, &mut edge_points);
+        let mut edge_points = approximate_edge_points(&edge, tolerance);
+
+        // Edges don't necessarily go in the direction of the cycle. Arcs, for
+        // example, always go counter-clockwise.
+        if let (Some(previous), Some(first), Some(last)) =
+            (points.last(), edge_points.first(), edge_points.last())
+        {
+            if previous != first && previous == last {
+                edge_points.reverse();
+            }
+        }
 
-        points.extend(approximate_edge(edge_points, edge.vertices()));
+        points.extend(edge_points);
     }
 
     points.dedup();
@@ -112,15 +124,60 @@ pub fn approximate_cycle(cycle: &Cycle, tolerance: Scalar) -> Vec<Point<3>> {
     points
 }
 
+/// Compute an approximation for an edge
+///
+/// The points go from the edge's first vertex to its second one. If the edge is
+/// continuous, the first point is repeated at the end.
+pub(super) fn approximate_edge_points(
+    edge: &Edge,
+    tolerance: Scalar,
+) -> Vec<Point<3>> {
+    let mut points = Vec::new();
+    edge.curve().approx(tolerance, &mut points);
+
+    let vertices = edge.vertices();
+    if let (Curve::Circle(circle), Some([a, b])) = (edge.curve(), &vertices) {
+        restrict_to_arc(&circle, [a.point(), b.point()], &mut points);
+    }
+
+    approximate_edge(points, vertices)
+}
+
+/// Restrict the approximation of a circle to an arc
+///
+/// Arcs go counter-clockwise from their first vertex to their second one. Only
+/// the points in between are kept, ordered from the first vertex to the second.
+fn restrict_to_arc(
+    circle: &Circle,
+    [a, b]: [Point<3>; 2],
+    points: &mut Vec<Point<3>>,
+) {
+    let full_circle = Scalar::PI * 2.;
+    let start = circle.point_model_to_curve(&a).t;
+
+    // The angle from the start of the arc to the point, counter-clockwise.
+    let angle = |point: &Point<3>| {
+        let angle = circle.point_model_to_curve(point).t - start;
+        (angle + full_circle) % full_circle
+    };
+
+    let end = angle(&b);
+    points.retain(|point| {
+        let angle = angle(point);
+        angle > Scalar::ZERO && angle < end
+    });
+    points.sort_by_key(|point| angle(point));
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_math::{Point, Scalar, Segment};
+    use fj_math::{Point, Scalar, Segment, Vector};
     use map_macro::set;
 
     use crate::{
-        geometry::Surface,
+        geometry::{Circle, Curve, Surface},
         shape::Shape,
-        topology::{Cycle, Face, Vertex},
+        topology::{Cycle, Edge, Face, Vertex},
     };
 
     use super::Approximation;
@@ -155,6 +212,43 @@ mod tests {
         assert_eq!(super::approximate_edge(points, None), vec![b, c, b],);
     }
 
+    #[test]
+    fn approximate_arc() {
+        let mut shape = Shape::new();
+
+        let [a, b] = [[0., 1., 0.], [-1., 0., 0.]].map(|point| {
+            let point = shape.geometry().add_point(Point::from(point));
+            shape.topology().add_vertex(Vertex { point }).unwrap()
+        });
+
+        let curve = shape.geometry().add_curve(Curve::Circle(Circle {
+            center: Point::origin(),
+            radius: Vector::from([1., 0., 0.]),
+            normal: Vector::unit_z(),
+        }));
+        let quarter = shape
+            .topology()
+            .add_edge(Edge {
+                curve,
+                vertices: Some([a, b]),
+            })
+            .unwrap();
+        let cycle = Cycle {
+            edges: vec![quarter],
+        };
+
+        let points = super::approximate_cycle(&cycle, Scalar::from_f64(0.01));
+
+        // The arc goes counter-clockwise, from the first vertex to the second.
+        assert_eq!(points.first(), Some(&Point::from([0., 1., 0.])));
+        assert_eq!(points.last(), Some(&Point::from([-1., 0., 0.])));
+        assert!(points.len() > 2);
+        for point in points {
+            assert!(point.x <= Scalar::ZERO);
+            assert!(point.y >= Scalar::ZERO);
+        }
+    }
+
     #[test]
     fn for_face_closed() {
         // Test a closed face, i.e. one that is completely encircled by edges.
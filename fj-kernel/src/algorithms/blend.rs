@@ -0,0 +1,678 @@
+use std::collections::HashMap;
+
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::{
+    geometry::{Circle, Curve, Line},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{
+    loft::{cycle_vertices, loft_shapes, LoftError},
+    offset::{offset_shape, OffsetError},
+    sweep::{sweep_shape, validate_path, PathSegment, SweepError},
+};
+
+/// An error that can occur when blending corners or edges
+#[derive(Debug, thiserror::Error)]
+pub enum BlendError {
+    /// The size of the blend is negative, or not finite
+    #[error("Can't blend by a negative or infinite size: {0}")]
+    InvalidSize(f64),
+
+    /// The blend doesn't fit into the shape
+    #[error("Blend of size {0} doesn't fit into the shape")]
+    TooLarge(f64),
+
+    /// Sweeping the blended shape failed
+    #[error("Error sweeping blended shape")]
+    Sweep(#[from] SweepError),
+
+    /// Lofting between the offsets of the shape failed
+    #[error("Error lofting between offsets of shape")]
+    Loft(#[from] LoftError),
+}
+
+/// How corners or edges are blended
+#[derive(Clone, Copy, Debug)]
+pub enum Blend {
+    /// Round the corners or edges, using the given radius
+    Fillet(Scalar),
+
+    /// Bevel the corners or edges, cutting the given distance off each side
+    Chamfer(Scalar),
+}
+
+impl Blend {
+    fn size(&self) -> Scalar {
+        match self {
+            Self::Fillet(radius) => *radius,
+            Self::Chamfer(distance) => *distance,
+        }
+    }
+
+    /// Check that the size of the blend is valid
+    fn validate(&self) -> Result<(), BlendError> {
+        let size = self.size();
+        if size < Scalar::ZERO || !size.into_f64().is_finite() {
+            return Err(BlendError::InvalidSize(size.into_f64()));
+        }
+
+        Ok(())
+    }
+}
+
+/// The edges of a sweep that are blended
+#[derive(Clone, Copy, Debug)]
+pub struct SweepEdges {
+    /// The edges along the path, created by the corners of the shape
+    pub side: bool,
+
+    /// The edges around the cap at the end of the path
+    pub top: bool,
+
+    /// The edges around the cap at the start of the path
+    pub bottom: bool,
+}
+
+/// Create a new shape by blending the corners of an existing one
+///
+/// The shape must lie in the x-y plane. Only corners between two straight
+/// edges are blended. A blend of size zero leaves the shape as it is.
+///
+/// Returns an error, if the size of the blend is negative, or not finite, or if
+/// the blends of a corner don't fit into the edges next to it.
+pub fn blend_corners(
+    mut source: Shape,
+    blend: Blend,
+) -> Result<Shape, BlendError> {
+    blend.validate()?;
+    if blend.size() == Scalar::ZERO {
+        return Ok(source);
+    }
+
+    let mut target = Shape::new();
+    let mut vertices = HashMap::new();
+
+    for face_source in source.topology().faces().values() {
+        let (surface, color) = match &face_source {
+            Face::Face { surface, color, .. } => (*surface.get(), *color),
+            Face::Triangles(_) => {
+                target.topology().add_face(face_source.clone()).unwrap();
+                continue;
+            }
+        };
+
+        let cycles = face_source
+            .cycles()
+            .map(|cycle| {
+                let edges =
+                    blend_cycle(&mut target, &cycle, blend, &mut vertices)?;
+                Ok(target.topology().add_cycle(Cycle { edges }).unwrap())
+            })
+            .collect::<Result<_, BlendError>>()?;
+
+        let surface = target.geometry().add_surface(surface);
+        target
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles,
+                color,
+            })
+            .unwrap();
+    }
+
+    Ok(target)
+}
+
+/// Create a new shape by sweeping an existing one, blending some of its edges
+///
+/// Like [`sweep_shape`], but the edges selected by `edges` are blended. The
+/// shape must lie in the x-y plane.
+///
+/// The edges around the caps are blended by lofting between offsets of the
+/// shape, which approximates rounded edges with planar faces, where possible.
+/// The blend is limited to the length of the path. A blend of size zero results
+/// in a plain sweep.
+///
+/// Returns an error, if the path has a length of zero, or an infinite one, if
+/// the size of the blend is negative, or not finite, or if the blend doesn't
+/// fit into the shape. The blend of the caps doesn't fit, if any of the offset
+/// shapes' edges would vanish or flip around.
+pub fn blend_sweep(
+    source: Shape,
+    path: Vector<3>,
+    edges: SweepEdges,
+    blend: Blend,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Result<Shape, BlendError> {
+    validate_path(&[PathSegment::Line(path)])?;
+    blend.validate()?;
+
+    let mut source = source;
+    let mut blended = if edges.side {
+        blend_corners(source.clone(), blend)?
+    } else {
+        source.clone()
+    };
+
+    if !edges.top && !edges.bottom || blend.size() == Scalar::ZERO {
+        return Ok(sweep_shape(blended, path, tolerance, color)?);
+    }
+
+    let length = path.magnitude();
+    let caps = if edges.top && edges.bottom { 2 } else { 1 };
+    let size = blend.size().min(length / Scalar::from_u64(caps));
+
+    // How far the shape is offset, relative to where the blend starts. The
+    // blend starts without an offset, and ends at the cap.
+    let profile: Vec<_> = match blend {
+        Blend::Fillet(_) => {
+            let vertices = Circle::number_of_vertices(tolerance, size);
+            let steps = (vertices + 3) / 4;
+
+            (0..=steps)
+                .map(|step| {
+                    let angle = Scalar::PI / Scalar::TWO
+                        * Scalar::from_u64(step)
+                        / Scalar::from_u64(steps);
+                    let (sin, cos) = angle.sin_cos();
+                    (size * sin, size * (Scalar::ONE - cos))
+                })
+                .collect()
+        }
+        Blend::Chamfer(_) => vec![(Scalar::ZERO, Scalar::ZERO), (size, size)],
+    };
+
+    // Where along the path an offset of the shape is placed, and how large
+    // that offset is.
+    let mut stations = Vec::new();
+    if edges.bottom {
+        stations.extend(
+            profile
+                .iter()
+                .rev()
+                .map(|&(along, offset)| (size - along, offset)),
+        );
+    } else {
+        stations.push((Scalar::ZERO, Scalar::ZERO));
+    }
+    if edges.top {
+        stations.extend(
+            profile
+                .iter()
+                .map(|&(along, offset)| (length - size + along, offset)),
+        );
+    } else {
+        stations.push((length, Scalar::ZERO));
+    }
+    stations.dedup_by(|a, b| a.0 == b.0);
+
+    let too_large = |OffsetError::TooLarge(_)| {
+        BlendError::TooLarge(blend.size().into_f64())
+    };
+    let profiles = stations
+        .into_iter()
+        .map(|(along, offset)| {
+            let mut profile = match blend {
+                // Offsetting the arcs of rounded corners would shrink them to
+                // nothing at the end of the blend. Rounding the offset corners
+                // results in the same arcs, without that problem.
+                Blend::Fillet(radius) if edges.side => blend_corners(
+                    offset_shape(&mut source, offset, tolerance)
+                        .map_err(too_large)?,
+                    Blend::Fillet((radius - offset).max(Scalar::ZERO)),
+                )?,
+                _ => offset_shape(&mut blended, offset, tolerance)
+                    .map_err(too_large)?,
+            };
+            profile
+                .geometry()
+                .transform(&Transform::translation(path * (along / length)));
+            Ok(profile)
+        })
+        .collect::<Result<_, BlendError>>()?;
+
+    Ok(loft_shapes(profiles, tolerance, color)?)
+}
+
+/// Blend the corners of a cycle
+///
+/// Vertices that aren't blended away are shared between cycles, using
+/// `vertices`.
+fn blend_cycle(
+    target: &mut Shape,
+    cycle: &Cycle,
+    blend: Blend,
+    vertices: &mut HashMap<Handle<Vertex>, Handle<Vertex>>,
+) -> Result<Vec<Handle<Edge>>, BlendError> {
+    let mut copy_vertex = |target: &mut Shape, vertex: &Handle<Vertex>| {
+        vertices
+            .entry(vertex.clone())
+            .or_insert_with(|| {
+                let point = target.geometry().add_point(vertex.get().point());
+                target.topology().add_vertex(Vertex { point }).unwrap()
+            })
+            .clone()
+    };
+
+    let starts = match cycle_vertices(&cycle.edges) {
+        Some(starts) => starts,
+        None => {
+            // The cycle consists of a continuous edge, which has no corners.
+            return Ok(cycle
+                .edges
+                .iter()
+                .map(|edge| {
+                    let curve = target.geometry().add_curve(edge.get().curve());
+                    let vertices =
+                        edge.get().vertices.clone().map(|vertices| {
+                            vertices.map(|vertex| copy_vertex(target, &vertex))
+                        });
+
+                    target
+                        .topology()
+                        .add_edge(Edge { curve, vertices })
+                        .unwrap()
+                })
+                .collect());
+        }
+    };
+
+    let n = starts.len();
+    let points: Vec<_> =
+        starts.iter().map(|vertex| vertex.get().point()).collect();
+    let is_line =
+        |i: usize| matches!(cycle.edges[i % n].get().curve(), Curve::Line(_));
+
+    // How far the blend of each corner reaches into the edges before and after
+    // it, and the directions of those edges.
+    let blends: Vec<_> = (0..n)
+        .map(|i| {
+            let previous = (i + n - 1) % n;
+            if !is_line(previous) || !is_line(i) {
+                return None;
+            }
+
+            let corner = points[i];
+            let [a, b] = [points[previous], points[(i + 1) % n]];
+            let [to_a, to_b] = [a - corner, b - corner];
+            let [u_a, u_b] = [to_a.normalize(), to_b.normalize()];
+
+            let cos = u_a.dot(&u_b).max(-Scalar::ONE).min(Scalar::ONE);
+            let half_angle = cos.acos() / Scalar::TWO;
+            if half_angle > Scalar::PI / Scalar::TWO - Scalar::from_f64(1e-9) {
+                // The edges are collinear. There's no corner.
+                return None;
+            }
+
+            let distance = match blend {
+                Blend::Fillet(radius) => radius / half_angle.tan(),
+                Blend::Chamfer(distance) => distance,
+            };
+            if distance < Scalar::from_f64(5e-7) {
+                // The blend is too small to create distinct vertices.
+                return None;
+            }
+
+            Some((distance, [u_a, u_b]))
+        })
+        .collect();
+
+    // The blends at both ends of an edge must fit into it.
+    for i in 0..n {
+        let reach = |corner: Option<(Scalar, _)>| {
+            corner.map(|(distance, _)| distance).unwrap_or(Scalar::ZERO)
+        };
+        let length = (points[(i + 1) % n] - points[i]).magnitude();
+
+        if reach(blends[i]) + reach(blends[(i + 1) % n])
+            > length + Scalar::from_f64(1e-9)
+        {
+            return Err(BlendError::TooLarge(blend.size().into_f64()));
+        }
+    }
+
+    // Where the blend of each corner touches the edges before and after it.
+    let corners: Vec<_> = blends
+        .iter()
+        .zip(&points)
+        .map(|(blend, &corner)| {
+            blend.map(|(distance, [u_a, u_b])| {
+                [corner + u_a * distance, corner + u_b * distance]
+            })
+        })
+        .collect();
+
+    // Create the vertices at the start and end of each corner. If an edge is
+    // used up completely by the blends at its ends, those share a vertex.
+    let is_same = |a: &Handle<Vertex>, b: Point<3>| {
+        // The same distance that `Shape` uses to detect duplicate vertices.
+        (a.get().point() - b).magnitude() < Scalar::from_f64(5e-7)
+    };
+    let mut corner_vertices: Vec<[Handle<Vertex>; 2]> = Vec::new();
+    for i in 0..n {
+        let [start, end] = match corners[i] {
+            Some([start, end]) => {
+                let previous_end = corner_vertices.last().map(|[_, end]| end);
+                let first_start =
+                    corner_vertices.first().map(|[start, _]| start);
+
+                let start = match previous_end {
+                    Some(vertex) if is_same(vertex, start) => vertex.clone(),
+                    _ => add_vertex(target, start),
+                };
+                let end = match first_start {
+                    Some(vertex) if i == n - 1 && is_same(vertex, end) => {
+                        vertex.clone()
+                    }
+                    _ => add_vertex(target, end),
+                };
+
+                [start, end]
+            }
+            None => {
+                let vertex = copy_vertex(target, &starts[i]);
+                [vertex.clone(), vertex]
+            }
+        };
+
+        corner_vertices.push([start, end]);
+    }
+
+    let mut edges = Vec::new();
+    for i in 0..n {
+        let [start, end] = corner_vertices[i].clone();
+
+        if let Some(blend_points) = corners[i] {
+            let (curve, vertices) = match blend {
+                Blend::Fillet(radius) => {
+                    let (arc, is_reversed) =
+                        fillet_arc(points[i], blend_points, radius);
+
+                    // Arcs go counter-clockwise from their first vertex.
+                    let vertices = if is_reversed {
+                        [end.clone(), start.clone()]
+                    } else {
+                        [start.clone(), end.clone()]
+                    };
+
+                    (Curve::Circle(arc), vertices)
+                }
+                Blend::Chamfer(_) => (
+                    Curve::Line(Line::from_points(blend_points)),
+                    [start.clone(), end.clone()],
+                ),
+            };
+
+            let curve = target.geometry().add_curve(curve);
+            edges.push(
+                target
+                    .topology()
+                    .add_edge(Edge {
+                        curve,
+                        vertices: Some(vertices),
+                    })
+                    .unwrap(),
+            );
+        }
+
+        // The edge between this corner and the next one.
+        let [next_start, _] = corner_vertices[(i + 1) % n].clone();
+        if end == next_start {
+            continue;
+        }
+
+        let edge_source = cycle.edges[i].get();
+        let curve = match edge_source.curve() {
+            Curve::Line(_) => Curve::Line(Line::from_points(
+                [&end, &next_start].map(|vertex| vertex.get().point()),
+            )),
+            curve => curve,
+        };
+        let is_forward =
+            edge_source.vertices.as_ref().map(|[a, _]| a) == Some(&starts[i]);
+        let vertices = if is_forward || is_line(i) {
+            [end, next_start]
+        } else {
+            [next_start, end]
+        };
+
+        let curve = target.geometry().add_curve(curve);
+        edges.push(
+            target
+                .topology()
+                .add_edge(Edge {
+                    curve,
+                    vertices: Some(vertices),
+                })
+                .unwrap(),
+        );
+    }
+
+    Ok(edges)
+}
+
+/// The arc that rounds a corner
+///
+/// The arc touches the edges of the corner at `a` and `b`. Arcs go
+/// counter-clockwise, so it starts at `a`, unless it is returned as reversed.
+fn fillet_arc(
+    corner: Point<3>,
+    [a, b]: [Point<3>; 2],
+    radius: Scalar,
+) -> (Circle, bool) {
+    let bisector = ((a - corner) + (b - corner)).normalize();
+    let half_angle = (a - corner).normalize().dot(&bisector).acos();
+    let center = corner + bisector * (radius / half_angle.sin());
+
+    let normal = Vector::unit_z();
+    let is_reversed =
+        (a - center).cross(&(b - center)).dot(&normal) < Scalar::ZERO;
+    let start = if is_reversed { b } else { a };
+
+    let arc = Circle {
+        center,
+        radius: start - center,
+        normal,
+    };
+
+    (arc, is_reversed)
+}
+
+fn add_vertex(target: &mut Shape, point: Point<3>) -> Handle<Vertex> {
+    let point = target.geometry().add_point(point);
+    target.topology().add_vertex(Vertex { point }).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        algorithms::test_helpers::{
+            assert_area, assert_area_between, square, tolerance,
+        },
+        geometry::Curve,
+        shape::Shape,
+    };
+
+    use super::{blend_corners, blend_sweep, Blend, BlendError, SweepEdges};
+
+    #[test]
+    fn fillet_corners() {
+        let mut rounded =
+            blend_corners(square(2.), Blend::Fillet(Scalar::from_f64(0.5)))
+                .unwrap();
+
+        // Each corner is replaced by an arc.
+        assert_eq!(count_arcs(&mut rounded), 4);
+        assert!(rounded.validate().is_ok());
+
+        let area = 4. - (4. - PI) * 0.25;
+        assert_area_between(rounded, area * 0.99, area);
+    }
+
+    #[test]
+    fn chamfer_corners() {
+        let mut beveled =
+            blend_corners(square(2.), Blend::Chamfer(Scalar::from_f64(0.5)))
+                .unwrap();
+
+        assert_eq!(count_arcs(&mut beveled), 0);
+        assert_eq!(beveled.topology().edges().count(), 8);
+        assert!(beveled.validate().is_ok());
+
+        let area = 4. - 4. * 0.125;
+        assert_area(beveled, area);
+    }
+
+    #[test]
+    fn fillet_whole_corners() {
+        // The blends of neighboring corners meet in the middle of each edge.
+        let mut circle =
+            blend_corners(square(2.), Blend::Fillet(Scalar::from_f64(1.)))
+                .unwrap();
+
+        assert_eq!(circle.topology().edges().count(), 4);
+        assert!(circle.validate().is_ok());
+    }
+
+    #[test]
+    fn chamfer_sweep_top() {
+        let mut solid = blend_sweep(
+            square(2.),
+            Vector::from([0., 0., 1.]),
+            SweepEdges {
+                side: false,
+                top: true,
+                bottom: false,
+            },
+            Blend::Chamfer(Scalar::from_f64(0.5)),
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        // Two caps, and two rings of four planar faces.
+        assert_eq!(solid.topology().faces().count(), 10);
+        assert!(solid.validate().is_ok());
+
+        let slope = 0.5 * 2_f64.sqrt();
+        let area = 4. + 1. + 2. * 4. * 0.5 + (2. + 1.) / 2. * slope * 4.;
+        assert_area(solid, area);
+    }
+
+    #[test]
+    fn fillet_sweep_all() {
+        let solid = blend_sweep(
+            square(2.),
+            Vector::from([0., 0., 1.]),
+            SweepEdges {
+                side: true,
+                top: true,
+                bottom: true,
+            },
+            Blend::Fillet(Scalar::from_f64(0.25)),
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        assert!(solid.validate().is_ok());
+    }
+
+    #[test]
+    fn zero_size() {
+        for blend in [Blend::Fillet(Scalar::ZERO), Blend::Chamfer(Scalar::ZERO)]
+        {
+            let mut corners = blend_corners(square(2.), blend).unwrap();
+            assert_eq!(corners.topology().edges().count(), 4);
+
+            let mut solid = blend_sweep(
+                square(2.),
+                Vector::from([0., 0., 1.]),
+                SweepEdges {
+                    side: true,
+                    top: true,
+                    bottom: true,
+                },
+                blend,
+                tolerance(),
+                [255, 0, 0, 255],
+            )
+            .unwrap();
+            assert_eq!(solid.topology().faces().count(), 6);
+        }
+    }
+
+    #[test]
+    fn negative_size() {
+        let blend = Blend::Fillet(Scalar::from_f64(-0.5));
+
+        assert!(matches!(
+            blend_corners(square(2.), blend),
+            Err(BlendError::InvalidSize(_))
+        ));
+        assert!(matches!(
+            blend_sweep(
+                square(2.),
+                Vector::from([0., 0., 1.]),
+                SweepEdges {
+                    side: false,
+                    top: true,
+                    bottom: false,
+                },
+                blend,
+                tolerance(),
+                [255, 0, 0, 255],
+            ),
+            Err(BlendError::InvalidSize(_))
+        ));
+    }
+
+    #[test]
+    fn too_large() {
+        for blend in [
+            Blend::Fillet(Scalar::from_f64(5.)),
+            Blend::Chamfer(Scalar::from_f64(1.5)),
+        ] {
+            assert!(matches!(
+                blend_corners(square(2.), blend),
+                Err(BlendError::TooLarge(_))
+            ));
+        }
+
+        // The offset of the top cap would be turned inside out.
+        for size in [1., 1.5] {
+            assert!(matches!(
+                blend_sweep(
+                    square(2.),
+                    Vector::from([0., 0., 10.]),
+                    SweepEdges {
+                        side: false,
+                        top: true,
+                        bottom: false,
+                    },
+                    Blend::Chamfer(Scalar::from_f64(size)),
+                    tolerance(),
+                    [255, 0, 0, 255],
+                ),
+                Err(BlendError::TooLarge(_))
+            ));
+        }
+    }
+
+    fn count_arcs(shape: &mut Shape) -> usize {
+        shape
+            .topology()
+            .edges()
+            .filter(|edge| matches!(edge.get().curve(), Curve::Circle(_)))
+            .count()
+    }
+}
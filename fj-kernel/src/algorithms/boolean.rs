@@ -0,0 +1,1390 @@
+use std::collections::HashMap;
+
+use fj_debug::DebugInfo;
+use fj_math::{Point, Scalar, Triangle, Vector};
+use parry3d_f64::{
+    bounding_volume::BoundingVolume as _,
+    query::{PointQuery as _, Ray, RayCast as _},
+};
+
+use crate::{
+    geometry::{Circle, Curve, Line, RevolvedCurve, Surface, SweptCurve},
+    shape::{Handle, Shape, ValidationError},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{boolean_2d::boolean_2d, triangulation::triangulate_face};
+
+/// A boolean operation that combines two shapes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BooleanOp {
+    /// Everything that is inside of either shape
+    Union,
+
+    /// Everything that is inside of both shapes
+    Intersection,
+
+    /// Everything that is inside of the first shape, but not the second
+    Difference,
+}
+
+impl BooleanOp {
+    /// Determine whether a point is inside the result, given whether it is
+    /// inside of the two shapes
+    pub(super) fn apply(&self, inside_a: bool, inside_b: bool) -> bool {
+        match self {
+            Self::Union => inside_a || inside_b,
+            Self::Intersection => inside_a && inside_b,
+            Self::Difference => inside_a && !inside_b,
+        }
+    }
+
+    /// Determine whether a point on the boundary of one of the shapes is on
+    /// the boundary of the result
+    ///
+    /// `is_a` specifies whether the point is on the boundary of the first
+    /// shape, `inside_other` whether it is inside of the other shape.
+    ///
+    /// There's inside on one side of the boundary, and outside on the other.
+    /// The point is on the boundary of the result, if the result differs
+    /// between those sides.
+    pub(super) fn keeps(&self, is_a: bool, inside_other: bool) -> bool {
+        if is_a {
+            self.apply(true, inside_other) != self.apply(false, inside_other)
+        } else {
+            self.apply(inside_other, true) != self.apply(inside_other, false)
+        }
+    }
+}
+
+/// Combine two shapes using a boolean operation
+///
+/// Both shapes must be closed solids. The faces of each shape are intersected
+/// with the other shape, and whatever is part of the boundary of the result
+/// is copied into a new shape.
+///
+/// Faces that don't intersect the other shape are copied as they are, with
+/// their full topology. Faces that do intersect the other shape are cut along
+/// the intersection. What remains of a planar face is represented as b-rep
+/// faces on the same plane, bounded by the face's original edges and new edges
+/// along the intersection. What remains of a face on a curved surface is
+/// represented as triangles.
+///
+/// The faces of the second shape that bound a difference face the other way
+/// than they do in the second shape, so they are reversed. Faces whose surface
+/// can't be reversed are represented as triangles too.
+///
+/// # Implementation note
+///
+/// The intersections are computed on the triangle approximations of the
+/// faces. Where the other shape's surface is curved, the new edges of a planar
+/// face approximate the intersection with line segments. Planar faces are also
+/// represented as triangles, if the other shape's surface touches their plane
+/// in a way that can't be handled yet (for example, because a face of the
+/// other shape lies in the same plane).
+///
+/// Cut faces on curved surfaces should be represented as b-rep faces too, once
+/// the kernel is able to compute intersections between its curved surfaces.
+pub fn boolean(
+    mut a: Shape,
+    mut b: Shape,
+    op: BooleanOp,
+    tolerance: Scalar,
+) -> Shape {
+    // Points closer than this to a surface are considered to be on it.
+    let epsilon = tolerance / 100.;
+
+    let a = Operand::new(&mut a, tolerance, epsilon);
+    let b = Operand::new(&mut b, tolerance, epsilon);
+
+    let mut target = Shape::new();
+    let mut copier = Copier::default();
+
+    for (this, other, is_a) in [(&a, &b, true), (&b, &a, false)] {
+        // The inside of the difference is on the outside of `b`.
+        let reverse = op == BooleanOp::Difference && !is_a;
+
+        let keep = |point: Point<3>, normal: Vector<3>| {
+            if other.is_on_surface(&point) {
+                // The point is on the surfaces of both shapes. Those
+                // overlapping parts are taken from `a`, or we'd end up
+                // with duplicate faces.
+                if !is_a {
+                    return false;
+                }
+
+                // Whether the point is on the boundary of the result
+                // depends on what's on either side of the surface.
+                let offset = normal * (epsilon * 10.);
+                let inside = |point: Point<3>| {
+                    op.apply(a.contains(&point), b.contains(&point))
+                };
+                return inside(point + offset)
+                    != inside(point + offset * -Scalar::ONE);
+            }
+
+            op.keeps(is_a, other.contains(&point))
+        };
+
+        for face in &this.faces {
+            let mut pieces = Vec::new();
+            let mut is_cut = false;
+
+            for triangle in &face.triangles {
+                match other.split(triangle) {
+                    Some(split) => {
+                        pieces.push((triangle, split));
+                        is_cut = true;
+                    }
+                    None => pieces.push((triangle, vec![*triangle])),
+                }
+            }
+
+            if !is_cut {
+                // The face is either completely part of the result, or not at
+                // all. Any of its triangles can tell us which.
+                let triangle = match face.triangles.first() {
+                    Some(triangle) => triangle,
+                    None => continue,
+                };
+
+                if keep(centroid(&triangle.points()), normal(triangle)) {
+                    if reverse {
+                        copier.copy_face_reversed(face, &mut target);
+                    } else {
+                        copier.copy_face(&face.face.get(), &mut target);
+                    }
+                }
+
+                continue;
+            }
+
+            // What remains of the face is everything on the side of the other
+            // shape's surface that is kept.
+            let keep_inside = op.keeps(is_a, true);
+            if let Some(trimmed) =
+                other.trim(&face.face.get(), keep_inside, tolerance)
+            {
+                trimmed.add_to(&mut target, reverse);
+                continue;
+            }
+
+            let mut triangles = Vec::new();
+            for (triangle, split) in pieces {
+                for piece in split {
+                    if keep(centroid(&piece.points()), normal(triangle)) {
+                        triangles.push(if reverse {
+                            reverse_triangle(&piece)
+                        } else {
+                            piece
+                        });
+                    }
+                }
+            }
+
+            if !triangles.is_empty() {
+                // Can't fail. Triangle faces don't refer to other objects.
+                target
+                    .topology()
+                    .add_face(Face::Triangles(triangles))
+                    .unwrap();
+            }
+        }
+    }
+
+    target
+}
+
+/// One of the two shapes that a boolean operation combines
+struct Operand {
+    faces: Vec<OperandFace>,
+    triangles: Vec<parry3d_f64::shape::Triangle>,
+    epsilon: Scalar,
+}
+
+impl Operand {
+    fn new(shape: &mut Shape, tolerance: Scalar, epsilon: Scalar) -> Self {
+        // The debug info of the operands would just be noise. What's relevant
+        // is the debug info of the result, once that is triangulated.
+        let mut debug_info = DebugInfo::new();
+
+        let mut faces = Vec::new();
+        for face in shape.topology().faces() {
+            let mut triangles = Vec::new();
+            triangulate_face(
+                &face.get(),
+                tolerance,
+                &mut triangles,
+                &mut debug_info,
+            );
+
+            faces.push(OperandFace { face, triangles });
+        }
+
+        let triangles = faces
+            .iter()
+            .flat_map(|face| &face.triangles)
+            .map(|triangle| triangle.to_parry())
+            .collect();
+
+        Self {
+            faces,
+            triangles,
+            epsilon,
+        }
+    }
+
+    /// Determine whether a point is inside of the operand
+    ///
+    /// Casts rays in multiple directions and counts how often each one
+    /// crosses the surface. Rays that hit an edge or vertex of the triangle
+    /// mesh might be counted wrong, so the majority decides.
+    fn contains(&self, point: &Point<3>) -> bool {
+        // Directions that are unlikely to line up with any edges in the model.
+        let directions = [
+            [0.267_261_2, 0.534_522_5, 0.801_783_7],
+            [-0.624_695_0, 0.156_173_8, 0.765_251_5],
+            [0.371_390_7, -0.742_781_4, -0.557_086_0],
+        ];
+
+        let inside = directions
+            .iter()
+            .filter(|&&direction| {
+                let ray =
+                    Ray::new(point.to_na(), Vector::from(direction).to_na());
+
+                let hits = self
+                    .triangles
+                    .iter()
+                    .filter(|triangle| {
+                        // A ray that starts on the plane of a triangle is
+                        // reported to hit it right away, even if it starts
+                        // outside of the triangle. Points that are actually
+                        // on the surface are handled elsewhere, so we can
+                        // ignore those hits.
+                        triangle
+                            .cast_local_ray(&ray, f64::INFINITY, true)
+                            .map_or(false, |t| t > self.epsilon.into_f64())
+                    })
+                    .count();
+
+                hits % 2 == 1
+            })
+            .count();
+
+        inside > directions.len() / 2
+    }
+
+    /// Determine whether a point is on the surface of the operand
+    fn is_on_surface(&self, point: &Point<3>) -> bool {
+        let point = point.to_na();
+
+        self.triangles.iter().any(|triangle| {
+            triangle.distance_to_local_point(&point, true)
+                < self.epsilon.into_f64()
+        })
+    }
+
+    /// Split a triangle where it intersects the surface of the operand
+    ///
+    /// The triangle is split along the plane of each triangle of the operand
+    /// it intersects. This might split it more than necessary, but guarantees
+    /// that none of the resulting pieces crosses the surface of the operand.
+    ///
+    /// Returns `None`, if the triangle doesn't need to be split.
+    fn split(&self, triangle: &Triangle<3>) -> Option<Vec<Triangle<3>>> {
+        let epsilon = self.epsilon;
+
+        let plane = Plane::from_triangle(triangle);
+        let aabb = triangle
+            .to_parry()
+            .local_aabb()
+            .loosened(epsilon.into_f64());
+
+        let mut pieces = vec![triangle.points().to_vec()];
+        let mut is_split = false;
+
+        for other in &self.triangles {
+            if !aabb.intersects(&other.local_aabb()) {
+                continue;
+            }
+
+            // If the other triangle doesn't at least touch the plane of the
+            // triangle, they can't intersect.
+            let other_points = [other.a, other.b, other.c].map(Point::from);
+            if plane.side(&other_points, epsilon).is_some() {
+                continue;
+            }
+
+            let other_plane = Plane::from_points(other_points);
+
+            let mut next = Vec::new();
+            for piece in pieces {
+                match other_plane.split(&piece, epsilon) {
+                    Some([front, back]) => {
+                        next.push(front);
+                        next.push(back);
+                        is_split = true;
+                    }
+                    None => next.push(piece),
+                }
+            }
+            pieces = next;
+        }
+
+        if !is_split {
+            return None;
+        }
+
+        // All pieces are convex polygons, so we can triangulate them as fans.
+        // Slivers that don't span any area are left out.
+        let mut triangles = Vec::new();
+        for piece in pieces {
+            for i in 1..piece.len() - 1 {
+                let points = [piece[0], piece[i], piece[i + 1]];
+
+                let [a, b, c] = points;
+                if (b - a).cross(&(c - a)).magnitude() <= epsilon * epsilon {
+                    continue;
+                }
+
+                let mut piece = Triangle::from_points(points);
+                piece.set_color(triangle.color());
+                triangles.push(piece);
+            }
+        }
+
+        Some(triangles)
+    }
+
+    /// Trim a planar face along the surface of the operand
+    ///
+    /// Keeps the part of the face that is inside of the operand, if
+    /// `keep_inside` is `true`, the part outside of it otherwise.
+    ///
+    /// Returns `None`, if the face isn't planar, or if the surface of the
+    /// operand touches the plane of the face in a way that the cross section
+    /// can't be computed.
+    fn trim(
+        &self,
+        face: &Face,
+        keep_inside: bool,
+        tolerance: Scalar,
+    ) -> Option<Trimmed> {
+        let (surface, color) = match face {
+            Face::Face { surface, color, .. } => (*surface.get(), *color),
+            Face::Triangles(_) => return None,
+        };
+        let frame = Frame::from_surface(&surface)?;
+
+        let section = self.section(&frame)?;
+        let op = if keep_inside {
+            BooleanOp::Intersection
+        } else {
+            BooleanOp::Difference
+        };
+        let faces =
+            boolean_2d(frame.face_to_2d(face), section, op, tolerance, color);
+
+        Some(Trimmed {
+            faces,
+            frame,
+            surface,
+        })
+    }
+
+    /// Compute the cross section of the operand in a plane
+    ///
+    /// Returns a 2-dimensional shape in the coordinates of the plane, which is
+    /// bounded by the intersection of the plane with the operand's surface.
+    /// Returns `None`, if the operand's surface lies in the plane, or touches
+    /// it such that the intersection doesn't form simple cycles.
+    fn section(&self, frame: &Frame) -> Option<Shape> {
+        let epsilon = self.epsilon;
+
+        // Points closer than `epsilon` to each other are merged.
+        let mut points: Vec<Point<2>> = Vec::new();
+        let mut snap = |point: Point<2>| {
+            let existing = points
+                .iter()
+                .copied()
+                .find(|&existing| (existing - point).magnitude() < epsilon);
+
+            existing.unwrap_or_else(|| {
+                points.push(point);
+                point
+            })
+        };
+
+        // Segments are counted, and cancel each other out in pairs. This
+        // works for the containment tests that the 2-dimensional boolean
+        // operation does, which only care whether a ray crosses the boundary
+        // an odd number of times.
+        let mut segments: HashMap<[Point<2>; 2], usize> = HashMap::new();
+
+        for triangle in &self.triangles {
+            let points = [triangle.a, triangle.b, triangle.c].map(Point::from);
+            let distances = points.map(|point| frame.distance(&point));
+            let on_plane = distances.map(|distance| distance.abs() <= epsilon);
+
+            if on_plane.iter().all(|&on_plane| on_plane) {
+                return None;
+            }
+
+            let mut crossing = Vec::new();
+            for i in 0..3 {
+                let j = (i + 1) % 3;
+
+                if on_plane[i] {
+                    crossing.push(points[i]);
+                } else if !on_plane[j]
+                    && (distances[i] > Scalar::ZERO)
+                        != (distances[j] > Scalar::ZERO)
+                {
+                    let [p, q] = [points[i], points[j]];
+                    let [d_p, d_q] = [distances[i], distances[j]];
+                    crossing.push(p + (q - p) * (d_p / (d_p - d_q)));
+                }
+            }
+
+            // Otherwise, the triangle touches the plane at a single vertex, or
+            // not at all.
+            if crossing.len() != 2 {
+                continue;
+            }
+
+            // An edge in the plane is shared with a neighboring triangle. It
+            // is on the surface's intersection with the plane, if the
+            // triangles are on different sides of the plane. Only counting the
+            // edge for triangles in front of the plane makes sure it's counted
+            // exactly once then, and never or twice otherwise.
+            if on_plane.iter().filter(|&&on_plane| on_plane).count() == 2 {
+                let is_in_front = distances.iter().zip(on_plane).any(
+                    |(&distance, on_plane)| {
+                        !on_plane && distance > Scalar::ZERO
+                    },
+                );
+                if !is_in_front {
+                    continue;
+                }
+            }
+
+            let mut segment = [crossing[0], crossing[1]]
+                .map(|point| snap(frame.local(point)));
+            if segment[0] == segment[1] {
+                continue;
+            }
+
+            segment.sort();
+            *segments.entry(segment).or_default() += 1;
+        }
+
+        let segments: Vec<_> = segments
+            .into_iter()
+            .filter(|&(_, count)| count % 2 == 1)
+            .map(|(segment, _)| segment)
+            .collect();
+
+        let mut section = Shape::new();
+        let mut cycles = Vec::new();
+        for polygon in polygons(&segments)? {
+            let vertices: Vec<_> = polygon
+                .into_iter()
+                .map(|point| {
+                    let point = section.geometry().add_point(point.to_xyz());
+                    section.topology().add_vertex(Vertex { point }).ok()
+                })
+                .collect::<Option<_>>()?;
+
+            let edges = (0..vertices.len())
+                .map(|i| {
+                    let j = (i + 1) % vertices.len();
+                    section
+                        .topology()
+                        .add_line_segment([
+                            vertices[i].clone(),
+                            vertices[j].clone(),
+                        ])
+                        .unwrap()
+                })
+                .collect();
+
+            // Can't fail. All edges have just been added to the shape.
+            cycles.push(section.topology().add_cycle(Cycle { edges }).unwrap());
+        }
+
+        if !cycles.is_empty() {
+            let surface = section.geometry().add_surface(Surface::x_y_plane());
+
+            // Can't fail. The surface and cycles have just been added.
+            section
+                .topology()
+                .add_face(Face::Face {
+                    surface,
+                    cycles,
+                    color: [255, 0, 0, 255],
+                })
+                .unwrap();
+        }
+
+        Some(section)
+    }
+}
+
+/// What remains of a planar face, after trimming it
+struct Trimmed {
+    /// The remaining faces, in the coordinates of the plane
+    faces: Shape,
+
+    frame: Frame,
+    surface: Surface,
+}
+
+impl Trimmed {
+    /// Add the remaining faces to a shape
+    fn add_to(mut self, target: &mut Shape, reverse: bool) {
+        let surface = if reverse {
+            // Can't fail. Planes can always be reversed.
+            reverse_surface(self.surface).unwrap()
+        } else {
+            self.surface
+        };
+        let surface = target.geometry().add_surface(surface);
+
+        let mut vertices = HashMap::new();
+
+        let faces: Vec<_> = self.faces.topology().faces().values().collect();
+        for face in faces {
+            let color = match &face {
+                Face::Face { color, .. } => *color,
+                Face::Triangles(_) => continue,
+            };
+
+            let cycles = face
+                .cycles()
+                .map(|cycle| {
+                    let edges = cycle
+                        .edges
+                        .iter()
+                        .map(|edge| {
+                            self.add_edge(&edge.get(), target, &mut vertices)
+                        })
+                        .collect();
+
+                    // Can't fail. All edges have just been added.
+                    target.topology().add_cycle(Cycle { edges }).unwrap()
+                })
+                .collect();
+
+            // Can't fail. The surface and cycles have just been added.
+            target
+                .topology()
+                .add_face(Face::Face {
+                    surface: surface.clone(),
+                    cycles,
+                    color,
+                })
+                .unwrap();
+        }
+    }
+
+    fn add_edge(
+        &self,
+        edge: &Edge,
+        target: &mut Shape,
+        vertices: &mut HashMap<Handle<Vertex>, Handle<Vertex>>,
+    ) -> Handle<Edge> {
+        let frame = &self.frame;
+
+        let edge_vertices = edge.vertices.clone().map(|edge_vertices| {
+            edge_vertices.map(|vertex| {
+                vertices
+                    .entry(vertex.clone())
+                    .or_insert_with(|| {
+                        let point = frame.global(xy(vertex.get().point()));
+                        let point = target.geometry().add_point(point);
+                        add_or_reuse_vertex(target, point)
+                    })
+                    .clone()
+            })
+        });
+
+        let curve = match (edge.curve(), &edge_vertices) {
+            (Curve::Line(_), Some(edge_vertices)) => {
+                Curve::Line(Line::from_points(
+                    edge_vertices.clone().map(|vertex| vertex.get().point()),
+                ))
+            }
+            (Curve::Line(line), None) => Curve::Line(Line {
+                origin: frame.global(xy(line.origin)),
+                direction: frame.global_vector(xy_vector(line.direction)),
+            }),
+            (Curve::Circle(circle), _) => Curve::Circle(Circle {
+                center: frame.global(xy(circle.center)),
+                radius: frame.global_vector(xy_vector(circle.radius)),
+                normal: frame.normal,
+            }),
+        };
+        let curve = target.geometry().add_curve(curve);
+
+        // Can't fail. The curve and vertices have just been added.
+        target
+            .topology()
+            .add_edge(Edge {
+                curve,
+                vertices: edge_vertices,
+            })
+            .unwrap()
+    }
+}
+
+/// The coordinate system of a plane
+///
+/// Converts between the global coordinates of points in the plane, and their
+/// 2-dimensional coordinates within the plane.
+struct Frame {
+    origin: Point<3>,
+    u: Vector<3>,
+    v: Vector<3>,
+    normal: Vector<3>,
+}
+
+impl Frame {
+    /// Create the coordinate system of a surface, if it is a plane
+    ///
+    /// The normal of the coordinate system is the normal of the surface.
+    fn from_surface(surface: &Surface) -> Option<Self> {
+        match surface {
+            Surface::SweptCurve(SweptCurve {
+                curve: Curve::Line(line),
+                path,
+            }) => {
+                let u = line.direction.normalize();
+                let normal = line.direction.cross(path).normalize();
+                let v = normal.cross(&u);
+
+                Some(Self {
+                    origin: line.origin,
+                    u,
+                    v,
+                    normal,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn distance(&self, point: &Point<3>) -> Scalar {
+        (*point - self.origin).dot(&self.normal)
+    }
+
+    fn local(&self, point: Point<3>) -> Point<2> {
+        Point::origin() + self.local_vector(point - self.origin)
+    }
+
+    fn local_vector(&self, vector: Vector<3>) -> Vector<2> {
+        Vector::from([vector.dot(&self.u), vector.dot(&self.v)])
+    }
+
+    fn global(&self, point: Point<2>) -> Point<3> {
+        self.origin + self.global_vector(point.coords)
+    }
+
+    fn global_vector(&self, vector: Vector<2>) -> Vector<3> {
+        self.u * vector.u + self.v * vector.v
+    }
+
+    /// Convert a face in the plane into a 2-dimensional shape
+    fn face_to_2d(&self, face: &Face) -> Shape {
+        let mut shape = Shape::new();
+        let mut vertices = HashMap::new();
+
+        let cycles = face
+            .cycles()
+            .map(|cycle| {
+                let edges = cycle
+                    .edges
+                    .iter()
+                    .map(|edge| {
+                        self.edge_to_2d(&edge.get(), &mut shape, &mut vertices)
+                    })
+                    .collect();
+
+                // Can't fail. All edges have just been added to the shape.
+                shape.topology().add_cycle(Cycle { edges }).unwrap()
+            })
+            .collect();
+
+        let surface = shape.geometry().add_surface(Surface::x_y_plane());
+
+        // Can't fail. The surface and cycles have just been added.
+        shape
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles,
+                color: [255, 0, 0, 255],
+            })
+            .unwrap();
+
+        shape
+    }
+
+    fn edge_to_2d(
+        &self,
+        edge: &Edge,
+        shape: &mut Shape,
+        vertices: &mut HashMap<Handle<Vertex>, Handle<Vertex>>,
+    ) -> Handle<Edge> {
+        let mut edge_vertices = edge.vertices.clone();
+
+        let curve = match edge.curve() {
+            Curve::Line(line) => Curve::Line(Line {
+                origin: self.local(line.origin).to_xyz(),
+                direction: self.local_vector(line.direction).to_xyz(),
+            }),
+            Curve::Circle(circle) => {
+                // Arcs go counter-clockwise around the normal of their circle,
+                // from their first vertex. In the plane, they go
+                // counter-clockwise around its normal.
+                if circle.normal.dot(&self.normal) < Scalar::ZERO {
+                    if let Some(edge_vertices) = &mut edge_vertices {
+                        edge_vertices.reverse();
+                    }
+                }
+                let start = edge_vertices
+                    .as_ref()
+                    .map(|[start, _]| start.get().point())
+                    .unwrap_or(circle.center + circle.radius);
+
+                Curve::Circle(Circle {
+                    center: self.local(circle.center).to_xyz(),
+                    radius: self.local_vector(start - circle.center).to_xyz(),
+                    normal: Vector::unit_z(),
+                })
+            }
+        };
+        let curve = shape.geometry().add_curve(curve);
+
+        let edge_vertices = edge_vertices.map(|edge_vertices| {
+            edge_vertices.map(|vertex| {
+                vertices
+                    .entry(vertex.clone())
+                    .or_insert_with(|| {
+                        let point = self.local(vertex.get().point());
+                        let point = shape.geometry().add_point(point.to_xyz());
+                        add_or_reuse_vertex(shape, point)
+                    })
+                    .clone()
+            })
+        });
+
+        // Can't fail. The curve and vertices have just been added.
+        shape
+            .topology()
+            .add_edge(Edge {
+                curve,
+                vertices: edge_vertices,
+            })
+            .unwrap()
+    }
+}
+
+/// Assemble line segments into closed polygons
+///
+/// Returns `None`, if the segments don't form simple cycles, meaning that
+/// some point is not the endpoint of exactly two segments.
+fn polygons(segments: &[[Point<2>; 2]]) -> Option<Vec<Vec<Point<2>>>> {
+    let mut adjacent: HashMap<Point<2>, Vec<usize>> = HashMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        for point in segment {
+            adjacent.entry(*point).or_default().push(i);
+        }
+    }
+    if adjacent.values().any(|segments| segments.len() != 2) {
+        return None;
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut polygons = Vec::new();
+
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+
+        let mut polygon = Vec::new();
+        let [start, mut point] = segments[first];
+        let mut current = first;
+        used[first] = true;
+        polygon.push(start);
+
+        while point != start {
+            polygon.push(point);
+
+            // Can't panic. Every point is shared by exactly two segments.
+            current = adjacent[&point]
+                .iter()
+                .copied()
+                .find(|&i| i != current)
+                .unwrap();
+            used[current] = true;
+
+            let [a, b] = segments[current];
+            point = if a == point { b } else { a };
+        }
+
+        polygons.push(without_collinear_points(polygon));
+    }
+
+    Some(polygons)
+}
+
+/// Remove the points of a polygon that are between collinear edges
+///
+/// The cross section of a planar face consists of one segment for each
+/// triangle of its approximation. Those need to be merged again.
+fn without_collinear_points(polygon: Vec<Point<2>>) -> Vec<Point<2>> {
+    let n = polygon.len();
+
+    let is_collinear = |i: usize| {
+        let [a, b, c] =
+            [polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]];
+        let [ab, bc] = [b - a, c - b];
+
+        let cross = ab.u * bc.v - ab.v * bc.u;
+        cross.abs() <= Scalar::from_f64(1e-9) * ab.magnitude() * bc.magnitude()
+            && ab.dot(&bc) > Scalar::ZERO
+    };
+
+    let keep: Vec<_> = (0..n).map(|i| !is_collinear(i)).collect();
+    polygon
+        .iter()
+        .zip(keep)
+        .filter(|&(_, keep)| keep)
+        .map(|(&point, _)| point)
+        .collect()
+}
+
+/// A face of an [`Operand`], along with its triangle approximation
+struct OperandFace {
+    face: Handle<Face>,
+    triangles: Vec<Triangle<3>>,
+}
+
+/// A plane, defined by a point on it and its normal
+struct Plane {
+    origin: Point<3>,
+    normal: Vector<3>,
+}
+
+impl Plane {
+    fn from_triangle(triangle: &Triangle<3>) -> Self {
+        Self::from_points(triangle.points())
+    }
+
+    fn from_points([a, b, c]: [Point<3>; 3]) -> Self {
+        Self {
+            origin: a,
+            normal: (b - a).cross(&(c - a)).normalize(),
+        }
+    }
+
+    fn distance(&self, point: &Point<3>) -> Scalar {
+        (*point - self.origin).dot(&self.normal)
+    }
+
+    /// Determine on which side of the plane all the points are
+    ///
+    /// Returns `None`, if the points are on both sides, or on the plane.
+    fn side(&self, points: &[Point<3>], epsilon: Scalar) -> Option<bool> {
+        let distances = points.iter().map(|point| self.distance(point));
+
+        let mut front = false;
+        let mut back = false;
+        for distance in distances {
+            front |= distance > epsilon;
+            back |= distance < -epsilon;
+
+            if distance.abs() <= epsilon {
+                return None;
+            }
+        }
+
+        match (front, back) {
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Split a convex polygon along the plane
+    ///
+    /// Returns `None`, if the polygon is not on both sides of the plane.
+    fn split(
+        &self,
+        polygon: &[Point<3>],
+        epsilon: Scalar,
+    ) -> Option<[Vec<Point<3>>; 2]> {
+        let distances: Vec<_> =
+            polygon.iter().map(|point| self.distance(point)).collect();
+
+        let is_in_front = distances.iter().any(|&d| d > epsilon);
+        let is_behind = distances.iter().any(|&d| d < -epsilon);
+        if !is_in_front || !is_behind {
+            return None;
+        }
+
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+
+        for i in 0..polygon.len() {
+            let j = (i + 1) % polygon.len();
+
+            let (p, d_p) = (polygon[i], distances[i]);
+            let (q, d_q) = (polygon[j], distances[j]);
+
+            if d_p >= -epsilon {
+                front.push(p);
+            }
+            if d_p <= epsilon {
+                back.push(p);
+            }
+
+            let crosses = (d_p > epsilon && d_q < -epsilon)
+                || (d_p < -epsilon && d_q > epsilon);
+            if crosses {
+                let intersection = p + (q - p) * (d_p / (d_p - d_q));
+
+                front.push(intersection);
+                back.push(intersection);
+            }
+        }
+
+        Some([front, back])
+    }
+}
+
+/// Copies faces, and all objects they refer to, into another shape
+///
+/// Objects that are referred to by multiple faces are only copied once.
+#[derive(Default)]
+struct Copier {
+    points: HashMap<Handle<Point<3>>, Handle<Point<3>>>,
+    curves: HashMap<Handle<Curve>, Handle<Curve>>,
+    surfaces: HashMap<Handle<Surface>, Handle<Surface>>,
+
+    vertices: HashMap<Handle<Vertex>, Handle<Vertex>>,
+    edges: HashMap<Handle<Edge>, Handle<Edge>>,
+    cycles: HashMap<Handle<Cycle>, Handle<Cycle>>,
+}
+
+impl Copier {
+    fn copy_face(&mut self, face: &Face, target: &mut Shape) {
+        let face = match face {
+            Face::Face {
+                surface,
+                cycles,
+                color,
+            } => {
+                let surface = self
+                    .surfaces
+                    .entry(surface.clone())
+                    .or_insert_with(|| {
+                        target.geometry().add_surface(*surface.get())
+                    })
+                    .clone();
+                let cycles = cycles
+                    .iter()
+                    .map(|cycle| self.copy_cycle(cycle, target))
+                    .collect();
+
+                Face::Face {
+                    surface,
+                    cycles,
+                    color: *color,
+                }
+            }
+            face @ Face::Triangles(_) => face.clone(),
+        };
+
+        // Can't fail. All objects the face refers to have just been copied.
+        target.topology().add_face(face).unwrap();
+    }
+
+    /// Copy a face, but with the opposite orientation
+    ///
+    /// Falls back to the face's triangles, if its surface can't be reversed.
+    fn copy_face_reversed(&mut self, face: &OperandFace, target: &mut Shape) {
+        let face = match &*face.face.get() {
+            Face::Face {
+                surface,
+                cycles,
+                color,
+            } => match reverse_surface(*surface.get()) {
+                Some(surface) => {
+                    let surface = target.geometry().add_surface(surface);
+                    let cycles = cycles
+                        .iter()
+                        .map(|cycle| self.copy_cycle(cycle, target))
+                        .collect();
+
+                    Face::Face {
+                        surface,
+                        cycles,
+                        color: *color,
+                    }
+                }
+                None => Face::Triangles(
+                    face.triangles.iter().map(reverse_triangle).collect(),
+                ),
+            },
+            Face::Triangles(triangles) => Face::Triangles(
+                triangles.iter().map(reverse_triangle).collect(),
+            ),
+        };
+
+        // Can't fail. All objects the face refers to have just been copied.
+        target.topology().add_face(face).unwrap();
+    }
+
+    fn copy_cycle(
+        &mut self,
+        cycle: &Handle<Cycle>,
+        target: &mut Shape,
+    ) -> Handle<Cycle> {
+        if let Some(copy) = self.cycles.get(cycle) {
+            return copy.clone();
+        }
+
+        let edges = cycle
+            .get()
+            .edges
+            .iter()
+            .map(|edge| self.copy_edge(edge, target))
+            .collect();
+
+        // Can't fail. All edges have just been copied.
+        let copy = target.topology().add_cycle(Cycle { edges }).unwrap();
+        self.cycles.insert(cycle.clone(), copy.clone());
+
+        copy
+    }
+
+    fn copy_edge(
+        &mut self,
+        edge: &Handle<Edge>,
+        target: &mut Shape,
+    ) -> Handle<Edge> {
+        if let Some(copy) = self.edges.get(edge) {
+            return copy.clone();
+        }
+
+        let curve = edge.get().curve.clone();
+        let curve = self
+            .curves
+            .entry(curve.clone())
+            .or_insert_with(|| target.geometry().add_curve(*curve.get()))
+            .clone();
+        let vertices = edge.get().vertices.clone().map(|vertices| {
+            vertices.map(|vertex| self.copy_vertex(&vertex, target))
+        });
+
+        // Can't fail. The curve and vertices have just been copied.
+        let copy = target
+            .topology()
+            .add_edge(Edge { curve, vertices })
+            .unwrap();
+        self.edges.insert(edge.clone(), copy.clone());
+
+        copy
+    }
+
+    fn copy_vertex(
+        &mut self,
+        vertex: &Handle<Vertex>,
+        target: &mut Shape,
+    ) -> Handle<Vertex> {
+        if let Some(copy) = self.vertices.get(vertex) {
+            return copy.clone();
+        }
+
+        let point = vertex.get().point.clone();
+        let point = self
+            .points
+            .entry(point.clone())
+            .or_insert_with(|| target.geometry().add_point(*point.get()))
+            .clone();
+
+        let copy = add_or_reuse_vertex(target, point);
+        self.vertices.insert(vertex.clone(), copy.clone());
+
+        copy
+    }
+}
+
+/// Add a vertex to a shape, or reuse the vertex that is already there
+///
+/// Both operands of a boolean operation might have a vertex at the same point.
+/// The shape doesn't accept a second vertex there, so the one that has already
+/// been added is used instead.
+pub(super) fn add_or_reuse_vertex(
+    target: &mut Shape,
+    point: Handle<Point<3>>,
+) -> Handle<Vertex> {
+    match target.topology().add_vertex(Vertex {
+        point: point.clone(),
+    }) {
+        Ok(vertex) => vertex,
+        Err(ValidationError::Uniqueness) => {
+            let point = *point.get();
+            target
+                .topology()
+                .vertices()
+                .min_by_key(|vertex| (vertex.get().point() - point).magnitude())
+                .expect("Vertex not unique, but no other vertex found")
+        }
+        Err(err) => {
+            panic!("Failed to add vertex: {:?}", err);
+        }
+    }
+}
+
+/// Reverse a surface, so its normal points the other way
+///
+/// Returns `None`, if the surface can't be reversed without changing the range
+/// of its surface coordinates. Faces on those surfaces might not be
+/// triangulated correctly anymore.
+fn reverse_surface(surface: Surface) -> Option<Surface> {
+    match surface {
+        Surface::SweptCurve(SweptCurve { curve, path }) => {
+            Some(Surface::SweptCurve(SweptCurve {
+                curve,
+                path: path * -Scalar::ONE,
+            }))
+        }
+        Surface::RevolvedCurve(RevolvedCurve {
+            curve: Curve::Line(Line { origin, direction }),
+            axis,
+        }) => Some(Surface::RevolvedCurve(RevolvedCurve {
+            curve: Curve::Line(Line {
+                origin,
+                direction: direction * -Scalar::ONE,
+            }),
+            axis,
+        })),
+        // Reversing a circle would move where its coordinates wrap around.
+        Surface::RevolvedCurve(_) => None,
+    }
+}
+
+fn xy(point: Point<3>) -> Point<2> {
+    Point::from([point.x, point.y])
+}
+
+fn xy_vector(vector: Vector<3>) -> Vector<2> {
+    Vector::from([vector.x, vector.y])
+}
+
+fn reverse_triangle(triangle: &Triangle<3>) -> Triangle<3> {
+    let [a, b, c] = triangle.points();
+
+    let mut reversed = Triangle::from_points([a, c, b]);
+    reversed.set_color(triangle.color());
+    reversed
+}
+
+fn centroid(points: &[Point<3>]) -> Point<3> {
+    let sum = points
+        .iter()
+        .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords);
+
+    Point::origin() + sum / Scalar::from_u64(points.len() as u64)
+}
+
+fn normal(triangle: &Triangle<3>) -> Vector<3> {
+    Plane::from_triangle(triangle).normal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::{Scalar, Vector};
+    use parry3d_f64::query::PointQuery as _;
+
+    use crate::{
+        algorithms::{
+            sweep_shape,
+            test_helpers::{
+                assert_area, assert_area_approx, circle, cuboid, tolerance,
+                translated, triangles,
+            },
+        },
+        shape::Shape,
+        topology::Face,
+    };
+
+    use super::{boolean, centroid, normal, BooleanOp};
+
+    #[test]
+    fn overlapping() {
+        let a = || cuboid([0., 0., 0.], [1., 1., 1.]);
+        let b = || cuboid([0.5, 0.5, 0.5], [1.5, 1.5, 1.5]);
+
+        let union = boolean(a(), b(), BooleanOp::Union, tolerance());
+        assert_area(union, 10.5);
+
+        let intersection =
+            boolean(a(), b(), BooleanOp::Intersection, tolerance());
+        assert_area(intersection, 1.5);
+
+        let mut difference =
+            boolean(a(), b(), BooleanOp::Difference, tolerance());
+
+        // The faces that don't intersect `b` are preserved, the others are
+        // trimmed. All of them are b-rep faces.
+        let faces: Vec<_> = difference.topology().faces().values().collect();
+        assert_eq!(faces.len(), 9);
+        assert!(faces.iter().all(|face| matches!(face, Face::Face { .. })));
+        assert!(difference.validate().is_ok());
+
+        assert_area(difference, 6.);
+
+        let difference = boolean(a(), b(), BooleanOp::Difference, tolerance());
+        assert_reversed(b(), difference, [0.5, 0.5, 0.5], [1.5, 1.5, 1.5]);
+    }
+
+    #[test]
+    fn cavity() {
+        // `b` is completely inside of `a`, so none of its faces are cut.
+        let a = cuboid([0., 0., 0.], [3., 3., 3.]);
+        let b = || cuboid([1., 1., 1.], [2., 2., 2.]);
+
+        let mut difference =
+            boolean(a, b(), BooleanOp::Difference, tolerance());
+        let faces = difference
+            .topology()
+            .faces()
+            .filter(|face| matches!(&*face.get(), Face::Face { .. }))
+            .count();
+        assert_eq!(faces, 12);
+
+        assert_reversed(b(), difference, [1., 1., 1.], [2., 2., 2.]);
+    }
+
+    #[test]
+    fn disjoint() {
+        let a = || cuboid([0., 0., 0.], [1., 1., 1.]);
+        let b = || cuboid([2., 0., 0.], [3., 1., 1.]);
+
+        let mut union = boolean(a(), b(), BooleanOp::Union, tolerance());
+        assert_eq!(union.topology().faces().count(), 12);
+        assert_eq!(union.topology().vertices().count(), 16);
+        assert!(union.validate().is_ok());
+
+        let mut intersection =
+            boolean(a(), b(), BooleanOp::Intersection, tolerance());
+        assert_eq!(intersection.topology().faces().count(), 0);
+
+        let difference = boolean(a(), b(), BooleanOp::Difference, tolerance());
+        assert_area(difference, 6.);
+    }
+
+    #[test]
+    fn coplanar() {
+        // Two cuboids that touch along a face.
+        let a = cuboid([0., 0., 0.], [1., 1., 1.]);
+        let b = cuboid([0., 0., 1.], [1., 1., 2.]);
+
+        let mut union = boolean(a, b, BooleanOp::Union, tolerance());
+        assert_eq!(union.topology().vertices().count(), 12);
+        assert_area(union, 10.);
+
+        // A hole through a plate, with the cutter exactly as high as the
+        // plate.
+        let plate = cuboid([0., 0., 0.], [2., 2., 1.]);
+        let cutter = cuboid([0.5, 0.5, 0.], [1.5, 1.5, 1.]);
+
+        let difference =
+            boolean(plate, cutter, BooleanOp::Difference, tolerance());
+        assert_area(difference, 18.);
+    }
+
+    #[test]
+    fn curved() {
+        // A round hole through a plate.
+        let plate = cuboid([0., 0., 0.], [2., 2., 1.]);
+        let cutter = sweep_shape(
+            circle([1., 1.], 0.5),
+            Vector::from([0., 0., 3.]),
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+        let cutter = translated(cutter, [0., 0., -1.]);
+
+        let mut difference =
+            boolean(plate, cutter, BooleanOp::Difference, tolerance());
+
+        // The planar faces of the plate are preserved or trimmed. The wall of
+        // the hole is on a curved surface, and represented as triangles.
+        let faces: Vec<_> = difference.topology().faces().values().collect();
+        let b_rep = faces
+            .iter()
+            .filter(|face| matches!(face, Face::Face { .. }))
+            .count();
+        assert_eq!(b_rep, 6);
+        assert_eq!(faces.len(), 7);
+        assert!(difference.validate().is_ok());
+
+        // The hole is approximated by a polygon.
+        assert_area_approx(difference, 16. + PI / 2., 0.01);
+    }
+
+    /// Assert that the faces of the difference that come from `b` face the
+    /// other way than they do in `b`
+    ///
+    /// The faces that come from `b` are identified by being within the
+    /// bounding box of `b`, given by `min` and `max`.
+    fn assert_reversed(
+        b: Shape,
+        difference: Shape,
+        min: [f64; 3],
+        max: [f64; 3],
+    ) {
+        let triangles_b = triangles(b);
+
+        let mut checked = 0;
+        for triangle in triangles(difference) {
+            let center = centroid(&triangle.points());
+
+            let is_within_b = (0..3).all(|i| {
+                let coord = center.coords.components[i].into_f64();
+                coord > min[i] - 1e-9 && coord < max[i] + 1e-9
+            });
+            if !is_within_b {
+                continue;
+            }
+
+            let original = triangles_b
+                .iter()
+                .find(|original| {
+                    original
+                        .to_parry()
+                        .distance_to_local_point(&center.to_na(), true)
+                        < 1e-9
+                })
+                .expect("Triangle within `b` is not on the surface of `b`");
+
+            assert!(
+                normal(&triangle).dot(&normal(original)) < Scalar::ZERO,
+                "Face of `b` has not been reversed: {:?}",
+                triangle.points()
+            );
+            checked += 1;
+        }
+
+        assert!(checked > 0);
+    }
+}
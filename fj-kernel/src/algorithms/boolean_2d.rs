@@ -0,0 +1,963 @@
+use std::collections::HashMap;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{Circle, Curve, Surface},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::boolean::{add_or_reuse_vertex, BooleanOp};
+
+/// Combine two 2-dimensional shapes using a boolean operation
+///
+/// Both shapes must consist of faces in the x-y plane, bounded by line
+/// segments, arcs, and circles. The edges of each shape are split wherever
+/// they intersect the edges of the other shape. The pieces that bound the
+/// result are then assembled into new cycles, and those into faces.
+///
+/// The result can consist of multiple faces, each of which can have holes. All
+/// of them get the provided color.
+pub fn boolean_2d(
+    mut a: Shape,
+    mut b: Shape,
+    op: BooleanOp,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Shape {
+    // Points closer than this to each other are considered identical.
+    let epsilon = tolerance / 100.;
+
+    let mut points = Points::new(epsilon);
+    let a = boundary(&mut a, &mut points);
+    let b = boundary(&mut b, &mut points);
+
+    let mut pieces = Vec::new();
+
+    for (this, other, is_a) in [(&a, &b, true), (&b, &a, false)] {
+        let keep = |segment: &Segment| {
+            let (point, normal) = segment.midpoint_and_normal();
+
+            if is_on_boundary(other, point, epsilon) {
+                // The piece is on the boundaries of both shapes. Those
+                // overlapping parts are taken from `a`, or we'd end up with
+                // duplicate edges.
+                if !is_a {
+                    return false;
+                }
+
+                // Whether the piece is on the boundary of the result depends
+                // on what's on either side of it.
+                let offset = normal * (epsilon * 10.);
+                let inside = |point: Point<2>| {
+                    op.apply(contains(&a, point), contains(&b, point))
+                };
+                return inside(point + offset)
+                    != inside(point + offset * -Scalar::ONE);
+            }
+
+            op.keeps(is_a, contains(other, point))
+        };
+
+        for segment in this {
+            let mut split_points = Vec::new();
+            for other_segment in other {
+                for point in segment.intersections(other_segment, epsilon) {
+                    split_points.push(points.snap(point));
+                }
+            }
+
+            for piece in segment.split(split_points, &mut points) {
+                if keep(&piece) {
+                    pieces.push(piece);
+                }
+            }
+        }
+    }
+
+    let cycles = assemble(pieces);
+
+    // Cycles don't cross each other, so each cycle is either an outer
+    // boundary, or a hole. Which one it is depends on how many other cycles it
+    // is nested in.
+    let samples: Vec<_> = cycles
+        .iter()
+        .map(|cycle| cycle[0].midpoint_and_normal().0)
+        .collect();
+    let containers: Vec<Vec<_>> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| {
+            (0..cycles.len())
+                .filter(|&j| j != i && contains(&cycles[j], sample))
+                .collect()
+        })
+        .collect();
+
+    let mut target = Shape::new();
+    let mut vertices = HashMap::new();
+
+    let handles: Vec<_> = cycles
+        .iter()
+        .map(|cycle| {
+            let edges = cycle
+                .iter()
+                .map(|segment| segment.add_to(&mut target, &mut vertices))
+                .collect();
+
+            // Can't fail. All edges have just been added to the shape.
+            target.topology().add_cycle(Cycle { edges }).unwrap()
+        })
+        .collect();
+
+    let surface = target.geometry().add_surface(Surface::x_y_plane());
+
+    for (i, outer) in handles.iter().enumerate() {
+        let depth = containers[i].len();
+        if depth % 2 != 0 {
+            continue;
+        }
+
+        let mut cycles = vec![outer.clone()];
+        for (j, hole) in handles.iter().enumerate() {
+            if containers[j].len() == depth + 1 && containers[j].contains(&i) {
+                cycles.push(hole.clone());
+            }
+        }
+
+        // Can't fail. All cycles have just been added to the shape.
+        target
+            .topology()
+            .add_face(Face::Face {
+                cycles,
+                surface: surface.clone(),
+                color,
+            })
+            .unwrap();
+    }
+
+    target
+}
+
+/// A piece of the boundary of a 2-dimensional shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Segment {
+    /// A straight line
+    Line { start: Point<2>, end: Point<2> },
+
+    /// An arc, going counter-clockwise from `start` to `end`
+    Arc {
+        center: Point<2>,
+        radius: Scalar,
+        start: Point<2>,
+        end: Point<2>,
+    },
+
+    /// A full circle
+    Circle { center: Point<2>, radius: Scalar },
+}
+
+impl Segment {
+    /// The points that bound the segment, if any
+    fn endpoints(&self) -> Option<[Point<2>; 2]> {
+        match *self {
+            Self::Line { start, end } | Self::Arc { start, end, .. } => {
+                Some([start, end])
+            }
+            Self::Circle { .. } => None,
+        }
+    }
+
+    /// The parameter of a point on the segment
+    ///
+    /// For lines, this is the fraction of the way from the start to the end.
+    /// For arcs and circles, it's the angle from the start, or the positive u
+    /// axis respectively.
+    fn parameter(&self, point: Point<2>) -> Scalar {
+        match *self {
+            Self::Line { start, end } => {
+                let direction = end - start;
+                (point - start).dot(&direction) / direction.dot(&direction)
+            }
+            Self::Arc { center, start, .. } => {
+                sweep(angle(center, start), angle(center, point))
+            }
+            Self::Circle { center, .. } => angle(center, point),
+        }
+    }
+
+    /// The point in the middle of the segment, and the normal there
+    fn midpoint_and_normal(&self) -> (Point<2>, Vector<2>) {
+        let (center, radius, angle) = match *self {
+            Self::Line { start, end } => {
+                let direction = (end - start).normalize();
+                let midpoint = start + (end - start) / Scalar::TWO;
+                let normal = Vector::from([-direction.v, direction.u]);
+
+                return (midpoint, normal);
+            }
+            Self::Arc {
+                center,
+                radius,
+                start,
+                end,
+            } => {
+                let from = angle(center, start);
+                let to = angle(center, end);
+                (center, radius, from + sweep(from, to) / Scalar::TWO)
+            }
+            Self::Circle { center, radius } => (center, radius, Scalar::ZERO),
+        };
+
+        let normal = direction(angle);
+        (center + normal * radius, normal)
+    }
+
+    /// Compute the distance of a point to the segment
+    fn distance(&self, point: Point<2>) -> Scalar {
+        match *self {
+            Self::Line { start, end } => {
+                let t =
+                    self.parameter(point).max(Scalar::ZERO).min(Scalar::ONE);
+                (start + (end - start) * t - point).magnitude()
+            }
+            Self::Arc {
+                center,
+                radius,
+                start,
+                end,
+            } => {
+                let from = angle(center, start);
+                if sweep(from, angle(center, point))
+                    <= sweep(from, angle(center, end))
+                {
+                    ((point - center).magnitude() - radius).abs()
+                } else {
+                    (point - start).magnitude().min((point - end).magnitude())
+                }
+            }
+            Self::Circle { center, radius } => {
+                ((point - center).magnitude() - radius).abs()
+            }
+        }
+    }
+
+    /// Compute the points where the segment intersects another one
+    ///
+    /// If the segments overlap, the endpoints of the overlap are returned.
+    fn intersections(&self, other: &Self, epsilon: Scalar) -> Vec<Point<2>> {
+        let candidates = match (self.carrier(), other.carrier()) {
+            (
+                Carrier::Line { origin, direction },
+                Carrier::Line {
+                    origin: other_origin,
+                    direction: other_direction,
+                },
+            ) => {
+                let denominator = cross(direction, other_direction);
+
+                // How far apart the lines get over the length of the
+                // segments, if they intersect at one end.
+                let [length, other_length] = [direction, other_direction]
+                    .map(|direction| direction.magnitude());
+                let divergence = (denominator / (length * other_length)).abs()
+                    * length.max(other_length);
+
+                if divergence < epsilon {
+                    // The lines are parallel. If they're on top of each
+                    // other, the segments might overlap.
+                    if self.distance_to_carrier(other_origin) < epsilon {
+                        overlap_candidates(self, other)
+                    } else {
+                        Vec::new()
+                    }
+                } else {
+                    let t = cross(other_origin - origin, other_direction)
+                        / denominator;
+                    vec![origin + direction * t]
+                }
+            }
+            (Carrier::Line { origin, direction }, Carrier::Circle(circle)) => {
+                line_circle_intersections(origin, direction, circle, epsilon)
+            }
+            (Carrier::Circle(circle), Carrier::Line { origin, direction }) => {
+                line_circle_intersections(origin, direction, circle, epsilon)
+            }
+            (Carrier::Circle((c1, r1)), Carrier::Circle((c2, r2))) => {
+                let distance = (c2 - c1).magnitude();
+
+                if distance < epsilon {
+                    // The circles are concentric. If they have the same
+                    // radius, the segments might overlap.
+                    if (r1 - r2).abs() < epsilon {
+                        overlap_candidates(self, other)
+                    } else {
+                        Vec::new()
+                    }
+                } else if distance > r1 + r2 + epsilon
+                    || distance < (r1 - r2).abs() - epsilon
+                {
+                    Vec::new()
+                } else {
+                    let direction = (c2 - c1) / distance;
+                    let a = (distance * distance + r1 * r1 - r2 * r2)
+                        / (distance * Scalar::TWO);
+                    let h = (r1 * r1 - a * a).max(Scalar::ZERO).sqrt();
+
+                    let base = c1 + direction * a;
+                    let perpendicular =
+                        Vector::from([-direction.v, direction.u]);
+
+                    if h < epsilon {
+                        vec![base]
+                    } else {
+                        vec![
+                            base + perpendicular * h,
+                            base + perpendicular * -h,
+                        ]
+                    }
+                }
+            }
+        };
+
+        candidates
+            .into_iter()
+            .filter(|&point| {
+                self.distance(point) < epsilon
+                    && other.distance(point) < epsilon
+            })
+            .collect()
+    }
+
+    /// Split the segment at the provided points
+    ///
+    /// All points must be on the segment. A circle that is split at only one
+    /// point is also split at the opposite point, as an arc can't start and
+    /// end at the same point.
+    fn split(
+        &self,
+        mut split_points: Vec<Point<2>>,
+        points: &mut Points,
+    ) -> Vec<Self> {
+        if let Some([start, end]) = self.endpoints() {
+            split_points.retain(|&point| point != start && point != end);
+        }
+        split_points.sort_by_key(|&point| self.parameter(point));
+        split_points.dedup();
+
+        match *self {
+            Self::Line { start, end } => {
+                let mut boundaries = vec![start];
+                boundaries.extend(split_points);
+                boundaries.push(end);
+
+                boundaries
+                    .windows(2)
+                    .map(|window| Self::Line {
+                        start: window[0],
+                        end: window[1],
+                    })
+                    .collect()
+            }
+            Self::Arc {
+                center,
+                radius,
+                start,
+                end,
+            } => {
+                let mut boundaries = vec![start];
+                boundaries.extend(split_points);
+                boundaries.push(end);
+
+                boundaries
+                    .windows(2)
+                    .map(|window| Self::Arc {
+                        center,
+                        radius,
+                        start: window[0],
+                        end: window[1],
+                    })
+                    .collect()
+            }
+            Self::Circle { center, radius } => {
+                if split_points.is_empty() {
+                    return vec![*self];
+                }
+                if split_points.len() == 1 {
+                    let opposite = center + (center - split_points[0]);
+                    split_points.push(points.snap(opposite));
+                    split_points.sort_by_key(|&point| self.parameter(point));
+                }
+
+                (0..split_points.len())
+                    .map(|i| Self::Arc {
+                        center,
+                        radius,
+                        start: split_points[i],
+                        end: split_points[(i + 1) % split_points.len()],
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Count how often a ray, cast from `point` in positive u direction,
+    /// crosses the segment
+    ///
+    /// Arcs and circles are split into parts that are monotone in v, so they
+    /// can be handled just like lines. Points at the same height as the ray
+    /// are considered to be below it. That way, a ray that hits the point
+    /// where two segments or parts meet is counted correctly.
+    fn ray_crossings(&self, point: Point<2>) -> usize {
+        let crosses = |start: Point<2>, end: Point<2>| {
+            (start.v > point.v) != (end.v > point.v)
+        };
+
+        let (center, radius, from, to, [start, end]) = match *self {
+            Self::Line { start, end } => {
+                if !crosses(start, end) {
+                    return 0;
+                }
+                let u = start.u
+                    + (point.v - start.v) * (end.u - start.u)
+                        / (end.v - start.v);
+                return usize::from(u > point.u);
+            }
+            Self::Arc {
+                center,
+                radius,
+                start,
+                end,
+            } => {
+                let from = angle(center, start);
+                (
+                    center,
+                    radius,
+                    from,
+                    sweep(from, angle(center, end)),
+                    [start, end],
+                )
+            }
+            Self::Circle { center, radius } => {
+                let start = center + Vector::from([radius, Scalar::ZERO]);
+                (
+                    center,
+                    radius,
+                    Scalar::ZERO,
+                    Scalar::PI * 2.,
+                    [start, start],
+                )
+            }
+        };
+
+        // The angles at which the arc or circle changes direction along v.
+        let mut offsets = vec![Scalar::ZERO];
+        for turn in [Scalar::PI / 2., Scalar::PI * 1.5] {
+            let offset = sweep(from, turn);
+            if offset > Scalar::ZERO && offset < to {
+                offsets.push(offset);
+            }
+        }
+        offsets.sort();
+        offsets.push(to);
+
+        let point_at = |offset: Scalar| {
+            if offset == Scalar::ZERO {
+                start
+            } else if offset == to {
+                end
+            } else {
+                center + direction(from + offset) * radius
+            }
+        };
+
+        let mut crossings = 0;
+        for window in offsets.windows(2) {
+            if !crosses(point_at(window[0]), point_at(window[1])) {
+                continue;
+            }
+
+            let dv = point.v - center.v;
+            let du = (radius * radius - dv * dv).max(Scalar::ZERO).sqrt();
+
+            let middle = from + (window[0] + window[1]) / Scalar::TWO;
+            let u = if middle.cos() > Scalar::ZERO {
+                center.u + du
+            } else {
+                center.u - du
+            };
+
+            if u > point.u {
+                crossings += 1;
+            }
+        }
+
+        crossings
+    }
+
+    /// Add the segment to a shape, as an edge
+    fn add_to(
+        &self,
+        shape: &mut Shape,
+        vertices: &mut HashMap<Point<2>, Handle<Vertex>>,
+    ) -> Handle<Edge> {
+        let mut vertex = |point: Point<2>| {
+            vertices
+                .entry(point)
+                .or_insert_with(|| {
+                    let point = shape.geometry().add_point(point.to_xyz());
+                    add_or_reuse_vertex(shape, point)
+                })
+                .clone()
+        };
+
+        let (center, radius, vertices) = match *self {
+            Self::Line { start, end } => {
+                let vertices = [vertex(start), vertex(end)];
+
+                // Can't fail. The vertices have just been added to the shape.
+                return shape.topology().add_line_segment(vertices).unwrap();
+            }
+            Self::Arc { center, start, .. } => {
+                // The start of the arc is the origin of the circle's curve
+                // coordinates.
+                let vertices =
+                    self.endpoints().map(|points| points.map(vertex));
+                (center, start - center, vertices)
+            }
+            Self::Circle { center, radius } => {
+                (center, Vector::from([radius, Scalar::ZERO]), None)
+            }
+        };
+
+        let curve = shape.geometry().add_curve(Curve::Circle(Circle {
+            center: center.to_xyz(),
+            radius: radius.to_xyz(),
+            normal: Vector::unit_z(),
+        }));
+
+        // Can't fail. The curve and vertices have just been added to the
+        // shape.
+        shape.topology().add_edge(Edge { curve, vertices }).unwrap()
+    }
+
+    /// The same segment, traversed in the opposite direction
+    ///
+    /// Arcs always go counter-clockwise, so they stay as they are.
+    fn reverse(self) -> Self {
+        match self {
+            Self::Line { start, end } => Self::Line {
+                start: end,
+                end: start,
+            },
+            segment => segment,
+        }
+    }
+
+    /// The line or circle that the segment is a part of
+    fn carrier(&self) -> Carrier {
+        match *self {
+            Self::Line { start, end } => Carrier::Line {
+                origin: start,
+                direction: end - start,
+            },
+            Self::Arc { center, radius, .. }
+            | Self::Circle { center, radius } => {
+                Carrier::Circle((center, radius))
+            }
+        }
+    }
+
+    /// Compute the distance of a point to the line or circle that the segment
+    /// is a part of
+    fn distance_to_carrier(&self, point: Point<2>) -> Scalar {
+        match self.carrier() {
+            Carrier::Line { origin, direction } => {
+                cross(point - origin, direction.normalize()).abs()
+            }
+            Carrier::Circle((center, radius)) => {
+                ((point - center).magnitude() - radius).abs()
+            }
+        }
+    }
+}
+
+enum Carrier {
+    Line {
+        origin: Point<2>,
+        direction: Vector<2>,
+    },
+    Circle((Point<2>, Scalar)),
+}
+
+/// The points that might bound the overlap of two segments, that are part of
+/// the same line or circle
+fn overlap_candidates(a: &Segment, b: &Segment) -> Vec<Point<2>> {
+    [a.endpoints(), b.endpoints()]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect()
+}
+
+/// Compute the points where a line intersects a circle
+fn line_circle_intersections(
+    origin: Point<2>,
+    direction: Vector<2>,
+    (center, radius): (Point<2>, Scalar),
+    epsilon: Scalar,
+) -> Vec<Point<2>> {
+    let direction = direction.normalize();
+
+    let foot = origin + direction * (center - origin).dot(&direction);
+    let distance = (foot - center).magnitude();
+
+    if distance > radius + epsilon {
+        return Vec::new();
+    }
+
+    let offset = (radius * radius - distance * distance)
+        .max(Scalar::ZERO)
+        .sqrt();
+
+    if offset < epsilon {
+        vec![foot]
+    } else {
+        vec![foot + direction * offset, foot + direction * -offset]
+    }
+}
+
+/// The points of both shapes
+///
+/// Makes sure that points that are very close to each other are represented
+/// by the exact same value. This includes vertices that both shapes have in
+/// common, and intersection points that hit a vertex.
+struct Points {
+    points: Vec<Point<2>>,
+    epsilon: Scalar,
+}
+
+impl Points {
+    fn new(epsilon: Scalar) -> Self {
+        Self {
+            points: Vec::new(),
+            epsilon,
+        }
+    }
+
+    fn snap(&mut self, point: Point<2>) -> Point<2> {
+        let existing = self
+            .points
+            .iter()
+            .find(|&&existing| (existing - point).magnitude() < self.epsilon);
+
+        match existing {
+            Some(&existing) => existing,
+            None => {
+                self.points.push(point);
+                point
+            }
+        }
+    }
+}
+
+/// Extract the boundary of a 2-dimensional shape
+fn boundary(shape: &mut Shape, points: &mut Points) -> Vec<Segment> {
+    let mut segments = Vec::new();
+
+    for face in shape.topology().faces() {
+        let cycles = match &*face.get() {
+            Face::Face { cycles, .. } => cycles.clone(),
+            Face::Triangles(_) => {
+                // Triangle faces only result from 3-dimensional operations.
+                continue;
+            }
+        };
+
+        for cycle in cycles {
+            for edge in cycle.get().edges() {
+                let endpoints = edge.vertices().map(|vertices| {
+                    vertices.map(|v| points.snap(xy(v.point())))
+                });
+
+                let segment = match (edge.curve(), endpoints) {
+                    (Curve::Line(_), Some([start, end])) => {
+                        Segment::Line { start, end }
+                    }
+                    (Curve::Circle(circle), Some([start, end])) => {
+                        Segment::Arc {
+                            center: xy(circle.center),
+                            radius: circle.radius.magnitude(),
+                            start,
+                            end,
+                        }
+                    }
+                    (Curve::Circle(circle), None) => Segment::Circle {
+                        center: xy(circle.center),
+                        radius: circle.radius.magnitude(),
+                    },
+                    (Curve::Line(_), None) => {
+                        // A continuous line is infinite. It can't bound a
+                        // face.
+                        continue;
+                    }
+                };
+
+                segments.push(segment);
+            }
+        }
+    }
+
+    segments
+}
+
+/// Determine whether a point is inside of a boundary
+fn contains(boundary: &[Segment], point: Point<2>) -> bool {
+    let crossings: usize = boundary
+        .iter()
+        .map(|segment| segment.ray_crossings(point))
+        .sum();
+
+    crossings % 2 == 1
+}
+
+fn is_on_boundary(
+    boundary: &[Segment],
+    point: Point<2>,
+    epsilon: Scalar,
+) -> bool {
+    boundary
+        .iter()
+        .any(|segment| segment.distance(point) < epsilon)
+}
+
+/// Assemble segments into cycles
+///
+/// Where the boundary touches itself, more than two segments meet at a point.
+/// The loops that meet there are split into separate cycles.
+///
+/// The segments of each returned cycle are connected, and lines go along the
+/// cycle. Arcs always go counter-clockwise, so some of them might go against
+/// the cycle. It is made sure that the first segment of each cycle doesn't.
+fn assemble(segments: Vec<Segment>) -> Vec<Vec<Segment>> {
+    let mut cycles = Vec::new();
+
+    let mut adjacent: HashMap<Point<2>, Vec<usize>> = HashMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match segment.endpoints() {
+            Some([start, end]) => {
+                adjacent.entry(start).or_default().push(i);
+                adjacent.entry(end).or_default().push(i);
+            }
+            None => cycles.push(vec![*segment]),
+        }
+    }
+
+    let mut used: Vec<_> = segments
+        .iter()
+        .map(|segment| segment.endpoints().is_none())
+        .collect();
+
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+
+        // The start of each segment in the path, the segment, and whether it
+        // goes along the path.
+        let mut path: Vec<(Point<2>, Segment, bool)> = Vec::new();
+        let mut current = segments[first].endpoints().map(|[start, _]| start);
+        let mut next = Some(first);
+
+        while let (Some(point), Some(i)) = (current, next) {
+            used[i] = true;
+
+            // Can't panic. Only segments with endpoints are in the path.
+            let [start, end] = segments[i].endpoints().unwrap();
+            let forward = start == point;
+            let segment = if forward {
+                segments[i]
+            } else {
+                segments[i].reverse()
+            };
+            let is_arc = matches!(segment, Segment::Arc { .. });
+
+            path.push((point, segment, forward || !is_arc));
+            let point = if forward { end } else { start };
+
+            if let Some(k) = path.iter().position(|&(start, ..)| start == point)
+            {
+                let cycle = path.split_off(k);
+                cycles.push(orient(cycle));
+            }
+
+            current = Some(point);
+            next = adjacent[&point].iter().copied().find(|&j| !used[j]);
+        }
+    }
+
+    cycles
+}
+
+/// Make sure that the first segment of a cycle goes along the cycle
+fn orient(mut cycle: Vec<(Point<2>, Segment, bool)>) -> Vec<Segment> {
+    if !cycle.iter().any(|&(_, _, along)| along) {
+        // All segments are arcs that go against the cycle. Reversing the cycle
+        // turns that around.
+        cycle.reverse();
+        for (_, segment, along) in &mut cycle {
+            *segment = segment.reverse();
+            *along = true;
+        }
+    }
+
+    // Can't panic. We just made sure that there is such a segment.
+    let first = cycle.iter().position(|&(_, _, along)| along).unwrap();
+    cycle.rotate_left(first);
+
+    cycle.into_iter().map(|(_, segment, _)| segment).collect()
+}
+
+/// The angle of `point` around `center`, between `0` and `2π`
+fn angle(center: Point<2>, point: Point<2>) -> Scalar {
+    let v = point - center;
+    let full_circle = Scalar::PI * 2.;
+    (Scalar::atan2(v.v, v.u) + full_circle) % full_circle
+}
+
+/// The counter-clockwise angle from `from` to `to`, between `0` and `2π`
+fn sweep(from: Scalar, to: Scalar) -> Scalar {
+    let full_circle = Scalar::PI * 2.;
+    ((to - from) % full_circle + full_circle) % full_circle
+}
+
+/// The unit vector pointing in the direction of `angle`
+fn direction(angle: Scalar) -> Vector<2> {
+    let (sin, cos) = angle.sin_cos();
+    Vector::from([cos, sin])
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.u * b.v - a.v * b.u
+}
+
+fn xy(point: Point<3>) -> Point<2> {
+    Point::from([point.x, point.y])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::{
+        algorithms::{
+            test_helpers::{
+                assert_area, assert_area_approx, circle, rectangle, tolerance,
+            },
+            BooleanOp,
+        },
+        shape::Shape,
+    };
+
+    use super::boolean_2d;
+
+    #[test]
+    fn overlapping() {
+        let a = || rectangle([0., 0.], [1., 1.]);
+        let b = || rectangle([0.5, 0.5], [1.5, 1.5]);
+
+        let mut union = combine(a(), b(), BooleanOp::Union);
+        assert_eq!(union.topology().faces().count(), 1);
+        assert_eq!(union.topology().edges().count(), 8);
+        assert_area(union, 1.75);
+
+        let intersection = combine(a(), b(), BooleanOp::Intersection);
+        assert_area(intersection, 0.25);
+
+        let difference = combine(a(), b(), BooleanOp::Difference);
+        assert_area(difference, 0.75);
+    }
+
+    #[test]
+    fn hole() {
+        let a = || rectangle([0., 0.], [3., 3.]);
+        let b = || rectangle([1., 1.], [2., 2.]);
+
+        let mut difference = combine(a(), b(), BooleanOp::Difference);
+        assert_eq!(difference.topology().faces().count(), 1);
+        assert_eq!(difference.topology().cycles().count(), 2);
+        assert_area(difference, 8.);
+
+        let mut union = combine(a(), b(), BooleanOp::Union);
+        assert_eq!(union.topology().cycles().count(), 1);
+        assert_area(union, 9.);
+
+        // A circle that is fully inside, remains a circle.
+        let mut difference =
+            combine(a(), circle([1.5, 1.5], 1.), BooleanOp::Difference);
+        assert_eq!(difference.topology().edges().count(), 5);
+        assert_area_approx(difference, 9. - PI, 5e-2);
+    }
+
+    #[test]
+    fn disjoint() {
+        let a = || rectangle([0., 0.], [1., 1.]);
+        let b = || rectangle([2., 0.], [3., 1.]);
+
+        let mut union = combine(a(), b(), BooleanOp::Union);
+        assert_eq!(union.topology().faces().count(), 2);
+        assert_area(union, 2.);
+
+        let mut intersection = combine(a(), b(), BooleanOp::Intersection);
+        assert_eq!(intersection.topology().faces().count(), 0);
+
+        // Squares that share an edge are merged.
+        let b = rectangle([1., 0.], [2., 1.]);
+        let mut union = combine(a(), b, BooleanOp::Union);
+        assert_eq!(union.topology().faces().count(), 1);
+        assert_area(union, 2.);
+    }
+
+    #[test]
+    fn circle_crossing_rectangle() {
+        let a = || rectangle([0., 0.], [2., 2.]);
+        let b = || circle([2., 2.], 1.);
+
+        let difference = combine(a(), b(), BooleanOp::Difference);
+        assert_area_approx(difference, 4. - PI / 4., 5e-2);
+
+        let intersection = combine(a(), b(), BooleanOp::Intersection);
+        assert_area_approx(intersection, PI / 4., 5e-2);
+
+        let union = combine(a(), b(), BooleanOp::Union);
+        assert_area_approx(union, 4. + PI * 3. / 4., 5e-2);
+    }
+
+    #[test]
+    fn circles() {
+        let a = || circle([0., 0.], 1.);
+        let b = || circle([1., 0.], 1.);
+
+        // The area of the lens, where the circles overlap.
+        let lens = PI * 2. / 3. - 3f64.sqrt() / 2.;
+
+        let intersection = combine(a(), b(), BooleanOp::Intersection);
+        assert_area_approx(intersection, lens, 5e-2);
+
+        let difference = combine(a(), b(), BooleanOp::Difference);
+        assert_area_approx(difference, PI - lens, 5e-2);
+
+        // The result can be used as an operand again.
+        let mut difference = combine(
+            combine(a(), b(), BooleanOp::Difference),
+            rectangle([-2., -0.5], [2., 0.5]),
+            BooleanOp::Difference,
+        );
+        assert_eq!(difference.topology().faces().count(), 2);
+    }
+
+    fn combine(a: Shape, b: Shape, op: BooleanOp) -> Shape {
+        boolean_2d(a, b, op, tolerance(), [255, 0, 0, 255])
+    }
+}
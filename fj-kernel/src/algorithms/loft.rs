@@ -0,0 +1,612 @@
+use std::collections::HashMap;
+
+use fj_math::{Point, Scalar, Transform, Triangle};
+
+use crate::{
+    geometry::{Curve, Line, Surface, SweptCurve},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{
+    approximation::{approximate_cycle, approximate_edge_points},
+    sweep::{first_position, Relation},
+};
+
+/// An error that can occur when lofting between profiles
+#[derive(Debug, thiserror::Error)]
+pub enum LoftError {
+    /// Two consecutive profiles consist of different numbers of faces
+    #[error("Profiles consist of different numbers of faces: {0} and {1}")]
+    FaceCountMismatch(usize, usize),
+
+    /// Two matched faces consist of different numbers of cycles
+    #[error("Faces consist of different numbers of cycles: {0} and {1}")]
+    CycleCountMismatch(usize, usize),
+}
+
+/// Create a new shape by lofting between profiles
+///
+/// The profiles are expected to be in their final position already. Each
+/// profile is connected to the next one by side faces, and the first and the
+/// last profile close off both ends.
+///
+/// Faces of consecutive profiles are matched by proximity, their cycles by
+/// order. If two matched cycles consist of the same number of edges, each edge
+/// is connected to the corresponding edge of the other cycle. Otherwise, the
+/// cycles are connected as a whole.
+///
+/// Returns an error, if consecutive profiles consist of different numbers of
+/// faces, or if matched faces consist of different numbers of cycles.
+pub fn loft_shapes(
+    mut profiles: Vec<Shape>,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Result<Shape, LoftError> {
+    let mut target = Shape::new();
+    if profiles.is_empty() {
+        return Ok(target);
+    }
+
+    let relations: Vec<_> = profiles
+        .iter_mut()
+        .map(|profile| first_position(profile, &mut target))
+        .collect();
+
+    // Close off both ends.
+    let last = profiles.len() - 1;
+    let mut ends = vec![0, last];
+    ends.dedup();
+    for i in ends {
+        for face_source in profiles[i].topology().faces().values() {
+            add_cap(
+                &mut target,
+                &face_source,
+                &relations[i],
+                face_source.surface(),
+                color,
+            );
+        }
+    }
+
+    // Create the side faces.
+    for i in 0..last {
+        let faces_a: Vec<_> = profiles[i].topology().faces().values().collect();
+        let mut faces_b: Vec<_> =
+            profiles[i + 1].topology().faces().values().collect();
+
+        // Unmatched faces or cycles would leave holes in the shape.
+        if faces_a.len() != faces_b.len() {
+            return Err(LoftError::FaceCountMismatch(
+                faces_a.len(),
+                faces_b.len(),
+            ));
+        }
+
+        let mut vertex_a_to_edge = HashMap::new();
+
+        for face_a in faces_a {
+            let center_a = center(&face_a, tolerance);
+            let closest = faces_b
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, face_b)| {
+                    (center(face_b, tolerance) - center_a).magnitude()
+                })
+                .map(|(j, _)| j);
+            let face_b = match closest {
+                Some(j) => faces_b.remove(j),
+                None => break,
+            };
+
+            let cycle_counts =
+                [&face_a, &face_b].map(|face| face.cycles().count());
+            if cycle_counts[0] != cycle_counts[1] {
+                return Err(LoftError::CycleCountMismatch(
+                    cycle_counts[0],
+                    cycle_counts[1],
+                ));
+            }
+
+            for (cycle_a, cycle_b) in face_a.cycles().zip(face_b.cycles()) {
+                let edges_a = edges_for_cycle(&relations[i], &cycle_a);
+                let edges_b = edges_for_cycle(&relations[i + 1], &cycle_b);
+
+                loft_side_faces(
+                    &mut target,
+                    [edges_a, edges_b],
+                    &mut vertex_a_to_edge,
+                    tolerance,
+                    color,
+                );
+            }
+        }
+    }
+
+    Ok(target)
+}
+
+/// Create a new shape by lofting between transformed copies of a shape
+///
+/// Each transform places one copy of the shape. Like [`loft_shapes`], but the
+/// copies don't need to be matched, as they all come from the same shape.
+///
+/// There must be at least one transform.
+pub(super) fn loft_transformed(
+    mut source: Shape,
+    transforms: &[Transform],
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Shape {
+    let mut target = Shape::new();
+
+    let relations: Vec<_> = transforms
+        .iter()
+        .map(|transform| {
+            transformed_position(&mut source, &mut target, transform)
+        })
+        .collect();
+
+    // Close off both ends.
+    let last = transforms.len().saturating_sub(1);
+    let mut ends = vec![0, last];
+    ends.dedup();
+    for i in ends {
+        for face_source in source.topology().faces().values() {
+            add_cap(
+                &mut target,
+                &face_source,
+                &relations[i],
+                face_source.surface().transform(&transforms[i]),
+                color,
+            );
+        }
+    }
+
+    // Create the side faces.
+    for i in 0..last {
+        let mut vertex_a_to_edge = HashMap::new();
+
+        for cycle_source in source.topology().cycles() {
+            let edges_a = edges_for_cycle(&relations[i], &cycle_source.get());
+            let edges_b =
+                edges_for_cycle(&relations[i + 1], &cycle_source.get());
+
+            loft_side_faces(
+                &mut target,
+                [edges_a, edges_b],
+                &mut vertex_a_to_edge,
+                tolerance,
+                color,
+            );
+        }
+    }
+
+    target
+}
+
+/// Copy the vertices and edges of the source shape into the target shape
+///
+/// Like [`first_position`], but the copies are transformed.
+fn transformed_position(
+    source: &mut Shape,
+    target: &mut Shape,
+    transform: &Transform,
+) -> Relation {
+    let mut relation = Relation::new();
+
+    for vertex_source in source.topology().vertices() {
+        let point = target
+            .geometry()
+            .add_point(transform.transform_point(&vertex_source.get().point()));
+        let vertex = target.topology().add_vertex(Vertex { point }).unwrap();
+
+        relation.vertices.insert(vertex_source, vertex);
+    }
+
+    for edge_source in source.topology().edges() {
+        let curve = target
+            .geometry()
+            .add_curve(edge_source.get().curve().transform(transform));
+        let vertices = relation.vertices_for_edge(&edge_source);
+        let edge = target
+            .topology()
+            .add_edge(Edge { curve, vertices })
+            .unwrap();
+
+        relation.edges.insert(edge_source, edge);
+    }
+
+    relation
+}
+
+/// Add a copy of a source face, that closes off one end of the loft
+fn add_cap(
+    target: &mut Shape,
+    face_source: &Face,
+    relation: &Relation,
+    surface: Surface,
+    color: [u8; 4],
+) {
+    let surface = target.geometry().add_surface(surface);
+    let cycles = face_source
+        .cycles()
+        .map(|cycle_source| {
+            let edges = edges_for_cycle(relation, &cycle_source);
+            target.topology().add_cycle(Cycle { edges }).unwrap()
+        })
+        .collect();
+
+    target
+        .topology()
+        .add_face(Face::Face {
+            surface,
+            cycles,
+            color,
+        })
+        .unwrap();
+}
+
+/// Create the faces that connect two cycles
+fn loft_side_faces(
+    target: &mut Shape,
+    [edges_a, edges_b]: [Vec<Handle<Edge>>; 2],
+    vertex_a_to_edge: &mut HashMap<Handle<Vertex>, Handle<Edge>>,
+    tolerance: Scalar,
+    color: [u8; 4],
+) {
+    let (vertices_a, vertices_b) =
+        match (cycle_vertices(&edges_a), cycle_vertices(&edges_b)) {
+            (Some(a), Some(b)) if a.len() == b.len() => (a, b),
+            _ => {
+                // The edges of the cycles don't correspond to each other. We
+                // can only connect the cycles as a whole, using the triangle
+                // representation.
+                let [a, b] = [edges_a, edges_b].map(|edges| {
+                    approximate_cycle(&Cycle { edges }, tolerance)
+                });
+                let triangles = ruled_triangles_closed(a, b, color);
+
+                target
+                    .topology()
+                    .add_face(Face::Triangles(triangles))
+                    .unwrap();
+
+                return;
+            }
+        };
+
+    let n = vertices_a.len();
+    for i in 0..n {
+        let [a0, a1] = [&vertices_a[i], &vertices_a[(i + 1) % n]];
+        let [b0, b1] = [&vertices_b[i], &vertices_b[(i + 1) % n]];
+        let edge_a = edges_a[i].clone();
+        let edge_b = edges_b[i].clone();
+
+        let [p_a0, p_a1, p_b0, p_b1] =
+            [a0, a1, b0, b1].map(|vertex| vertex.get().point());
+
+        let is_planar = matches!(
+            (edge_a.get().curve(), edge_b.get().curve()),
+            (Curve::Line(_), Curve::Line(_))
+        ) && is_planar([p_a0, p_a1, p_b0, p_b1]);
+
+        if !is_planar {
+            // Faces between curved or skewed edges aren't planar, and there's
+            // no surface that could represent them. We fall back to the
+            // triangle representation for those.
+            let [a, b] =
+                [(&edge_a, p_a0), (&edge_b, p_b0)].map(|(edge, start)| {
+                    let mut points =
+                        approximate_edge_points(&edge.get(), tolerance);
+                    if points.first() != Some(&start) {
+                        points.reverse();
+                    }
+                    points
+                });
+
+            target
+                .topology()
+                .add_face(Face::Triangles(ruled_triangles(&a, &b, color)))
+                .unwrap();
+
+            continue;
+        }
+
+        // Create (or retrieve from the cache, `vertex_a_to_edge`) the edges
+        // that connect the vertices of the two edges.
+        let [side_edge_0, side_edge_1] = [[a0, b0], [a1, b1]].map(|[a, b]| {
+            vertex_a_to_edge
+                .entry(a.clone())
+                .or_insert_with(|| {
+                    let curve = target.geometry().add_curve(Curve::Line(
+                        Line::from_points([a, b].map(|v| v.get().point())),
+                    ));
+
+                    target
+                        .topology()
+                        .add_edge(Edge {
+                            curve,
+                            vertices: Some([a.clone(), b.clone()]),
+                        })
+                        .unwrap()
+                })
+                .clone()
+        });
+
+        let surface =
+            target
+                .geometry()
+                .add_surface(Surface::SweptCurve(SweptCurve {
+                    curve: edge_a.get().curve(),
+                    path: p_b0 - p_a0,
+                }));
+
+        // The edges are ordered such, that each one connects to the next.
+        let cycle = target
+            .topology()
+            .add_cycle(Cycle {
+                edges: vec![edge_a, side_edge_1, edge_b, side_edge_0],
+            })
+            .unwrap();
+
+        target
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles: vec![cycle],
+                color,
+            })
+            .unwrap();
+    }
+}
+
+/// The edges in the target shape, that were created from a source cycle
+fn edges_for_cycle(relation: &Relation, cycle: &Cycle) -> Vec<Handle<Edge>> {
+    cycle
+        .edges
+        .iter()
+        .map(|edge| relation.edges.get(edge).unwrap().clone())
+        .collect()
+}
+
+/// The vertex where each edge starts, following the direction of the cycle
+///
+/// Returns `None`, if the cycle contains continuous edges.
+pub(super) fn cycle_vertices(
+    edges: &[Handle<Edge>],
+) -> Option<Vec<Handle<Vertex>>> {
+    if edges.len() == 2 {
+        // Both edges connect the same two vertices, so the direction of the
+        // cycle is arbitrary. We follow the first edge.
+        let [a, b] = edges[0].get().vertices.clone()?;
+        edges[1].get().vertices.as_ref()?;
+
+        return Some(vec![a, b]);
+    }
+
+    (0..edges.len())
+        .map(|i| {
+            let [a, b] = edges[i].get().vertices.clone()?;
+            let next = edges[(i + 1) % edges.len()].get().vertices.clone()?;
+
+            if next.contains(&b) {
+                Some(a)
+            } else {
+                Some(b)
+            }
+        })
+        .collect()
+}
+
+/// The center of the approximation of a face's first cycle
+fn center(face: &Face, tolerance: Scalar) -> Point<3> {
+    let points = face
+        .cycles()
+        .next()
+        .map(|cycle| approximate_cycle(&cycle, tolerance))
+        .unwrap_or_default();
+
+    let sum = points
+        .iter()
+        .fold(Point::origin(), |sum, point| sum + point.coords);
+    Point::origin() + sum.coords / Scalar::from_u64(points.len().max(1) as u64)
+}
+
+/// Whether four points lie in a plane
+fn is_planar([a, b, c, d]: [Point<3>; 4]) -> bool {
+    let normal = (b - a).cross(&(c - a));
+
+    // This is only used to decide whether a face can be represented by a plane
+    // exactly, so any deviation beyond numerical noise is too much.
+    let epsilon = Scalar::from_f64(1e-12);
+
+    normal.magnitude() > epsilon
+        && (d - a).dot(&normal.normalize()).abs() < epsilon
+}
+
+/// Connect two closed polygons with triangles
+///
+/// The polygons are aligned, so that the connection starts at the points of
+/// the two polygons that are closest to each other.
+fn ruled_triangles_closed(
+    mut a: Vec<Point<3>>,
+    mut b: Vec<Point<3>>,
+    color: [u8; 4],
+) -> Vec<Triangle<3>> {
+    for points in [&mut a, &mut b] {
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+    }
+
+    if let Some(start) = a.first().copied() {
+        let closest = b
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, point)| (*point - start).magnitude())
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        b.rotate_left(closest);
+    }
+
+    for points in [&mut a, &mut b] {
+        if let Some(first) = points.first().copied() {
+            points.push(first);
+        }
+    }
+
+    ruled_triangles(&a, &b, color)
+}
+
+/// Connect two polylines with triangles
+///
+/// Both polylines are walked from start to end at the same relative speed, so
+/// they don't need to consist of the same number of points.
+fn ruled_triangles(
+    a: &[Point<3>],
+    b: &[Point<3>],
+    color: [u8; 4],
+) -> Vec<Triangle<3>> {
+    let [t_a, t_b] = [a, b].map(relative_lengths);
+
+    let mut triangles = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i + 1 < a.len() || j + 1 < b.len() {
+        let advance_a =
+            j + 1 >= b.len() || (i + 1 < a.len() && t_a[i + 1] <= t_b[j + 1]);
+
+        let [p, q, r] = if advance_a {
+            i += 1;
+            [a[i - 1], a[i], b[j]]
+        } else {
+            j += 1;
+            [a[i], b[j], b[j - 1]]
+        };
+
+        // Profiles can shrink to a point, which leads to degenerate triangles.
+        if p == q || q == r || r == p {
+            continue;
+        }
+
+        let mut triangle = Triangle::from([p, q, r]);
+        triangle.set_color(color);
+        triangles.push(triangle);
+    }
+
+    triangles
+}
+
+/// The length along the polyline up to each point, relative to its total
+fn relative_lengths(points: &[Point<3>]) -> Vec<Scalar> {
+    let mut lengths = vec![Scalar::ZERO];
+    for segment in points.windows(2) {
+        let length = *lengths.last().unwrap();
+        lengths.push(length + (segment[1] - segment[0]).magnitude());
+    }
+
+    let total = *lengths.last().unwrap();
+    if total > Scalar::ZERO {
+        for length in &mut lengths {
+            *length = *length / total;
+        }
+    }
+
+    lengths
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::test_helpers::{
+            add_face, assert_area, circle, rectangle_cycle, square, tolerance,
+            translated,
+        },
+        shape::Shape,
+        topology::Face,
+    };
+
+    use super::{loft_shapes, LoftError};
+
+    #[test]
+    fn frustum() {
+        let mut frustum = loft_shapes(
+            vec![
+                translated(square(2.), [0., 0., 0.]),
+                translated(square(1.), [0., 0., 1.]),
+            ],
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        // Two caps, and one planar face for each edge.
+        assert_eq!(frustum.topology().faces().count(), 6);
+        assert!(frustum
+            .topology()
+            .faces()
+            .values()
+            .all(|face| matches!(face, Face::Face { .. })));
+        assert!(frustum.validate().is_ok());
+
+        let side = 1.5 * 1.25_f64.sqrt();
+        assert_area(frustum, 4. + 1. + side * 4.);
+    }
+
+    #[test]
+    fn square_to_circle() {
+        let mut loft = loft_shapes(
+            vec![
+                translated(square(2.), [0., 0., 0.]),
+                translated(circle([0., 0.], 1.), [0., 0., 1.]),
+                translated(square(2.), [0., 0., 2.]),
+            ],
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        // Two caps, and the two connections between the circle and the
+        // squares.
+        assert_eq!(loft.topology().faces().count(), 4);
+        assert!(loft.validate().is_ok());
+    }
+
+    #[test]
+    fn face_count_mismatch() {
+        let mut two_squares = square(2.);
+        let cycle = rectangle_cycle(&mut two_squares, [2., -1.], [4., 1.]);
+        add_face(&mut two_squares, vec![cycle]);
+
+        let result = loft_shapes(
+            vec![
+                translated(two_squares, [0., 0., 0.]),
+                translated(square(2.), [0., 0., 1.]),
+            ],
+            tolerance(),
+            [255, 0, 0, 255],
+        );
+        assert!(matches!(result, Err(LoftError::FaceCountMismatch(2, 1))));
+    }
+
+    #[test]
+    fn cycle_count_mismatch() {
+        let mut square_with_hole = Shape::new();
+        let cycles = vec![
+            rectangle_cycle(&mut square_with_hole, [-1., -1.], [1., 1.]),
+            rectangle_cycle(&mut square_with_hole, [-0.5, -0.5], [0.5, 0.5]),
+        ];
+        add_face(&mut square_with_hole, cycles);
+
+        let result = loft_shapes(
+            vec![
+                translated(square(2.), [0., 0., 0.]),
+                translated(square_with_hole, [0., 0., 1.]),
+            ],
+            tolerance(),
+            [255, 0, 0, 255],
+        );
+        assert!(matches!(result, Err(LoftError::CycleCountMismatch(1, 2))));
+    }
+}
@@ -4,10 +4,29 @@
 //! on their respective purpose.
 
 mod approximation;
+mod blend;
+mod boolean;
+mod boolean_2d;
+mod loft;
+mod offset;
+mod revolve;
 mod sweep;
 mod triangulation;
 
+#[cfg(test)]
+mod test_helpers;
+
 pub use self::{
-    approximation::Approximation, sweep::sweep_shape,
+    approximation::Approximation,
+    blend::{blend_corners, blend_sweep, Blend, BlendError, SweepEdges},
+    boolean::{boolean, BooleanOp},
+    boolean_2d::boolean_2d,
+    loft::{loft_shapes, LoftError},
+    offset::{offset_shape, OffsetError},
+    revolve::{revolve_shape, RevolveError},
+    sweep::{
+        sweep_shape, sweep_shape_along, sweep_shape_tapered, validate_path,
+        PathSegment, SweepError,
+    },
     triangulation::triangulate,
 };
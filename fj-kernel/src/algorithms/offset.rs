@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{Circle, Curve, Line},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{approximation::approximate_cycle, loft::cycle_vertices};
+
+/// An error that can occur when offsetting a shape
+#[derive(Debug, thiserror::Error)]
+pub enum OffsetError {
+    /// The offset is so large, that an edge vanishes or flips its direction
+    #[error("Offset of {0} is too large for the shape")]
+    TooLarge(f64),
+}
+
+/// Create a new shape by offsetting the cycles of an existing one
+///
+/// The shape must lie in the x-y plane. Each cycle moves by `distance` into the
+/// face it bounds: The outer cycle of a face shrinks, while holes grow. A
+/// negative distance moves the cycles the other way.
+///
+/// Straight edges stay parallel to the original ones, and arcs keep their
+/// centers. Where an arc meets another edge at a sharp corner, the new corner
+/// is only approximated.
+///
+/// Returns an error, if the distance is so large, that an edge vanishes or
+/// flips its direction. For a convex shape, that is the case, once the distance
+/// reaches the radius of the largest circle that fits into the shape.
+pub fn offset_shape(
+    source: &mut Shape,
+    distance: Scalar,
+    tolerance: Scalar,
+) -> Result<Shape, OffsetError> {
+    let mut target = Shape::new();
+
+    for face_source in source.topology().faces().values() {
+        let (surface, color) = match &face_source {
+            Face::Face { surface, color, .. } => (*surface.get(), *color),
+            Face::Triangles(_) => {
+                target.topology().add_face(face_source.clone()).unwrap();
+                continue;
+            }
+        };
+
+        let cycles_source: Vec<_> = face_source.cycles().collect();
+
+        // The outer cycle is the one enclosing the largest area. All other
+        // cycles are holes.
+        let areas: Vec<_> = cycles_source
+            .iter()
+            .map(|cycle| signed_area(&approximate_cycle(cycle, tolerance)))
+            .collect();
+        let outer = areas
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, area)| area.abs())
+            .map(|(i, _)| i);
+
+        let cycles = cycles_source
+            .iter()
+            .enumerate()
+            .map(|(i, cycle)| {
+                // The face is to the left of a counter-clockwise outer cycle,
+                // and to the right of a counter-clockwise hole.
+                let is_ccw = areas[i] > Scalar::ZERO;
+                let inward = if is_ccw == (Some(i) == outer) {
+                    distance
+                } else {
+                    -distance
+                };
+
+                let edges = offset_cycle(&mut target, cycle, inward)?;
+                Ok(target.topology().add_cycle(Cycle { edges }).unwrap())
+            })
+            .collect::<Result<_, OffsetError>>()?;
+
+        let surface = target.geometry().add_surface(surface);
+        target
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles,
+                color,
+            })
+            .unwrap();
+    }
+
+    Ok(target)
+}
+
+/// Offset a cycle to the left of its direction
+fn offset_cycle(
+    target: &mut Shape,
+    cycle: &Cycle,
+    distance: Scalar,
+) -> Result<Vec<Handle<Edge>>, OffsetError> {
+    let too_large = || OffsetError::TooLarge(distance.into_f64());
+
+    let starts = match cycle_vertices(&cycle.edges) {
+        Some(starts) => starts,
+        None => {
+            // The cycle consists of a single continuous edge. Only circles
+            // can be continuous, and they follow their normal
+            // counter-clockwise.
+            return cycle
+                .edges
+                .iter()
+                .map(|edge| {
+                    let curve = match edge.get().curve() {
+                        Curve::Circle(circle) => {
+                            let start = circle.center + circle.radius;
+                            let tangent = circle.normal.cross(&circle.radius);
+                            let start =
+                                start + left(tangent.normalize()) * distance;
+
+                            // The circle must not shrink past its center.
+                            if (start - circle.center).dot(&circle.radius)
+                                <= Scalar::ZERO
+                            {
+                                return Err(too_large());
+                            }
+
+                            Curve::Circle(Circle {
+                                radius: start - circle.center,
+                                ..circle
+                            })
+                        }
+                        curve => curve,
+                    };
+                    let curve = target.geometry().add_curve(curve);
+
+                    Ok(target
+                        .topology()
+                        .add_edge(Edge {
+                            curve,
+                            vertices: None,
+                        })
+                        .unwrap())
+                })
+                .collect();
+        }
+    };
+
+    let n = starts.len();
+    let points: Vec<_> =
+        starts.iter().map(|vertex| vertex.get().point()).collect();
+
+    // The direction of each edge at its start and end, following the cycle.
+    let tangents: Vec<_> = (0..n)
+        .map(|i| {
+            let edge = cycle.edges[i].get();
+            let [a, b] = [points[i], points[(i + 1) % n]];
+
+            match edge.curve() {
+                Curve::Circle(circle) => {
+                    let is_forward =
+                        edge.vertices.as_ref().map(|[start, _]| start)
+                            == Some(&starts[i]);
+                    [a, b].map(|point| {
+                        let tangent = circle
+                            .normal
+                            .cross(&(point - circle.center))
+                            .normalize();
+                        if is_forward {
+                            tangent
+                        } else {
+                            tangent * -Scalar::ONE
+                        }
+                    })
+                }
+                _ => {
+                    let tangent = (b - a).normalize();
+                    [tangent, tangent]
+                }
+            }
+        })
+        .collect();
+
+    // Each vertex moves to where the offsets of its two edges meet.
+    let offset_points: Vec<_> = (0..n)
+        .map(|i| {
+            let [_, end_of_previous] = tangents[(i + n - 1) % n];
+            let [start_of_next, _] = tangents[i];
+            let [a, b] = [end_of_previous, start_of_next].map(left);
+
+            let denominator = Scalar::ONE + a.dot(&b);
+            let offset = if denominator > Scalar::from_f64(1e-9) {
+                (a + b) * (distance / denominator)
+            } else {
+                // The edges double back on each other. There's no sensible
+                // place for the new vertex, so we just move it along with the
+                // next edge.
+                b * distance
+            };
+
+            points[i] + offset
+        })
+        .collect();
+
+    // If the offset is too large, the offset edges shrink to nothing, and then
+    // flip around. This needs to be checked before adding the vertices, as
+    // `Shape` rejects vertices that coincide.
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let before = points[j] - points[i];
+        let after = offset_points[j] - offset_points[i];
+
+        if after.magnitude() < Scalar::from_f64(5e-7)
+            || after.dot(&before) <= Scalar::ZERO
+        {
+            return Err(too_large());
+        }
+
+        if let Curve::Circle(circle) = cycle.edges[i].get().curve() {
+            let radius_before = points[i] - circle.center;
+            let radius_after = offset_points[i] - circle.center;
+            if radius_after.dot(&radius_before) <= Scalar::ZERO {
+                return Err(too_large());
+            }
+        }
+    }
+
+    let vertices: Vec<_> = offset_points
+        .into_iter()
+        .map(|point| {
+            let point = target.geometry().add_point(point);
+            target.topology().add_vertex(Vertex { point }).unwrap()
+        })
+        .collect();
+
+    let vertex_map: HashMap<_, _> = starts
+        .iter()
+        .cloned()
+        .zip(vertices.iter().cloned())
+        .collect();
+
+    cycle
+        .edges
+        .iter()
+        .map(|edge| {
+            // `cycle_vertices` only returns vertices, if all edges have them.
+            let vertices = edge
+                .get()
+                .vertices
+                .clone()
+                .unwrap()
+                .map(|vertex| vertex_map[&vertex].clone());
+            let points = vertices.clone().map(|vertex| vertex.get().point());
+
+            let curve = match edge.get().curve() {
+                // Arcs start at their first vertex.
+                Curve::Circle(circle) => Curve::Circle(Circle {
+                    radius: points[0] - circle.center,
+                    ..circle
+                }),
+                _ => Curve::Line(Line::from_points(points)),
+            };
+            let curve = target.geometry().add_curve(curve);
+
+            Ok(target
+                .topology()
+                .add_edge(Edge {
+                    curve,
+                    vertices: Some(vertices),
+                })
+                .unwrap())
+        })
+        .collect()
+}
+
+/// The direction to the left of a direction in the x-y plane
+fn left(direction: Vector<3>) -> Vector<3> {
+    Vector::unit_z().cross(&direction)
+}
+
+/// The area of a polygon in the x-y plane
+///
+/// Positive, if the polygon is counter-clockwise.
+fn signed_area(points: &[Point<3>]) -> Scalar {
+    let mut area = Scalar::ZERO;
+
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+
+    area / Scalar::TWO
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::test_helpers::{
+            add_face, assert_area, rectangle_cycle, square, tolerance,
+        },
+        shape::Shape,
+    };
+
+    use super::{offset_shape, OffsetError};
+
+    #[test]
+    fn square_with_hole() {
+        let mut shape = Shape::new();
+        let cycles = vec![
+            rectangle_cycle(&mut shape, [-2., -2.], [2., 2.]),
+            rectangle_cycle(&mut shape, [-0.25, -0.25], [0.25, 0.25]),
+        ];
+        add_face(&mut shape, cycles);
+
+        // The outer square shrinks, while the hole grows.
+        let offset =
+            offset_shape(&mut shape, Scalar::from_f64(0.25), tolerance())
+                .unwrap();
+        assert!(offset.validate().is_ok());
+
+        assert_area(offset, 3.5 * 3.5 - 1. * 1.);
+    }
+
+    #[test]
+    fn too_large() {
+        let mut shape = square(2.);
+
+        // The edges vanish, once the offset reaches half the width.
+        for distance in [1., 1.5] {
+            assert!(matches!(
+                offset_shape(
+                    &mut shape,
+                    Scalar::from_f64(distance),
+                    tolerance()
+                ),
+                Err(OffsetError::TooLarge(_))
+            ));
+        }
+    }
+}
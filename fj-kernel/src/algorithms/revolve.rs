@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+
+use fj_math::{Point, Scalar, Transform, Triangle, Vector};
+
+use crate::{
+    geometry::{Circle, Curve, Line, RevolvedCurve, Surface},
+    shape::Shape,
+    topology::{Cycle, Edge, Face},
+};
+
+use super::{
+    approximation::approximate_edge_points,
+    sweep::{sweep, PathSegment, Relation},
+};
+
+/// Create a new shape by revolving an existing one around an axis
+///
+/// The shape revolves counter-clockwise around the direction of `axis`, by
+/// `angle` (in radians). Revolving by a full turn (or more) results in a closed
+/// shape. Otherwise, copies of the original shape close off both ends.
+///
+/// The shape must not cross the axis, but it may touch it.
+///
+/// Returns an error, if the axis has no valid direction, or if the angle is
+/// zero.
+pub fn revolve_shape(
+    source: Shape,
+    axis: Line,
+    angle: Scalar,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Result<Shape, RevolveError> {
+    validate_rotation(&axis, angle)?;
+
+    let full_turn = Scalar::PI * 2.;
+    let is_full_turn = angle.abs() >= full_turn;
+    let angle = angle.max(-full_turn).min(full_turn);
+
+    Ok(sweep(
+        source,
+        &[PathSegment::Arc { axis, angle }],
+        is_full_turn,
+        tolerance,
+        color,
+    ))
+}
+
+/// An error that can occur when revolving a shape
+#[derive(Debug, thiserror::Error)]
+pub enum RevolveError {
+    /// The direction of the axis is zero, or not finite
+    #[error("Can't revolve around an axis without a valid direction")]
+    InvalidAxis,
+
+    /// The angle is zero
+    #[error("Can't revolve by an angle of zero")]
+    ZeroAngle,
+}
+
+/// Check that a rotation around the axis, by the angle, is possible
+pub(super) fn validate_rotation(
+    axis: &Line,
+    angle: Scalar,
+) -> Result<(), RevolveError> {
+    let magnitude = axis.direction.magnitude();
+    if magnitude == Scalar::ZERO || !magnitude.into_f64().is_finite() {
+        return Err(RevolveError::InvalidAxis);
+    }
+    if angle == Scalar::ZERO {
+        return Err(RevolveError::ZeroAngle);
+    }
+
+    Ok(())
+}
+
+/// Create the faces that a step of a rotation creates
+///
+/// The rotation must not be larger than half a turn.
+pub(super) fn revolve_side_faces(
+    source: &mut Shape,
+    target: &mut Shape,
+    [start, end]: [&Relation; 2],
+    axis: Line,
+    angle: Scalar,
+    tolerance: Scalar,
+    color: [u8; 4],
+) {
+    let mut vertex_start_to_path = HashMap::new();
+
+    for edge_source in source.topology().edges() {
+        let edge_start = start.edges.get(&edge_source).unwrap().clone();
+        let edge_end = end.edges.get(&edge_source).unwrap().clone();
+
+        if edge_start == edge_end {
+            // The edge lies on the axis. It doesn't create a face.
+            continue;
+        }
+
+        let vertices_source = match (
+            edge_source.get().curve(),
+            edge_source.get().vertices.clone(),
+        ) {
+            (Curve::Line(_), Some(vertices_source)) => vertices_source,
+            _ => {
+                // The surfaces created by rotating circles aren't supported by
+                // the approximation code, as their curvature isn't fully
+                // defined by their edges. Like it's done for continuous faces
+                // created by straight sweeps, we fall back to the triangle
+                // representation for these faces.
+                let triangles = revolve_edge_approx(
+                    &edge_start.get(),
+                    &axis,
+                    angle,
+                    tolerance,
+                    color,
+                );
+
+                target
+                    .topology()
+                    .add_face(Face::Triangles(triangles))
+                    .unwrap();
+
+                continue;
+            }
+        };
+
+        // Create (or retrieve from the cache, `vertex_start_to_path`) the arcs
+        // that the vertices of the edge follow. Vertices that lie on the axis
+        // don't move, so they have no such arc.
+        let [path_a, path_b] = vertices_source.map(|vertex_source| {
+            let vertex_start =
+                start.vertices.get(&vertex_source).unwrap().clone();
+            let vertex_end = end.vertices.get(&vertex_source).unwrap().clone();
+
+            if vertex_start == vertex_end {
+                return None;
+            }
+
+            let path = vertex_start_to_path
+                .entry(vertex_start.clone())
+                .or_insert_with(|| {
+                    let curve = target.geometry().add_curve(Curve::Circle(
+                        path_circle(&axis, vertex_start.get().point()),
+                    ));
+
+                    target
+                        .topology()
+                        .add_edge(Edge {
+                            curve,
+                            vertices: Some([vertex_start, vertex_end]),
+                        })
+                        .unwrap()
+                })
+                .clone();
+
+            Some(path)
+        });
+
+        let surface = target.geometry().add_surface(Surface::RevolvedCurve(
+            RevolvedCurve {
+                curve: edge_start.get().curve(),
+                axis,
+            },
+        ));
+
+        // The edges are ordered such, that each one connects to the next.
+        // Otherwise the approximation of the face would be wrong.
+        let edges = [Some(edge_start), path_b, Some(edge_end), path_a]
+            .into_iter()
+            .flatten()
+            .collect();
+        let cycle = target.topology().add_cycle(Cycle { edges }).unwrap();
+
+        target
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles: vec![cycle],
+                color,
+            })
+            .unwrap();
+    }
+}
+
+/// Approximate the face that rotating an edge creates, using triangles
+fn revolve_edge_approx(
+    edge: &Edge,
+    axis: &Line,
+    angle: Scalar,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Vec<Triangle<3>> {
+    let points = approximate_edge_points(edge, tolerance);
+
+    // The approximation of the rotation needs to be as fine as the
+    // approximation of a circle with the largest radius involved.
+    let radius = points
+        .iter()
+        .map(|point| radial(axis, point).magnitude())
+        .max()
+        .unwrap_or(Scalar::ZERO);
+    let steps = if radius > Scalar::ZERO {
+        let vertices = Circle::number_of_vertices(tolerance, radius);
+        (Scalar::from_u64(vertices) * angle / (Scalar::PI * 2.))
+            .ceil()
+            .into_u64()
+            .max(1)
+    } else {
+        1
+    };
+
+    let normal = axis.direction.normalize();
+    let rotate = |step: u64| -> Vec<Point<3>> {
+        let transform = Transform::rotation(
+            axis.origin,
+            normal * angle * Scalar::from_u64(step) / Scalar::from_u64(steps),
+        );
+        points
+            .iter()
+            .map(|point| transform.transform_point(point))
+            .collect()
+    };
+
+    let mut triangles = Vec::new();
+    for step in 0..steps {
+        let start = rotate(step);
+        let end = rotate(step + 1);
+
+        for i in 0..points.len().saturating_sub(1) {
+            let [v0, v1] = [start[i], start[i + 1]];
+            let [v3, v2] = [end[i], end[i + 1]];
+
+            for [a, b, c] in [[v0, v1, v2], [v0, v2, v3]] {
+                // Points on the axis don't move, which leads to degenerate
+                // triangles.
+                if a == b || b == c || c == a {
+                    continue;
+                }
+
+                let mut triangle = Triangle::from([a, b, c]);
+                triangle.set_color(color);
+                triangles.push(triangle);
+            }
+        }
+    }
+
+    triangles
+}
+
+/// The circle that a point follows, when it is rotated around the axis
+fn path_circle(axis: &Line, point: Point<3>) -> Circle {
+    let normal = axis.direction.normalize();
+    let center = axis.origin + normal * (point - axis.origin).dot(&normal);
+
+    Circle {
+        center,
+        radius: point - center,
+        normal,
+    }
+}
+
+/// The vector from the axis to the point, perpendicular to the axis
+pub(super) fn radial(axis: &Line, point: &Point<3>) -> Vector<3> {
+    let normal = axis.direction.normalize();
+    let v = point - axis.origin;
+    v + normal * -v.dot(&normal)
+}
+
+/// Whether a point lies on the axis, and thus isn't moved by a rotation
+pub(super) fn is_on_axis(axis: &Line, point: &Point<3>) -> bool {
+    // Points that are this close would be considered the same vertex.
+    radial(axis, point).magnitude() < Scalar::from_f64(5e-7)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::test_helpers::{assert_area_between, rectangle, tolerance},
+        geometry::Line,
+    };
+
+    use super::{revolve_shape, RevolveError};
+
+    #[test]
+    fn full_turn() {
+        // A tube with an inner radius of 1, an outer radius of 2, and a height
+        // of 1.
+        let mut tube = revolve_shape(
+            rectangle([1., 0.], [2., 1.]),
+            y_axis(),
+            Scalar::PI * 2.,
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        // Two half-turns for each of the four edges.
+        assert_eq!(tube.topology().faces().count(), 8);
+        assert!(tube.validate().is_ok());
+
+        // The curved faces are approximated, so their area is a bit smaller.
+        let area = 2. * PI * 2. + 2. * PI + 2. * PI * (4. - 1.);
+        assert_area_between(tube, area * 0.99, area);
+    }
+
+    #[test]
+    fn partial_turn() {
+        // Half a cylinder, with a radius of 1 and a height of 1. One edge of
+        // the rectangle lies on the axis.
+        let mut half_cylinder = revolve_shape(
+            rectangle([0., 0.], [1., 1.]),
+            y_axis(),
+            Scalar::PI,
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        // Two caps, one curved face, and two half disks.
+        assert_eq!(half_cylinder.topology().faces().count(), 5);
+        assert!(half_cylinder.validate().is_ok());
+
+        let area = 2. + PI + PI / 2. * 2.;
+        assert_area_between(half_cylinder, area * 0.99, area);
+    }
+
+    #[test]
+    fn invalid_axis() {
+        for direction in [
+            Vector::from([0., 0., 0.]),
+            Vector::from([f64::INFINITY, 0., 0.]),
+        ] {
+            let axis = Line {
+                origin: Point::origin(),
+                direction,
+            };
+
+            let result = revolve_shape(
+                rectangle([1., 0.], [2., 1.]),
+                axis,
+                Scalar::PI,
+                tolerance(),
+                [255, 0, 0, 255],
+            );
+            assert!(matches!(result, Err(RevolveError::InvalidAxis)));
+        }
+    }
+
+    #[test]
+    fn zero_angle() {
+        let result = revolve_shape(
+            rectangle([1., 0.], [2., 1.]),
+            y_axis(),
+            Scalar::ZERO,
+            tolerance(),
+            [255, 0, 0, 255],
+        );
+        assert!(matches!(result, Err(RevolveError::ZeroAngle)));
+    }
+
+    fn y_axis() -> Line {
+        Line {
+            origin: Point::origin(),
+            direction: Vector::unit_y(),
+        }
+    }
+}
@@ -1,262 +1,357 @@
 use std::collections::HashMap;
 
-use fj_math::{Scalar, Segment, Transform, Triangle, Vector};
+use fj_math::{Point, Scalar, Segment, Transform, Triangle, Vector};
 
 use crate::{
-    geometry::{Surface, SweptCurve},
+    geometry::{Circle, Curve, Line, Surface, SweptCurve},
     shape::{Handle, Shape},
     topology::{Cycle, Edge, Face, Vertex},
 };
 
-use super::approximation::approximate_cycle;
+use super::{
+    approximation::approximate_edge_points,
+    loft,
+    revolve::{self, RevolveError},
+};
 
 /// Create a new shape by sweeping an existing one
 pub fn sweep_shape(
-    mut source: Shape,
+    source: Shape,
     path: Vector<3>,
     tolerance: Scalar,
     color: [u8; 4],
-) -> Shape {
-    let mut target = Shape::new();
-
-    let translation = Transform::translation(path);
+) -> Result<Shape, SweepError> {
+    sweep_shape_along(source, &[PathSegment::Line(path)], tolerance, color)
+}
 
-    let mut source_to_bottom = Relation::new();
-    let mut source_to_top = Relation::new();
+/// Create a new shape by sweeping an existing one along a path
+///
+/// The path starts where the shape is. Each segment of the path creates its
+/// own faces, and copies of the original shape close off both ends of the
+/// path.
+///
+/// The path must not intersect itself. Returns an error, if the path is empty,
+/// or if any of its segments is invalid (see [`validate_path`]).
+pub fn sweep_shape_along(
+    source: Shape,
+    path: &[PathSegment],
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Result<Shape, SweepError> {
+    validate_path(path)?;
+    Ok(sweep(source, path, false, tolerance, color))
+}
 
-    // Create the new vertices.
-    for vertex_source in source.topology().vertices() {
-        let point_bottom =
-            target.geometry().add_point(vertex_source.get().point());
-        let point_top = target.geometry().add_point(*point_bottom.get() + path);
+/// Create a new shape by sweeping an existing one along a path, while tapering
+/// and twisting it
+///
+/// The shape is expected to lie in the x-y plane. As it moves along the path,
+/// it is scaled towards the origin, such that the point of the shape farthest
+/// from the origin moves inward at the draft angle `taper` (in radians). Points
+/// closer to the origin move inward proportionally less. At the same time, the
+/// shape is rotated counter-clockwise around its z-axis, by `twist` (in
+/// radians) over the length of the path.
+///
+/// Side faces that aren't planar are approximated using triangles.
+///
+/// Returns an error, if the path is invalid (see [`validate_path`]), or if the
+/// taper is so large, that the shape shrinks to a point before the end of the
+/// path.
+pub fn sweep_shape_tapered(
+    mut source: Shape,
+    path: &[PathSegment],
+    taper: Scalar,
+    twist: Scalar,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Result<Shape, SweepError> {
+    validate_path(path)?;
 
-        let vertex_bottom = target
-            .topology()
-            .add_vertex(Vertex {
-                point: point_bottom,
-            })
-            .unwrap();
-        let vertex_top = target
-            .topology()
-            .add_vertex(Vertex { point: point_top })
-            .unwrap();
+    if taper == Scalar::ZERO && twist == Scalar::ZERO {
+        return sweep_shape_along(source, path, tolerance, color);
+    }
 
-        source_to_bottom
-            .vertices
-            .insert(vertex_source.clone(), vertex_bottom);
-        source_to_top.vertices.insert(vertex_source, vertex_top);
+    let radius = source
+        .topology()
+        .edges()
+        .flat_map(|edge| approximate_edge_points(&edge.get(), tolerance))
+        .map(|point| point.coords.magnitude())
+        .max()
+        .unwrap_or(Scalar::ZERO);
+
+    // Follow the origin of the shape along the path, to learn where each
+    // segment of the path starts, and how long it is.
+    let mut segments = Vec::new();
+    let mut placement = Transform::identity();
+    let mut length = Scalar::ZERO;
+    for segment in path {
+        let origin = placement.transform_point(&Point::origin());
+        let segment_length = segment.length(&origin);
+
+        segments.push((*segment, placement, length, segment_length));
+
+        placement = segment.transform() * placement;
+        length += segment_length;
     }
 
-    // Create the new edges.
-    for edge_source in source.topology().edges() {
-        let curve_bottom =
-            target.geometry().add_curve(edge_source.get().curve());
-        let curve_top = target
-            .geometry()
-            .add_curve(curve_bottom.get().transform(&translation));
+    // The shape shrinks the most at the end of the path. It must still have a
+    // size there, that the approximation can make out.
+    if radius != Scalar::ZERO {
+        let radius_end = radius - length * taper.tan();
+        if radius_end <= tolerance || !radius_end.into_f64().is_finite() {
+            return Err(SweepError::TaperTooLarge);
+        }
+    }
 
-        let vertices_bottom = source_to_bottom.vertices_for_edge(&edge_source);
-        let vertices_top = source_to_top.vertices_for_edge(&edge_source);
+    // Scale and rotate the shape, according to how far along the path it is.
+    let local = |distance: Scalar| {
+        if length == Scalar::ZERO || radius == Scalar::ZERO {
+            return Transform::identity();
+        }
 
-        let edge_bottom = target
-            .topology()
-            .add_edge(Edge {
-                curve: curve_bottom,
-                vertices: vertices_bottom,
-            })
-            .unwrap();
-        let edge_top = target
-            .topology()
-            .add_edge(Edge {
-                curve: curve_top,
-                vertices: vertices_top,
-            })
-            .unwrap();
+        Transform::similarity(
+            Vector::from([0., 0., 0.]),
+            Vector::unit_z() * twist * distance / length,
+            Scalar::ONE - distance * taper.tan() / radius,
+        )
+    };
 
-        source_to_bottom
-            .edges
-            .insert(edge_source.clone(), edge_bottom);
-        source_to_top.edges.insert(edge_source, edge_top);
+    let mut transforms = vec![local(Scalar::ZERO)];
+    for (segment, placement, start, segment_length) in segments {
+        // The approximation needs to be as fine as the approximation of a
+        // circle that the shape's points follow, for the twist and for arcs.
+        let fraction_of_turn = |angle: Scalar, radius: Scalar| {
+            if radius == Scalar::ZERO {
+                return 1;
+            }
+            let vertices = Circle::number_of_vertices(tolerance, radius);
+            (Scalar::from_u64(vertices) * angle.abs() / (Scalar::PI * 2.))
+                .ceil()
+                .into_u64()
+        };
+        let steps_twist = if length == Scalar::ZERO {
+            1
+        } else {
+            fraction_of_turn(twist * segment_length / length, radius)
+        };
+        let steps_path = match segment {
+            PathSegment::Line(_) => 1,
+            PathSegment::Arc { axis, angle } => {
+                let origin = placement.transform_point(&Point::origin());
+                let radius =
+                    revolve::radial(&axis, &origin).magnitude() + radius;
+                fraction_of_turn(angle, radius)
+            }
+        };
+        let steps = steps_twist.max(steps_path).max(1);
+
+        for step in 1..=steps {
+            let fraction = Scalar::from_u64(step) / Scalar::from_u64(steps);
+            transforms.push(
+                segment.part(fraction).transform()
+                    * placement
+                    * local(start + segment_length * fraction),
+            );
+        }
     }
 
-    // Create the new cycles.
-    for cycle_source in source.topology().cycles() {
-        let edges_bottom = source_to_bottom.edges_for_cycle(&cycle_source);
-        let edges_top = source_to_top.edges_for_cycle(&cycle_source);
+    Ok(loft::loft_transformed(
+        source,
+        &transforms,
+        tolerance,
+        color,
+    ))
+}
 
-        let cycle_bottom = target
-            .topology()
-            .add_cycle(Cycle {
-                edges: edges_bottom,
-            })
-            .unwrap();
-        let cycle_top = target
-            .topology()
-            .add_cycle(Cycle { edges: edges_top })
-            .unwrap();
+/// Check that a shape can be swept along a path
+///
+/// The path must have at least one segment. Lines must have a finite, non-zero
+/// length. Arcs must have an axis with a valid direction, and a non-zero angle.
+pub fn validate_path(path: &[PathSegment]) -> Result<(), SweepError> {
+    if path.is_empty() {
+        return Err(SweepError::EmptyPath);
+    }
 
-        source_to_bottom
-            .cycles
-            .insert(cycle_source.clone(), cycle_bottom);
-        source_to_top.cycles.insert(cycle_source, cycle_top);
+    for segment in path {
+        match segment {
+            PathSegment::Line(direction) => {
+                let length = direction.magnitude();
+                if length == Scalar::ZERO || !length.into_f64().is_finite() {
+                    return Err(SweepError::InvalidLine);
+                }
+            }
+            PathSegment::Arc { axis, angle } => {
+                revolve::validate_rotation(axis, *angle)
+                    .map_err(SweepError::InvalidArc)?;
+            }
+        }
     }
 
-    // Create top faces.
-    for face_source in source.topology().faces().values() {
-        let surface_bottom =
-            target.geometry().add_surface(face_source.surface());
-        let surface_top = target
-            .geometry()
-            .add_surface(surface_bottom.get().transform(&translation));
+    Ok(())
+}
 
-        let cycles_bottom = source_to_bottom.cycles_for_face(&face_source);
-        let cycles_top = source_to_top.cycles_for_face(&face_source);
+/// An error that can occur when sweeping a shape
+#[derive(Debug, thiserror::Error)]
+pub enum SweepError {
+    /// The path has no segments
+    #[error("Can't sweep along an empty path")]
+    EmptyPath,
 
-        target
-            .topology()
-            .add_face(Face::Face {
-                surface: surface_bottom,
-                cycles: cycles_bottom,
-                color,
-            })
-            .unwrap();
-        target
-            .topology()
-            .add_face(Face::Face {
-                surface: surface_top,
-                cycles: cycles_top,
-                color,
-            })
-            .unwrap();
-    }
+    /// A line of the path has a length of zero, or an infinite one
+    #[error("Can't sweep along a line of zero or infinite length")]
+    InvalidLine,
 
-    for cycle_source in source.topology().cycles() {
-        if cycle_source.get().edges.len() == 1 {
-            // If there's only one edge in the cycle, it must be a continuous
-            // edge that connects to itself. By sweeping that, we create a
-            // continuous face.
-            //
-            // Continuous faces aren't currently supported by the approximation
-            // code, and hence can't be triangulated. To address that, we fall
-            // back to the old and almost obsolete triangle representation to
-            // create the face.
-            //
-            // This is the last piece of code that still uses the triangle
-            // representation.
-
-            let approx = approximate_cycle(&cycle_source.get(), tolerance);
-
-            let mut quads = Vec::new();
-            for segment in approx.windows(2) {
-                let segment = Segment::from_points([segment[0], segment[1]]);
-
-                let [v0, v1] = segment.points();
-                let [v3, v2] = {
-                    let segment = Transform::translation(path)
-                        .transform_segment(&segment);
-                    segment.points()
+    /// An arc of the path is invalid
+    #[error("Can't sweep along an invalid arc")]
+    InvalidArc(#[source] RevolveError),
+
+    /// The taper shrinks the shape to a point before the end of the path
+    #[error("Taper is too large: The shape shrinks to a point")]
+    TaperTooLarge,
+}
+
+/// A segment of a path that a shape is swept along
+#[derive(Clone, Copy, Debug)]
+pub enum PathSegment {
+    /// A straight line, along which the shape is translated
+    Line(Vector<3>),
+
+    /// An arc, along which the shape is rotated around an axis
+    ///
+    /// The shape rotates counter-clockwise around the direction of `axis`, by
+    /// `angle` (in radians).
+    Arc {
+        /// The axis of the rotation
+        axis: Line,
+
+        /// The angle of the rotation
+        angle: Scalar,
+    },
+}
+
+impl PathSegment {
+    /// Split the segment into steps that can each be swept in one go
+    ///
+    /// Faces that are created by rotating an edge can't cover more than half a
+    /// turn, or their surface coordinates would become ambiguous.
+    fn steps(self) -> Vec<Self> {
+        match self {
+            Self::Line(_) => vec![self],
+            Self::Arc { axis, angle } => {
+                // Rotate clockwise rotations around the opposite direction
+                // instead, as surfaces assume counter-clockwise rotations.
+                let (axis, angle) = if angle < Scalar::ZERO {
+                    let axis = Line {
+                        origin: axis.origin,
+                        direction: axis.direction * -Scalar::ONE,
+                    };
+                    (axis, -angle)
+                } else {
+                    (axis, angle)
                 };
 
-                quads.push([v0, v1, v2, v3]);
+                let steps = (angle / Scalar::PI).ceil().into_u64().max(1);
+                let angle = angle / Scalar::from_u64(steps);
+
+                (0..steps).map(|_| Self::Arc { axis, angle }).collect()
             }
+        }
+    }
+
+    /// The part of the segment, from its start up to `fraction` of it
+    fn part(self, fraction: Scalar) -> Self {
+        match self {
+            Self::Line(path) => Self::Line(path * fraction),
+            Self::Arc { axis, angle } => Self::Arc {
+                axis,
+                angle: angle * fraction,
+            },
+        }
+    }
 
-            let mut side_face: Vec<Triangle<3>> = Vec::new();
-            for [v0, v1, v2, v3] in quads {
-                side_face.push([v0, v1, v2].into());
-                side_face.push([v0, v2, v3].into());
+    /// The length of the path that a point follows along the segment
+    fn length(&self, point: &Point<3>) -> Scalar {
+        match self {
+            Self::Line(path) => path.magnitude(),
+            Self::Arc { axis, angle } => {
+                revolve::radial(axis, point).magnitude() * angle.abs()
             }
+        }
+    }
+
+    fn transform(&self) -> Transform {
+        match self {
+            Self::Line(path) => Transform::translation(*path),
+            Self::Arc { axis, angle } => Transform::rotation(
+                axis.origin,
+                axis.direction.normalize() * *angle,
+            ),
+        }
+    }
+}
+
+/// Sweep a shape along a path
+///
+/// If `closed` is `true`, the path is expected to end where it started. In that
+/// case, no copies of the original shape are created to close it off.
+pub(super) fn sweep(
+    mut source: Shape,
+    path: &[PathSegment],
+    closed: bool,
+    tolerance: Scalar,
+    color: [u8; 4],
+) -> Shape {
+    let mut target = Shape::new();
+
+    let steps: Vec<_> =
+        path.iter().flat_map(|segment| segment.steps()).collect();
+
+    // The shape is copied to the start of every step, as well as to the end of
+    // the last one, unless that is the start of the first.
+    let mut positions = vec![first_position(&mut source, &mut target)];
+    let last = if closed { steps.len() - 1 } else { steps.len() };
+    for step in &steps[..last] {
+        let position = next_position(
+            &mut source,
+            &mut target,
+            positions.last().unwrap(),
+            step,
+        );
+        positions.push(position);
+    }
 
-            // FIXME: We probably want to allow the use of custom colors for the
-            // "walls" of the swept object.
-            for s in side_face.iter_mut() {
-                s.set_color(color);
+    // Close off both ends.
+    if !closed {
+        let transform_last = |surface: Surface| {
+            steps.iter().fold(surface, |surface, step| {
+                surface.transform(&step.transform())
+            })
+        };
+
+        for (position, is_last) in [(0, false), (positions.len() - 1, true)] {
+            let relation = &mut positions[position];
+
+            for cycle_source in source.topology().cycles() {
+                let edges = relation.edges_for_cycle(&cycle_source);
+                let cycle =
+                    target.topology().add_cycle(Cycle { edges }).unwrap();
+                relation.cycles.insert(cycle_source, cycle);
             }
 
-            target
-                .topology()
-                .add_face(Face::Triangles(side_face))
-                .unwrap();
-        } else {
-            // If there's no continuous edge, we can create the non-
-            // continuous faces using boundary representation.
-
-            let mut vertex_bottom_to_edge = HashMap::new();
-
-            for edge_source in &cycle_source.get().edges {
-                // Can't panic. We already ruled out the continuous edge case
-                // above, so this edge must have vertices.
-                let vertices_source =
-                    edge_source.get().vertices.clone().unwrap();
-
-                // Create (or retrieve from the cache, `vertex_bottom_to_edge`)
-                // side edges from the vertices of this source/bottom edge.
-                let [side_edge_a, side_edge_b] =
-                    vertices_source.map(|vertex_source| {
-                        let vertex_bottom = source_to_bottom
-                            .vertices
-                            .get(&vertex_source)
-                            .unwrap()
-                            .clone();
-
-                        vertex_bottom_to_edge
-                            .entry(vertex_bottom.clone())
-                            .or_insert_with(|| {
-                                let curve = target
-                                    .geometry()
-                                    .add_curve(edge_source.get().curve());
-
-                                let vertex_top = source_to_top
-                                    .vertices
-                                    .get(&vertex_source)
-                                    .unwrap()
-                                    .clone();
-
-                                target
-                                    .topology()
-                                    .add_edge(Edge {
-                                        curve,
-                                        vertices: Some([
-                                            vertex_bottom,
-                                            vertex_top,
-                                        ]),
-                                    })
-                                    .unwrap()
-                            })
-                            .clone()
-                    });
-
-                // Now we have everything we need to create the side face from
-                // this source/bottom edge.
-
-                let bottom_edge =
-                    source_to_bottom.edges.get(edge_source).unwrap().clone();
-                let top_edge =
-                    source_to_top.edges.get(edge_source).unwrap().clone();
-
-                let surface = target.geometry().add_surface(
-                    Surface::SweptCurve(SweptCurve {
-                        curve: bottom_edge.get().curve(),
-                        path,
-                    }),
-                );
-
-                let cycle = target
-                    .topology()
-                    .add_cycle(Cycle {
-                        edges: vec![
-                            bottom_edge,
-                            top_edge,
-                            side_edge_a,
-                            side_edge_b,
-                        ],
-                    })
-                    .unwrap();
+            for face_source in source.topology().faces().values() {
+                let surface = if is_last {
+                    transform_last(face_source.surface())
+                } else {
+                    face_source.surface()
+                };
+                let surface = target.geometry().add_surface(surface);
+                let cycles = relation.cycles_for_face(&face_source);
 
                 target
                     .topology()
                     .add_face(Face::Face {
                         surface,
-                        cycles: vec![cycle],
+                        cycles,
                         color,
                     })
                     .unwrap();
@@ -264,17 +359,258 @@ pub fn sweep_shape(
         }
     }
 
+    // Create the side faces.
+    for (i, step) in steps.iter().enumerate() {
+        let start = &positions[i];
+        let end = &positions[(i + 1) % positions.len()];
+
+        match *step {
+            PathSegment::Line(path) => sweep_side_faces(
+                &mut source,
+                &mut target,
+                [start, end],
+                path,
+                tolerance,
+                color,
+            ),
+            PathSegment::Arc { axis, angle } => revolve::revolve_side_faces(
+                &mut source,
+                &mut target,
+                [start, end],
+                axis,
+                angle,
+                tolerance,
+                color,
+            ),
+        }
+    }
+
     target
 }
 
-struct Relation {
-    vertices: HashMap<Handle<Vertex>, Handle<Vertex>>,
-    edges: HashMap<Handle<Edge>, Handle<Edge>>,
-    cycles: HashMap<Handle<Cycle>, Handle<Cycle>>,
+/// Copy the vertices and edges of the source shape into the target shape
+pub(super) fn first_position(
+    source: &mut Shape,
+    target: &mut Shape,
+) -> Relation {
+    let mut relation = Relation::new();
+
+    for vertex_source in source.topology().vertices() {
+        let point = target.geometry().add_point(vertex_source.get().point());
+        let vertex = target.topology().add_vertex(Vertex { point }).unwrap();
+
+        relation.vertices.insert(vertex_source, vertex);
+    }
+
+    for edge_source in source.topology().edges() {
+        let curve = target.geometry().add_curve(edge_source.get().curve());
+        let vertices = relation.vertices_for_edge(&edge_source);
+        let edge = target
+            .topology()
+            .add_edge(Edge { curve, vertices })
+            .unwrap();
+
+        relation.edges.insert(edge_source, edge);
+    }
+
+    relation
+}
+
+/// Create the vertices and edges at the end of a step of the path
+///
+/// Vertices that aren't moved by the step (because they lie on the axis of a
+/// rotation) are shared with the previous position, as are edges between them.
+fn next_position(
+    source: &mut Shape,
+    target: &mut Shape,
+    previous: &Relation,
+    step: &PathSegment,
+) -> Relation {
+    let transform = step.transform();
+    let mut relation = Relation::new();
+
+    for vertex_source in source.topology().vertices() {
+        let vertex_previous = previous.vertices.get(&vertex_source).unwrap();
+        let point = vertex_previous.get().point();
+
+        let is_stationary = match step {
+            PathSegment::Line(_) => false,
+            PathSegment::Arc { axis, .. } => revolve::is_on_axis(axis, &point),
+        };
+        let vertex = if is_stationary {
+            vertex_previous.clone()
+        } else {
+            let point = target
+                .geometry()
+                .add_point(transform.transform_point(&point));
+            target.topology().add_vertex(Vertex { point }).unwrap()
+        };
+
+        relation.vertices.insert(vertex_source, vertex);
+    }
+
+    for edge_source in source.topology().edges() {
+        let edge_previous = previous.edges.get(&edge_source).unwrap();
+        let vertices = relation.vertices_for_edge(&edge_source);
+
+        let is_stationary =
+            matches!(edge_previous.get().curve(), Curve::Line(_))
+                && vertices == edge_previous.get().vertices;
+        let edge = if is_stationary {
+            edge_previous.clone()
+        } else {
+            let curve = target
+                .geometry()
+                .add_curve(edge_previous.get().curve().transform(&transform));
+            target
+                .topology()
+                .add_edge(Edge { curve, vertices })
+                .unwrap()
+        };
+
+        relation.edges.insert(edge_source, edge);
+    }
+
+    relation
+}
+
+/// Create the faces that a step along a straight path creates
+fn sweep_side_faces(
+    source: &mut Shape,
+    target: &mut Shape,
+    [start, end]: [&Relation; 2],
+    path: Vector<3>,
+    tolerance: Scalar,
+    color: [u8; 4],
+) {
+    let mut vertex_start_to_edge = HashMap::new();
+
+    for edge_source in source.topology().edges() {
+        let edge_start = start.edges.get(&edge_source).unwrap().clone();
+        let edge_end = end.edges.get(&edge_source).unwrap().clone();
+
+        let vertices_source = match edge_source.get().vertices.clone() {
+            Some(vertices_source) => vertices_source,
+            None => {
+                // If the edge has no vertices, it must be a continuous edge
+                // that connects to itself. By sweeping that, we create a
+                // continuous face.
+                //
+                // Continuous faces aren't currently supported by the
+                // approximation code, and hence can't be triangulated. To
+                // address that, we fall back to the old and almost obsolete
+                // triangle representation to create the face.
+                let approx =
+                    approximate_edge_points(&edge_start.get(), tolerance);
+                let translation = Transform::translation(path);
+
+                let mut quads = Vec::new();
+                for segment in approx.windows(2) {
+                    let segment =
+                        Segment::from_points([segment[0], segment[1]]);
+
+                    let [v0, v1] = segment.points();
+                    let [v3, v2] =
+                        translation.transform_segment(&segment).points();
+
+                    quads.push([v0, v1, v2, v3]);
+                }
+
+                let mut side_face: Vec<Triangle<3>> = Vec::new();
+                for [v0, v1, v2, v3] in quads {
+                    side_face.push([v0, v1, v2].into());
+                    side_face.push([v0, v2, v3].into());
+                }
+
+                // FIXME: We probably want to allow the use of custom colors for
+                // the "walls" of the swept object.
+                for s in side_face.iter_mut() {
+                    s.set_color(color);
+                }
+
+                target
+                    .topology()
+                    .add_face(Face::Triangles(side_face))
+                    .unwrap();
+
+                continue;
+            }
+        };
+
+        // If there's no continuous edge, we can create the non-continuous
+        // faces using boundary representation.
+
+        // Create (or retrieve from the cache, `vertex_start_to_edge`) side
+        // edges from the vertices of this edge.
+        let [side_edge_a, side_edge_b] = vertices_source.map(|vertex_source| {
+            let vertex_start =
+                start.vertices.get(&vertex_source).unwrap().clone();
+
+            vertex_start_to_edge
+                .entry(vertex_start.clone())
+                .or_insert_with(|| {
+                    let vertex_end =
+                        end.vertices.get(&vertex_source).unwrap().clone();
+
+                    let curve = target.geometry().add_curve(Curve::Line(
+                        Line::from_points(
+                            [&vertex_start, &vertex_end]
+                                .map(|vertex| vertex.get().point()),
+                        ),
+                    ));
+
+                    target
+                        .topology()
+                        .add_edge(Edge {
+                            curve,
+                            vertices: Some([vertex_start, vertex_end]),
+                        })
+                        .unwrap()
+                })
+                .clone()
+        });
+
+        // Now we have everything we need to create the side face from this
+        // edge.
+
+        let surface =
+            target
+                .geometry()
+                .add_surface(Surface::SweptCurve(SweptCurve {
+                    curve: edge_start.get().curve(),
+                    path,
+                }));
+
+        // The edges are ordered such, that each one connects to the next.
+        // Otherwise the approximation of the face would be wrong, which matters
+        // for curved faces.
+        let cycle = target
+            .topology()
+            .add_cycle(Cycle {
+                edges: vec![edge_start, side_edge_b, edge_end, side_edge_a],
+            })
+            .unwrap();
+
+        target
+            .topology()
+            .add_face(Face::Face {
+                surface,
+                cycles: vec![cycle],
+                color,
+            })
+            .unwrap();
+    }
+}
+
+/// Relates the objects of a source shape to the objects created from them
+pub(super) struct Relation {
+    pub(super) vertices: HashMap<Handle<Vertex>, Handle<Vertex>>,
+    pub(super) edges: HashMap<Handle<Edge>, Handle<Edge>>,
+    pub(super) cycles: HashMap<Handle<Cycle>, Handle<Cycle>>,
 }
 
 impl Relation {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         Self {
             vertices: HashMap::new(),
             edges: HashMap::new(),
@@ -282,7 +618,7 @@ impl Relation {
         }
     }
 
-    fn vertices_for_edge(
+    pub(super) fn vertices_for_edge(
         &self,
         edge: &Handle<Edge>,
     ) -> Option<[Handle<Vertex>; 2]> {
@@ -291,7 +627,10 @@ impl Relation {
         })
     }
 
-    fn edges_for_cycle(&self, cycle: &Handle<Cycle>) -> Vec<Handle<Edge>> {
+    pub(super) fn edges_for_cycle(
+        &self,
+        cycle: &Handle<Cycle>,
+    ) -> Vec<Handle<Edge>> {
         cycle
             .get()
             .edges
@@ -300,7 +639,7 @@ impl Relation {
             .collect()
     }
 
-    fn cycles_for_face(&self, face: &Face) -> Vec<Handle<Cycle>> {
+    pub(super) fn cycles_for_face(&self, face: &Face) -> Vec<Handle<Cycle>> {
         let cycles = match face {
             Face::Face { cycles, .. } => cycles,
             _ => {
@@ -319,15 +658,23 @@ impl Relation {
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::{FRAC_PI_2, SQRT_2};
+
     use fj_math::{Point, Scalar, Vector};
 
     use crate::{
-        geometry::{Surface, SweptCurve},
+        algorithms::test_helpers::{
+            assert_area, assert_area_between, tolerance,
+        },
+        geometry::{Line, Surface, SweptCurve},
         shape::{Handle, Shape},
         topology::{Cycle, Face, Vertex},
     };
 
-    use super::sweep_shape;
+    use super::{
+        sweep_shape, sweep_shape_along, sweep_shape_tapered, PathSegment,
+        SweepError,
+    };
 
     #[test]
     fn sweep() {
@@ -338,7 +685,8 @@ mod tests {
             Vector::from([0., 0., 1.]),
             Scalar::from_f64(0.),
             [255, 0, 0, 255],
-        );
+        )
+        .unwrap();
 
         let bottom_face = sketch.face.get().clone();
         let top_face =
@@ -368,6 +716,213 @@ mod tests {
         // plan is to start testing them, as they are transitioned to b-rep.
     }
 
+    #[test]
+    fn side_faces() {
+        let sketch = Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+
+        let mut swept = sweep_shape(
+            sketch.shape,
+            Vector::from([0., 0., 1.]),
+            Scalar::from_f64(0.),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        for face in swept.topology().faces() {
+            for cycle in face.get().cycles() {
+                let edges = &cycle.edges;
+
+                for (i, edge) in edges.iter().enumerate() {
+                    let vertices = edge.get().vertices.clone().unwrap();
+
+                    // Each edge's vertices lie on its curve.
+                    let curve = edge.get().curve();
+                    for vertex in &vertices {
+                        let point = vertex.get().point();
+                        let on_curve = curve.point_curve_to_model(
+                            &curve.point_model_to_curve(&point),
+                        );
+                        assert!(
+                            (on_curve - point).magnitude()
+                                < Scalar::from_f64(1e-12)
+                        );
+                    }
+
+                    // Each edge connects to the next one in the cycle.
+                    let next = edges[(i + 1) % edges.len()].get();
+                    let next_vertices = next.vertices.clone().unwrap();
+                    assert!(vertices
+                        .iter()
+                        .any(|vertex| next_vertices.contains(vertex)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_oblique() {
+        let sketch = Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+
+        let swept = sweep_shape(
+            sketch.shape,
+            Vector::from([1., 0., 1.]),
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+        assert!(swept.validate().is_ok());
+
+        // Bottom and top, and a parallelogram for each edge.
+        let area = 0.5 * 2. + 1. + 3f64.sqrt() + SQRT_2;
+        assert_area(swept, area);
+    }
+
+    #[test]
+    fn sweep_along_path() {
+        let sketch = Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+
+        // Go up, then bend away from the axis of the arc, which is parallel to
+        // the y axis.
+        let path = [
+            PathSegment::Line(Vector::from([0., 0., 1.])),
+            PathSegment::Arc {
+                axis: Line {
+                    origin: Point::from([3., 0., 1.]),
+                    direction: Vector::unit_y(),
+                },
+                angle: Scalar::from_f64(FRAC_PI_2),
+            },
+        ];
+
+        let mut swept = sweep_shape_along(
+            sketch.shape,
+            &path,
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+
+        // Bottom and top, and a face per edge, for each segment.
+        assert_eq!(swept.topology().faces().count(), 2 + 3 + 3);
+        assert!(swept.validate().is_ok());
+
+        // The area of the bend follows from Pappus's centroid theorem: The
+        // length of each edge, times the distance its centroid travels.
+        let straight = 1. + SQRT_2 + 1.;
+        let bend = FRAC_PI_2 * (2.5 + SQRT_2 * 2.5 + 3.);
+        // Curved faces are approximated, so their area is a bit smaller.
+        let area = 0.5 * 2. + straight + bend;
+        assert_area_between(swept, area * 0.99, area);
+    }
+
+    #[test]
+    fn sweep_tapered() {
+        let sketch = Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+
+        // The points farthest from the origin move inward by half, so the top
+        // is half the size of the bottom.
+        let mut swept = sweep_shape_tapered(
+            sketch.shape,
+            &[PathSegment::Line(Vector::from([0., 0., 1.]))],
+            Scalar::from_f64(0.5f64.atan()),
+            Scalar::ZERO,
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+        assert!(swept.validate().is_ok());
+
+        // Scaling keeps the edges parallel, so all side faces are planar.
+        assert_eq!(swept.topology().faces().count(), 5);
+        assert!(swept
+            .topology()
+            .faces()
+            .values()
+            .all(|face| matches!(face, Face::Face { .. })));
+
+        // Bottom, top, two trapezoids along the axes, and one along the
+        // hypotenuse.
+        assert_area(swept, 0.5 + 0.125 + 0.75 * 2. + 1.125);
+    }
+
+    #[test]
+    fn invalid_path() {
+        let sweep = |path: &[PathSegment]| {
+            let sketch =
+                Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+            sweep_shape_along(sketch.shape, path, tolerance(), [255, 0, 0, 255])
+        };
+
+        assert!(matches!(sweep(&[]), Err(SweepError::EmptyPath)));
+        assert!(matches!(
+            sweep(&[PathSegment::Line(Vector::from([0., 0., 0.]))]),
+            Err(SweepError::InvalidLine)
+        ));
+
+        let arc = |direction: [f64; 3], angle: f64| PathSegment::Arc {
+            axis: Line {
+                origin: Point::from([3., 0., 1.]),
+                direction: Vector::from(direction),
+            },
+            angle: Scalar::from_f64(angle),
+        };
+        assert!(matches!(
+            sweep(&[arc([0., 0., 0.], FRAC_PI_2)]),
+            Err(SweepError::InvalidArc(_))
+        ));
+        assert!(matches!(
+            sweep(&[arc([0., 1., 0.], 0.)]),
+            Err(SweepError::InvalidArc(_))
+        ));
+    }
+
+    #[test]
+    fn taper_too_large() {
+        // The points farthest from the origin are at a distance of 1. At a
+        // draft angle of 45 degrees, they reach the origin after a length of
+        // 1, and would move beyond it after that.
+        for length in [1., 2.] {
+            let sketch =
+                Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+
+            let result = sweep_shape_tapered(
+                sketch.shape,
+                &[PathSegment::Line(Vector::from([0., 0., length]))],
+                Scalar::PI / 4.,
+                Scalar::ZERO,
+                tolerance(),
+                [255, 0, 0, 255],
+            );
+            assert!(matches!(result, Err(SweepError::TaperTooLarge)));
+        }
+    }
+
+    #[test]
+    fn sweep_twisted() {
+        let sketch = Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+
+        let mut swept = sweep_shape_tapered(
+            sketch.shape,
+            &[PathSegment::Line(Vector::from([0., 0., 1.]))],
+            Scalar::ZERO,
+            Scalar::from_f64(FRAC_PI_2),
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+        assert!(swept.validate().is_ok());
+
+        // The top is rotated by a quarter turn.
+        for expected in [[0., 0., 1.], [0., 1., 1.], [-1., 0., 1.]] {
+            let expected = Point::from(expected);
+            assert!(swept
+                .geometry()
+                .points()
+                .any(|point| (*point.get() - expected).magnitude()
+                    < Scalar::from_f64(1e-12)));
+        }
+    }
+
     pub struct Triangle {
         shape: Shape,
         face: Handle<Face>,
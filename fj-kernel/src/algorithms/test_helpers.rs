@@ -0,0 +1,162 @@
+//! Fixtures and assertions that are shared by the tests of the algorithms
+
+use fj_debug::DebugInfo;
+use fj_math::{Point, Scalar, Transform, Triangle, Vector};
+
+use crate::{
+    geometry::{Circle, Curve, Surface},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{sweep_shape, triangulate};
+
+/// The tolerance that the tests approximate curves with
+pub fn tolerance() -> Scalar {
+    Scalar::from_f64(0.001)
+}
+
+/// A rectangle in the x-y plane
+pub fn rectangle(min: [f64; 2], max: [f64; 2]) -> Shape {
+    let mut shape = Shape::new();
+
+    let cycle = rectangle_cycle(&mut shape, min, max);
+    add_face(&mut shape, vec![cycle]);
+
+    shape
+}
+
+/// A square around the origin, in the x-y plane
+pub fn square(size: f64) -> Shape {
+    let half = size / 2.;
+    rectangle([-half, -half], [half, half])
+}
+
+/// A counter-clockwise cycle around a rectangle in the x-y plane
+pub fn rectangle_cycle(
+    shape: &mut Shape,
+    min: [f64; 2],
+    max: [f64; 2],
+) -> Handle<Cycle> {
+    let corners = [
+        [min[0], min[1]],
+        [max[0], min[1]],
+        [max[0], max[1]],
+        [min[0], max[1]],
+    ]
+    .map(|[x, y]| {
+        let point = shape.geometry().add_point(Point::from([x, y, 0.]));
+        shape.topology().add_vertex(Vertex { point }).unwrap()
+    });
+
+    let edges = (0..corners.len())
+        .map(|i| {
+            let a = corners[i].clone();
+            let b = corners[(i + 1) % corners.len()].clone();
+            shape.topology().add_line_segment([a, b]).unwrap()
+        })
+        .collect();
+
+    shape.topology().add_cycle(Cycle { edges }).unwrap()
+}
+
+/// A circle in the x-y plane
+pub fn circle(center: [f64; 2], radius: f64) -> Shape {
+    let mut shape = Shape::new();
+
+    let curve = shape.geometry().add_curve(Curve::Circle(Circle {
+        center: Point::from([center[0], center[1], 0.]),
+        radius: Vector::from([radius, 0., 0.]),
+        normal: Vector::unit_z(),
+    }));
+    let edge = shape
+        .topology()
+        .add_edge(Edge {
+            curve,
+            vertices: None,
+        })
+        .unwrap();
+    let cycle = shape
+        .topology()
+        .add_cycle(Cycle { edges: vec![edge] })
+        .unwrap();
+    add_face(&mut shape, vec![cycle]);
+
+    shape
+}
+
+/// An axis-aligned cuboid
+pub fn cuboid(min: [f64; 3], max: [f64; 3]) -> Shape {
+    let bottom = translated(
+        rectangle([min[0], min[1]], [max[0], max[1]]),
+        [0., 0., min[2]],
+    );
+
+    sweep_shape(
+        bottom,
+        Vector::from([0., 0., max[2] - min[2]]),
+        tolerance(),
+        [255, 0, 0, 255],
+    )
+    .unwrap()
+}
+
+/// Translate a shape
+pub fn translated(mut shape: Shape, offset: [f64; 3]) -> Shape {
+    shape
+        .geometry()
+        .transform(&Transform::translation(Vector::from(offset)));
+    shape
+}
+
+/// Add a face in the x-y plane, that is bounded by the cycles
+pub fn add_face(shape: &mut Shape, cycles: Vec<Handle<Cycle>>) {
+    let surface = shape.geometry().add_surface(Surface::x_y_plane());
+    shape
+        .topology()
+        .add_face(Face::Face {
+            surface,
+            cycles,
+            color: [255, 0, 0, 255],
+        })
+        .unwrap();
+}
+
+/// Triangulate a shape
+pub fn triangles(shape: Shape) -> Vec<Triangle<3>> {
+    let mut triangles = Vec::new();
+    triangulate(shape, tolerance(), &mut triangles, &mut DebugInfo::new());
+    triangles
+}
+
+/// Assert the area of a shape's surface
+pub fn assert_area(shape: Shape, expected: f64) {
+    assert_area_between(shape, expected, expected);
+}
+
+/// Assert the area of a shape's surface, up to a precision
+pub fn assert_area_approx(shape: Shape, expected: f64, precision: f64) {
+    assert_area_between(shape, expected - precision, expected + precision);
+}
+
+/// Assert that the area of a shape's surface is within a range
+///
+/// Curved edges are approximated, so the area of shapes that have them can
+/// only be checked for a range.
+pub fn assert_area_between(shape: Shape, min: f64, max: f64) {
+    let area: f64 = triangles(shape)
+        .iter()
+        .map(|triangle| {
+            let [a, b, c] = triangle.points();
+            (b - a).cross(&(c - a)).magnitude().into_f64() / 2.
+        })
+        .sum();
+
+    assert!(
+        area >= min - 1e-9 && area <= max + 1e-9,
+        "Expected area between {} and {}, got {}",
+        min,
+        max,
+        area
+    );
+}
@@ -13,7 +13,13 @@ pub struct Circle {
     /// The radius is represented by a vector that points from the center to the
     /// circumference. The point on the circumference that it points to defines
     /// the origin of the circle's 1-dimensional curve coordinate system.
-    pub radius: Vector<2>,
+    pub radius: Vector<3>,
+
+    /// The normal of the plane that the circle lies in
+    ///
+    /// Must be a unit vector that is perpendicular to the radius. The circle
+    /// goes counter-clockwise around the normal.
+    pub normal: Vector<3>,
 }
 
 impl Circle {
@@ -25,20 +31,18 @@ impl Circle {
     /// Create a new instance that is transformed by `transform`
     #[must_use]
     pub fn transform(self, transform: &Transform) -> Self {
-        let radius = self.radius.to_xyz();
-        let radius = transform.transform_vector(&radius);
-        let radius = radius.xy();
-
         Self {
             center: transform.transform_point(&self.center),
-            radius,
+            radius: transform.transform_vector(&self.radius),
+            normal: transform.transform_vector(&self.normal).normalize(),
         }
     }
 
     /// Convert a point in model coordinates to curve coordinates
     ///
     /// Converts the provided point into curve coordinates between `0.`
-    /// (inclusive) and `PI * 2.` (exclusive).
+    /// (inclusive) and `PI * 2.` (exclusive). The curve coordinate is the angle
+    /// between the radius and the point, counter-clockwise.
     ///
     /// Projects the point onto the circle before computing curve coordinate,
     /// ignoring the radius. This is done to make this method robust against
@@ -49,7 +53,10 @@ impl Circle {
     /// error.
     pub fn point_model_to_curve(&self, point: &Point<3>) -> Point<1> {
         let v = point - self.center;
-        let atan = Scalar::atan2(v.y, v.x);
+        let atan = Scalar::atan2(
+            v.dot(&self.normal.cross(&self.radius)),
+            v.dot(&self.radius),
+        );
         let coord = if atan >= Scalar::ZERO {
             atan
         } else {
@@ -65,15 +72,11 @@ impl Circle {
 
     /// Convert a vector on the curve into model coordinates
     pub fn vector_curve_to_model(&self, vector: &Vector<1>) -> Vector<3> {
-        let radius = self.radius.magnitude();
         let angle = vector.t;
-
         let (sin, cos) = angle.sin_cos();
 
-        let x = cos * radius;
-        let y = sin * radius;
-
-        Vector::from([x, y, Scalar::ZERO])
+        // Rotate the radius around the normal by the angle.
+        self.radius * cos + self.normal.cross(&self.radius) * sin
     }
 
     /// Approximate the circle
@@ -98,7 +101,7 @@ impl Circle {
         }
     }
 
-    fn number_of_vertices(tolerance: Scalar, radius: Scalar) -> u64 {
+    pub(crate) fn number_of_vertices(tolerance: Scalar, radius: Scalar) -> u64 {
         assert!(tolerance > Scalar::ZERO);
         if tolerance > radius / Scalar::TWO {
             3
@@ -114,6 +117,7 @@ impl Circle {
 mod tests {
     use std::f64::consts::{FRAC_PI_2, PI};
 
+    use approx::assert_abs_diff_eq;
     use fj_math::{Point, Scalar, Vector};
 
     use super::Circle;
@@ -122,7 +126,8 @@ mod tests {
     fn point_model_to_curve() {
         let circle = Circle {
             center: Point::from([1., 2., 3.]),
-            radius: Vector::from([1., 0.]),
+            radius: Vector::from([1., 0., 0.]),
+            normal: Vector::unit_z(),
         };
 
         assert_eq!(
@@ -143,6 +148,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn point_model_to_curve_rotated() {
+        // The radius defines where the curve coordinates start.
+        let circle = Circle {
+            center: Point::from([1., 2., 3.]),
+            radius: Vector::from([0., 1., 0.]),
+            normal: Vector::unit_z(),
+        };
+
+        assert_eq!(
+            circle.point_model_to_curve(&Point::from([1., 3., 3.])),
+            Point::from([0.]),
+        );
+        assert_eq!(
+            circle.point_model_to_curve(&Point::from([0., 2., 3.])),
+            Point::from([FRAC_PI_2]),
+        );
+        assert_abs_diff_eq!(
+            circle.point_curve_to_model(&Point::from([PI])),
+            Point::from([1., 1., 3.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    #[test]
+    fn point_curve_to_model_tilted() {
+        // A circle in the x-z plane.
+        let circle = Circle {
+            center: Point::from([1., 2., 3.]),
+            radius: Vector::from([1., 0., 0.]),
+            normal: Vector::unit_y(),
+        };
+
+        let point = circle.point_curve_to_model(&Point::from([FRAC_PI_2]));
+        assert_abs_diff_eq!(point, Point::from([1., 2., 2.]), epsilon = 1e-12,);
+        assert_abs_diff_eq!(
+            circle.point_model_to_curve(&point),
+            Point::from([FRAC_PI_2]),
+            epsilon = 1e-12,
+        );
+    }
+
     #[test]
     fn number_of_vertices() {
         verify_result(50., 100., 3);
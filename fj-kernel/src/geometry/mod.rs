@@ -12,5 +12,5 @@ mod surfaces;
 pub use self::{
     curves::{Circle, Curve, Line},
     points::Point,
-    surfaces::{Surface, SweptCurve},
+    surfaces::{RevolvedCurve, Surface, SweptCurve},
 };
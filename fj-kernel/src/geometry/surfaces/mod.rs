@@ -1,6 +1,7 @@
+pub mod revolved;
 pub mod swept;
 
-pub use self::swept::SweptCurve;
+pub use self::{revolved::RevolvedCurve, swept::SweptCurve};
 
 use fj_math::{Point, Transform, Vector};
 
@@ -13,6 +14,9 @@ use super::{Curve, Line};
 pub enum Surface {
     /// A swept curve
     SweptCurve(SweptCurve),
+
+    /// A revolved curve
+    RevolvedCurve(RevolvedCurve),
 }
 
 impl Surface {
@@ -34,6 +38,9 @@ impl Surface {
             Self::SweptCurve(surface) => {
                 Self::SweptCurve(surface.transform(transform))
             }
+            Self::RevolvedCurve(surface) => {
+                Self::RevolvedCurve(surface.transform(transform))
+            }
         }
     }
 
@@ -46,6 +53,9 @@ impl Surface {
             Self::SweptCurve(surface) => {
                 surface.point_model_to_surface(&point_3d)
             }
+            Self::RevolvedCurve(surface) => {
+                surface.point_model_to_surface(&point_3d)
+            }
         };
 
         geometry::Point::new(point_2d, point_3d)
@@ -55,6 +65,9 @@ impl Surface {
     pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
         match self {
             Self::SweptCurve(surface) => surface.point_surface_to_model(point),
+            Self::RevolvedCurve(surface) => {
+                surface.point_surface_to_model(point)
+            }
         }
     }
 
@@ -64,6 +77,9 @@ impl Surface {
             Self::SweptCurve(surface) => {
                 surface.vector_surface_to_model(vector)
             }
+            Self::RevolvedCurve(surface) => {
+                surface.vector_surface_to_model(vector)
+            }
         }
     }
 }
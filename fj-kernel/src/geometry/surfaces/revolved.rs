@@ -0,0 +1,180 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::geometry::{Curve, Line};
+
+/// A surface that was created by revolving a curve around an axis
+///
+/// The first surface coordinate is the curve coordinate, the second one is the
+/// angle of the revolution, in radians.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct RevolvedCurve {
+    /// The curve that this surface was revolved from
+    pub curve: Curve,
+
+    /// The axis that the curve was revolved around
+    ///
+    /// The curve revolves counter-clockwise around the direction of the axis.
+    /// The curve must not cross the axis.
+    pub axis: Line,
+}
+
+impl RevolvedCurve {
+    /// Transform the surface
+    #[must_use]
+    pub fn transform(mut self, transform: &Transform) -> Self {
+        self.curve = self.curve.transform(transform);
+        self.axis = self.axis.transform(transform);
+        self
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// The angle is returned in the range from `-PI / 2.` (inclusive) to
+    /// `PI * 3. / 2.` (exclusive). This covers the faces that revolving a curve
+    /// by half a turn creates, even if points are a tiny bit off.
+    pub fn point_model_to_surface(&self, point: &Point<3>) -> Point<2> {
+        let normal = self.normal();
+        let reference = self.reference();
+
+        let radial = self.radial(point);
+        let mut v = Scalar::atan2(
+            radial.dot(&normal.cross(&reference)),
+            radial.dot(&reference),
+        );
+        if v < -Scalar::PI / 2. {
+            v += Scalar::PI * 2.;
+        }
+
+        let unrotated = Transform::rotation(self.axis.origin, normal * -v)
+            .transform_point(point);
+        let u = self.curve.point_model_to_curve(&unrotated).t;
+
+        // Rotating the point back introduces floating point noise. Points that
+        // lie on the same circle would end up with slightly different curve
+        // coordinates, which confuses the triangulation. Rounding gets rid of
+        // that noise, while staying far below any sensible tolerance.
+        let precision = Scalar::from_f64(1e12);
+        let u = (u * precision).round() / precision;
+
+        Point::from([u, v])
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
+        let point_curve = self.curve.point_curve_to_model(&point.to_t());
+        Transform::rotation(self.axis.origin, self.normal() * point.v)
+            .transform_point(&point_curve)
+    }
+
+    /// Convert a vector in surface coordinates to model coordinates
+    ///
+    /// The surface is curved, so the result is only exact at the surface's
+    /// origin, where the vector is applied to the curve at angle zero.
+    pub fn vector_surface_to_model(&self, vector: &Vector<2>) -> Vector<3> {
+        let origin = self.curve.origin();
+        let tangent = self.normal().cross(&self.radial(&origin));
+
+        self.curve.vector_curve_to_model(&vector.to_t()) + tangent * vector.v
+    }
+
+    fn normal(&self) -> Vector<3> {
+        self.axis.direction.normalize()
+    }
+
+    /// The component of the vector from the axis to the point, that is
+    /// perpendicular to the axis
+    fn radial(&self, point: &Point<3>) -> Vector<3> {
+        let normal = self.normal();
+        let v = point - self.axis.origin;
+        v + normal * -v.dot(&normal)
+    }
+
+    /// The direction from the axis, that defines the angle zero
+    ///
+    /// Taken from the point of the curve at coordinate `0.`, unless that lies
+    /// on the axis.
+    fn reference(&self) -> Vector<3> {
+        let radial =
+            self.radial(&self.curve.point_curve_to_model(&Point::from([0.])));
+        let radial = if radial.magnitude() > Scalar::from_f64(1e-10) {
+            radial
+        } else {
+            self.radial(&self.curve.point_curve_to_model(&Point::from([1.])))
+        };
+
+        radial.normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Vector};
+
+    use crate::geometry::{Curve, Line};
+
+    use super::RevolvedCurve;
+
+    #[test]
+    fn point_model_to_surface() {
+        let revolved = cone();
+
+        verify(&revolved, Point::from([0., 0.]));
+        verify(&revolved, Point::from([0.5, 1.]));
+        verify(&revolved, Point::from([1., -1.]));
+        verify(&revolved, Point::from([2., 4.]));
+
+        fn verify(revolved: &RevolvedCurve, surface_point: Point<2>) {
+            let point = revolved.point_surface_to_model(&surface_point);
+            let result = revolved.point_model_to_surface(&point);
+
+            assert_abs_diff_eq!(
+                result.to_na(),
+                surface_point.to_na(),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn point_surface_to_model() {
+        let revolved = cone();
+
+        assert_abs_diff_eq!(
+            revolved
+                .point_surface_to_model(&Point::from([1., FRAC_PI_2]))
+                .to_na(),
+            Point::from([0., 2., 1.]).to_na(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn vector_surface_to_model() {
+        let revolved = cone();
+
+        assert_abs_diff_eq!(
+            revolved
+                .vector_surface_to_model(&Vector::from([1., 2.]))
+                .to_na(),
+            Vector::from([1., 2., 1.]).to_na(),
+            epsilon = 1e-12
+        );
+    }
+
+    /// A cone around the z axis, that widens towards positive z
+    fn cone() -> RevolvedCurve {
+        RevolvedCurve {
+            curve: Curve::Line(Line {
+                origin: Point::from([1., 0., 0.]),
+                direction: Vector::from([1., 0., 1.]),
+            }),
+            axis: Line {
+                origin: Point::origin(),
+                direction: Vector::unit_z(),
+            },
+        }
+    }
+}
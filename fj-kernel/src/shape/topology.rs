@@ -117,7 +117,8 @@ impl Topology<'_> {
     pub fn add_circle(&mut self, radius: Scalar) -> ValidationResult<Edge> {
         let curve = self.geometry.add_curve(Curve::Circle(Circle {
             center: Point::origin(),
-            radius: Vector::from([radius, Scalar::ZERO]),
+            radius: Vector::from([radius, Scalar::ZERO, Scalar::ZERO]),
+            normal: Vector::unit_z(),
         }));
         self.add_edge(Edge {
             curve,
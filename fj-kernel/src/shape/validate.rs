@@ -1,11 +1,11 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     geometry::{Curve, Surface},
     topology::{Cycle, Edge, Face, Vertex},
 };
 
-use super::Handle;
+use super::{Handle, Shape};
 
 /// Returned by the various `add_` methods of the [`Shape`] API
 pub type ValidationResult<T> = Result<Handle<T>, ValidationError<T>>;
@@ -95,6 +95,153 @@ impl ValidationError<Face> {
     }
 }
 
+impl Shape {
+    /// Validate the complete shape
+    ///
+    /// Objects are already validated when they are added to the shape, but
+    /// only against the shape as it was at that time. This method validates all
+    /// objects against the complete shape, and additionally checks properties
+    /// that can only be checked once the shape is complete, like whether the
+    /// edges of a cycle actually form a cycle.
+    ///
+    /// Returns all problems that were found, not just the first one.
+    ///
+    /// # Implementation note
+    ///
+    /// Geometric validation (for example, whether the vertices of an edge lie
+    /// on its curve) is not done yet. Some of the kernel's operations produce
+    /// geometry that would fail such checks.
+    pub fn validate(&self) -> Result<(), Vec<ShapeError>> {
+        let mut errors = Vec::new();
+
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            if !self.points.contains(vertex.get().point.storage()) {
+                errors.push(ShapeError::MissingPoint(vertex.handle()));
+            }
+
+            for other in &self.vertices[i + 1..] {
+                let distance =
+                    (other.get().point() - vertex.get().point()).magnitude();
+
+                if distance < self.min_distance {
+                    errors.push(ShapeError::DuplicateVertex(
+                        vertex.handle(),
+                        other.handle(),
+                    ));
+                }
+            }
+        }
+
+        for edge in &self.edges {
+            let edge_ref = edge.get();
+
+            let curve_missing = !self.curves.contains(edge_ref.curve.storage());
+            let vertices_missing = edge_ref
+                .vertices
+                .iter()
+                .flatten()
+                .any(|vertex| !self.vertices.contains(vertex.storage()));
+
+            if curve_missing || vertices_missing {
+                errors.push(ShapeError::EdgeStructural(edge.handle()));
+            }
+        }
+
+        for cycle in &self.cycles {
+            let cycle_ref = cycle.get();
+
+            if cycle_ref
+                .edges
+                .iter()
+                .any(|edge| !self.edges.contains(edge.storage()))
+            {
+                errors.push(ShapeError::CycleStructural(cycle.handle()));
+            }
+            if !is_connected(&cycle_ref) {
+                errors.push(ShapeError::CycleNotConnected(cycle.handle()));
+            }
+        }
+
+        for face in &self.faces {
+            if let Face::Face {
+                surface, cycles, ..
+            } = &*face.get()
+            {
+                let surface_missing =
+                    !self.surfaces.contains(surface.storage());
+                let cycles_missing = cycles
+                    .iter()
+                    .any(|cycle| !self.cycles.contains(cycle.storage()));
+
+                if surface_missing || cycles_missing {
+                    errors.push(ShapeError::FaceStructural(face.handle()));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Determine whether the edges of a cycle are connected
+///
+/// The edges of a cycle are not required to be ordered, nor oriented
+/// consistently. They form a cycle, if every vertex is shared by exactly two
+/// of them. A continuous edge forms a cycle by itself.
+fn is_connected(cycle: &Cycle) -> bool {
+    let mut continuous = 0;
+    let mut vertex_uses = HashMap::new();
+
+    for edge in &cycle.edges {
+        match &edge.get().vertices {
+            Some(vertices) => {
+                for vertex in vertices {
+                    *vertex_uses.entry(vertex.clone()).or_insert(0) += 1;
+                }
+            }
+            None => continuous += 1,
+        }
+    }
+
+    if continuous > 0 {
+        return continuous == 1 && cycle.edges.len() == 1;
+    }
+
+    !vertex_uses.is_empty() && vertex_uses.values().all(|&uses| uses == 2)
+}
+
+/// A problem found by [`Shape::validate`]
+#[derive(Debug, thiserror::Error)]
+pub enum ShapeError {
+    /// A vertex refers to a point that is not part of the shape
+    #[error("Vertex refers to point that is not part of the shape: {0:?}")]
+    MissingPoint(Handle<Vertex>),
+
+    /// Two vertices are closer to each other than the minimum distance
+    #[error("Vertices are not distinct: {0:?}, {1:?}")]
+    DuplicateVertex(Handle<Vertex>, Handle<Vertex>),
+
+    /// An edge refers to a curve or vertices that are not part of the shape
+    #[error("Edge refers to objects that are not part of the shape: {0:?}")]
+    EdgeStructural(Handle<Edge>),
+
+    /// A cycle refers to edges that are not part of the shape
+    #[error("Cycle refers to edges that are not part of the shape: {0:?}")]
+    CycleStructural(Handle<Cycle>),
+
+    /// The edges of a cycle don't form a cycle
+    #[error("Edges of cycle are not connected: {0:?}")]
+    CycleNotConnected(Handle<Cycle>),
+
+    /// A face refers to a surface or cycles that are not part of the shape
+    #[error("Face refers to objects that are not part of the shape: {0:?}")]
+    FaceStructural(Handle<Face>),
+}
+
 /// Implemented for topological types, which can be validated
 ///
 /// Used by [`ValidationError`] to provide context on how validation failed.
@@ -117,3 +264,58 @@ impl Validatable for Cycle {
 impl Validatable for Face {
     type Structural = (Option<Handle<Surface>>, HashSet<Handle<Cycle>>);
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{
+        shape::{Handle, Shape, ShapeError},
+        topology::{Cycle, Edge, Vertex},
+    };
+
+    #[test]
+    fn validate_connected_cycle() {
+        let mut shape = Shape::new();
+
+        let [a, b, c] = vertices(&mut shape);
+        let edges = [[a.clone(), b.clone()], [b, c.clone()], [c, a]]
+            .map(|vertices| line_segment(&mut shape, vertices))
+            .to_vec();
+        shape.topology().add_cycle(Cycle { edges }).unwrap();
+
+        assert!(shape.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_disconnected_cycle() {
+        let mut shape = Shape::new();
+
+        let [a, b, c] = vertices(&mut shape);
+        let edges = [[a.clone(), b], [a, c]]
+            .map(|vertices| line_segment(&mut shape, vertices))
+            .to_vec();
+        let cycle = shape.topology().add_cycle(Cycle { edges }).unwrap();
+
+        let errors = shape.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ShapeError::CycleNotConnected(c) if *c == cycle
+        ));
+    }
+
+    fn vertices(shape: &mut Shape) -> [Handle<Vertex>; 3] {
+        [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(|point| {
+            let point = shape.geometry().add_point(Point::from(point));
+            shape.topology().add_vertex(Vertex { point }).unwrap()
+        })
+    }
+
+    fn line_segment(
+        shape: &mut Shape,
+        vertices: [Handle<Vertex>; 2],
+    ) -> Handle<Edge> {
+        shape.topology().add_line_segment(vertices).unwrap()
+    }
+}
@@ -71,6 +71,16 @@ impl Scalar {
         self.0.max(other.0).into()
     }
 
+    /// Compute the minimum of this and another scalar
+    pub fn min(self, other: Self) -> Self {
+        self.0.min(other.0).into()
+    }
+
+    /// Compute the square root
+    pub fn sqrt(self) -> Self {
+        self.0.sqrt().into()
+    }
+
     /// Compute the smallest integer larger than or equal to this scalar
     pub fn ceil(self) -> Self {
         self.0.ceil().into()
@@ -86,12 +96,22 @@ impl Scalar {
         self.0.cos().into()
     }
 
+    /// Compute the sine
+    pub fn sin(self) -> Self {
+        self.0.sin().into()
+    }
+
     /// Compute sine and cosine
     pub fn sin_cos(self) -> (Self, Self) {
         let (sin, cos) = self.0.sin_cos();
         (sin.into(), cos.into())
     }
 
+    /// Compute the tangent
+    pub fn tan(self) -> Self {
+        self.0.tan().into()
+    }
+
     /// Compute the arccosine
     pub fn acos(self) -> Self {
         self.0.acos().into()
@@ -1,16 +1,61 @@
-use super::{Aabb, Point, Segment, Triangle, Vector};
+use std::ops;
+
+use super::{Aabb, Point, Scalar, Segment, Triangle, Vector};
 
 /// A transform
+///
+/// Transforms can rotate, translate, and uniformly scale.
+#[derive(Clone, Copy, Debug)]
 #[repr(C)]
-pub struct Transform(parry3d_f64::math::Isometry<f64>);
+pub struct Transform(nalgebra::Similarity3<f64>);
 
 impl Transform {
+    /// Construct a transform that doesn't change anything
+    pub fn identity() -> Self {
+        Self(nalgebra::Similarity3::identity())
+    }
+
     /// Construct a translation
     pub fn translation(vector: Vector<3>) -> Self {
-        Self(parry3d_f64::math::Isometry::translation(
-            vector.x.into_f64(),
-            vector.y.into_f64(),
-            vector.z.into_f64(),
+        Self(nalgebra::Similarity3::from_isometry(
+            parry3d_f64::math::Isometry::translation(
+                vector.x.into_f64(),
+                vector.y.into_f64(),
+                vector.z.into_f64(),
+            ),
+            1.,
+        ))
+    }
+
+    /// Construct a rotation
+    ///
+    /// Rotates around the axis that goes through `center`, in the direction of
+    /// `axis`. The length of `axis` defines the angle of the rotation, in
+    /// radians.
+    pub fn rotation(center: Point<3>, axis: Vector<3>) -> Self {
+        Self(nalgebra::Similarity3::from_isometry(
+            parry3d_f64::math::Isometry::rotation_wrt_point(
+                nalgebra::UnitQuaternion::from_scaled_axis(axis.to_na()),
+                center.to_na(),
+            ),
+            1.,
+        ))
+    }
+
+    /// Construct a transform that scales, rotates, then translates
+    ///
+    /// Scaling and rotation happen around the origin. `rotation` is an axis,
+    /// whose length defines the angle of the rotation, in radians. `scaling`
+    /// must not be zero.
+    pub fn similarity(
+        translation: Vector<3>,
+        rotation: Vector<3>,
+        scaling: Scalar,
+    ) -> Self {
+        Self(nalgebra::Similarity3::new(
+            translation.to_na(),
+            rotation.to_na(),
+            scaling.into_f64(),
         ))
     }
 
@@ -31,13 +76,17 @@ impl Transform {
     }
 
     /// Transform the given triangle
+    ///
+    /// The color of the triangle is preserved.
     pub fn transform_triangle(&self, triangle: &Triangle<3>) -> Triangle<3> {
         let [a, b, c] = &triangle.points();
-        Triangle::from([
+        let mut transformed = Triangle::from([
             self.transform_point(a),
             self.transform_point(b),
             self.transform_point(c),
-        ])
+        ]);
+        transformed.set_color(triangle.color());
+        transformed
     }
 
     /// Transform the given axis-aligned bounding box
@@ -49,20 +98,19 @@ impl Transform {
     }
 }
 
-impl From<parry3d_f64::math::Isometry<f64>> for Transform {
-    fn from(isometry: parry3d_f64::math::Isometry<f64>) -> Self {
-        Self(isometry)
-    }
-}
+impl ops::Mul<Self> for Transform {
+    type Output = Self;
 
-impl From<Transform> for parry3d_f64::math::Isometry<f64> {
-    fn from(transform: Transform) -> Self {
-        transform.0
+    /// Combine two transforms
+    ///
+    /// Like with matrices, the transform on the right is applied first.
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
     }
 }
 
-impl<'r> From<&'r Transform> for parry3d_f64::math::Isometry<f64> {
-    fn from(transform: &Transform) -> Self {
-        transform.0
+impl From<parry3d_f64::math::Isometry<f64>> for Transform {
+    fn from(isometry: parry3d_f64::math::Isometry<f64>) -> Self {
+        Self(nalgebra::Similarity3::from_isometry(isometry, 1.))
     }
 }
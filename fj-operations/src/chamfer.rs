@@ -0,0 +1,48 @@
+use fj_debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{blend_corners, Blend},
+    shape::Shape,
+};
+use fj_math::{Aabb, Scalar};
+
+use super::{sweep::to_blended_shape, Error, ToShape};
+
+impl ToShape for fj::Chamfer2d {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let shape = blend_corners(
+            self.shape().to_shape(tolerance, debug_info)?,
+            Blend::Chamfer(Scalar::from_f64(self.distance())),
+        )?;
+
+        Ok(shape)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Beveling the corners never moves an edge beyond the original shape.
+        self.shape().bounding_volume()
+    }
+}
+
+impl ToShape for fj::Chamfer {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        to_blended_shape(
+            &self.sweep,
+            self.edges,
+            Blend::Chamfer(Scalar::from_f64(self.distance)),
+            tolerance,
+            debug_info,
+        )
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.sweep.bounding_volume()
+    }
+}
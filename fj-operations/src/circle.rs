@@ -6,10 +6,10 @@ use fj_kernel::{
 };
 use fj_math::{Aabb, Point, Scalar};
 
-use super::ToShape;
+use super::{Error, ToShape};
 
 impl ToShape for fj::Circle {
-    fn to_shape(&self, _: Scalar, _: &mut DebugInfo) -> Shape {
+    fn to_shape(&self, _: Scalar, _: &mut DebugInfo) -> Result<Shape, Error> {
         let mut shape = Shape::new();
 
         // Circles have just a single round edge with no vertices. So none need
@@ -35,7 +35,7 @@ impl ToShape for fj::Circle {
             })
             .unwrap();
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
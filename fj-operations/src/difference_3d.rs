@@ -0,0 +1,26 @@
+use fj_debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{boolean, BooleanOp},
+    shape::Shape,
+};
+use fj_math::{Aabb, Scalar};
+
+use super::{Error, ToShape};
+
+impl ToShape for fj::Difference3d {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let a = self.a.to_shape(tolerance, debug_info)?;
+        let b = self.b.to_shape(tolerance, debug_info)?;
+
+        Ok(boolean(a, b, BooleanOp::Difference, tolerance))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Subtracting from a shape can only make it smaller.
+        self.a.bounding_volume()
+    }
+}
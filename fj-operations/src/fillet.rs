@@ -0,0 +1,135 @@
+use fj_debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{blend_corners, Blend},
+    shape::Shape,
+};
+use fj_math::{Aabb, Scalar};
+
+use super::{sweep::to_blended_shape, Error, ToShape};
+
+impl ToShape for fj::Fillet2d {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let shape = blend_corners(
+            self.shape().to_shape(tolerance, debug_info)?,
+            Blend::Fillet(Scalar::from_f64(self.radius())),
+        )?;
+
+        Ok(shape)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Rounding the corners never moves an edge beyond the original shape.
+        self.shape().bounding_volume()
+    }
+}
+
+impl ToShape for fj::Fillet {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        to_blended_shape(
+            &self.sweep,
+            self.edges,
+            Blend::Fillet(Scalar::from_f64(self.radius)),
+            tolerance,
+            debug_info,
+        )
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.sweep.bounding_volume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::prelude::*;
+    use fj_debug::DebugInfo;
+    use fj_kernel::{algorithms::BlendError, shape::Shape};
+    use fj_math::Scalar;
+
+    use crate::{Error, ToShape as _};
+
+    #[test]
+    fn fillet_sweep() {
+        let mut shape = to_shape(rounded(sweep(), 0.25)).unwrap();
+        assert!(shape.topology().faces().count() > 6);
+        assert!(shape.validate().is_ok());
+    }
+
+    #[test]
+    fn unsupported_sweep() {
+        let result = to_shape(rounded(sweep().with_taper(0.1), 0.25));
+        assert!(matches!(result, Err(Error::UnsupportedBlend)));
+    }
+
+    #[test]
+    fn zero_radius() {
+        // Nothing is rounded, so any sweep is fine.
+        let mut shape = to_shape(rounded(sweep().with_taper(0.1), 0.)).unwrap();
+        assert_eq!(shape.topology().faces().count(), 6);
+
+        let mut shape = to_shape(square().fillet(0.)).unwrap();
+        assert_eq!(shape.topology().edges().count(), 4);
+    }
+
+    #[test]
+    fn negative_radius() {
+        let result = to_shape(rounded(sweep(), -0.25));
+        assert!(matches!(
+            result,
+            Err(Error::Blend(BlendError::InvalidSize(_)))
+        ));
+
+        let result = to_shape(square().fillet(-0.25));
+        assert!(matches!(
+            result,
+            Err(Error::Blend(BlendError::InvalidSize(_)))
+        ));
+    }
+
+    #[test]
+    fn too_large() {
+        let result = to_shape(square().fillet(5.));
+        assert!(matches!(result, Err(Error::Blend(BlendError::TooLarge(_)))));
+
+        let result = to_shape(rounded(sweep(), 1.5));
+        assert!(matches!(result, Err(Error::Blend(BlendError::TooLarge(_)))));
+    }
+
+    fn rounded(sweep: fj::Sweep, radius: f64) -> fj::Fillet {
+        sweep.fillet(
+            radius,
+            fj::SweepEdges {
+                side: true,
+                top: true,
+                bottom: false,
+            },
+        )
+    }
+
+    fn sweep() -> fj::Sweep {
+        fj::Sweep::from_shape_and_length(square().into(), 1.)
+    }
+
+    fn square() -> fj::Sketch {
+        fj::Sketch::from_points(vec![
+            [-1., -1.],
+            [1., -1.],
+            [1., 1.],
+            [-1., 1.],
+        ])
+    }
+
+    fn to_shape(shape: impl Into<fj::Shape>) -> Result<Shape, Error> {
+        shape
+            .into()
+            .to_shape(Scalar::from_f64(0.001), &mut DebugInfo::new())
+    }
+}
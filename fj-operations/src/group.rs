@@ -7,19 +7,23 @@ use fj_kernel::{
 };
 use fj_math::{Aabb, Scalar};
 
-use super::ToShape;
+use super::{Error, ToShape};
 
 impl ToShape for fj::Group {
-    fn to_shape(&self, tolerance: Scalar, debug_info: &mut DebugInfo) -> Shape {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
         let mut shape = Shape::new();
 
-        let a = self.a.to_shape(tolerance, debug_info);
-        let b = self.b.to_shape(tolerance, debug_info);
+        let a = self.a.to_shape(tolerance, debug_info)?;
+        let b = self.b.to_shape(tolerance, debug_info)?;
 
         copy_shape(a, &mut shape);
         copy_shape(b, &mut shape);
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -0,0 +1,53 @@
+use std::cmp;
+
+use fj_debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{boolean, BooleanOp},
+    shape::Shape,
+};
+use fj_math::{Aabb, Point, Scalar};
+
+use super::{Error, ToShape};
+
+impl ToShape for fj::Intersection {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let a = self.a.to_shape(tolerance, debug_info)?;
+        let b = self.b.to_shape(tolerance, debug_info)?;
+
+        Ok(boolean(a, b, BooleanOp::Intersection, tolerance))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let a = self.a.bounding_volume();
+        let b = self.b.bounding_volume();
+
+        overlap(&a, &b)
+    }
+}
+
+/// Compute the overlap of two bounding volumes
+///
+/// The intersection of two shapes can't be larger than that.
+pub(crate) fn overlap(a: &Aabb<3>, b: &Aabb<3>) -> Aabb<3> {
+    let min = Point::from([
+        cmp::max(a.min.x, b.min.x),
+        cmp::max(a.min.y, b.min.y),
+        cmp::max(a.min.z, b.min.z),
+    ]);
+    let max = Point::from([
+        cmp::min(a.max.x, b.max.x),
+        cmp::min(a.max.y, b.max.y),
+        cmp::min(a.max.z, b.max.z),
+    ]);
+
+    let is_empty = min.x > max.x || min.y > max.y || min.z > max.z;
+    if is_empty {
+        return Aabb { min, max: min };
+    }
+
+    Aabb { min, max }
+}
@@ -6,21 +6,37 @@
 
 #![deny(missing_docs)]
 
+mod chamfer;
 mod circle;
 mod difference_2d;
+mod difference_3d;
+mod fillet;
 mod group;
+mod intersection;
+mod intersection_2d;
+mod loft;
+mod revolve;
 mod sketch;
 mod sweep;
 mod transform;
+mod union;
+mod union_2d;
 
 use fj_debug::DebugInfo;
-use fj_kernel::shape::Shape;
+use fj_kernel::{
+    algorithms::{BlendError, LoftError, RevolveError, SweepError},
+    shape::Shape,
+};
 use fj_math::{Aabb, Scalar};
 
 /// Implemented for all operations from the [`fj`] crate
 pub trait ToShape {
     /// Compute the boundary representation of the shape
-    fn to_shape(&self, tolerance: Scalar, debug: &mut DebugInfo) -> Shape;
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug: &mut DebugInfo,
+    ) -> Result<Shape, Error>;
 
     /// Access the axis-aligned bounding box of a shape
     ///
@@ -29,6 +45,37 @@ pub trait ToShape {
     fn bounding_volume(&self) -> Aabb<3>;
 }
 
+/// An error that can occur while computing the boundary representation
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Blending corners or edges failed
+    #[error("Error blending shape")]
+    Blend(#[from] BlendError),
+
+    /// Lofting between profiles failed
+    #[error("Error lofting between profiles")]
+    Loft(#[from] LoftError),
+
+    /// The profiles of a loft aren't ordered by their offsets
+    #[error("The profiles of a loft must be ordered by increasing offsets")]
+    UnorderedProfiles,
+
+    /// Revolving a shape failed
+    #[error("Error revolving shape")]
+    Revolve(#[from] RevolveError),
+
+    /// Sweeping a shape failed
+    #[error("Error sweeping shape")]
+    Sweep(#[from] SweepError),
+
+    /// The edges of a sweep can't be blended
+    #[error(
+        "Only sweeps along a straight line, without taper or twist, can be \
+        filleted or chamfered"
+    )]
+    UnsupportedBlend,
+}
+
 macro_rules! dispatch {
     ($($method:ident($($arg_name:ident: $arg_ty:ty,)*) -> $ret:ty;)*) => {
         impl ToShape for fj::Shape {
@@ -46,9 +93,13 @@ macro_rules! dispatch {
             $(
                 fn $method(&self, $($arg_name: $arg_ty,)*) -> $ret {
                     match self {
+                        Self::Chamfer(shape) => shape.$method($($arg_name,)*),
                         Self::Circle(shape) => shape.$method($($arg_name,)*),
                         Self::Difference(shape) => shape.$method($($arg_name,)*),
+                        Self::Fillet(shape) => shape.$method($($arg_name,)*),
+                        Self::Intersection(shape) => shape.$method($($arg_name,)*),
                         Self::Sketch(shape) => shape.$method($($arg_name,)*),
+                        Self::Union(shape) => shape.$method($($arg_name,)*),
                     }
                 }
             )*
@@ -58,9 +109,16 @@ macro_rules! dispatch {
             $(
                 fn $method(&self, $($arg_name: $arg_ty,)*) -> $ret {
                     match self {
+                        Self::Chamfer(shape) => shape.$method($($arg_name,)*),
+                        Self::Difference(shape) => shape.$method($($arg_name,)*),
+                        Self::Fillet(shape) => shape.$method($($arg_name,)*),
                         Self::Group(shape) => shape.$method($($arg_name,)*),
+                        Self::Intersection(shape) => shape.$method($($arg_name,)*),
+                        Self::Loft(shape) => shape.$method($($arg_name,)*),
+                        Self::Revolve(shape) => shape.$method($($arg_name,)*),
                         Self::Sweep(shape) => shape.$method($($arg_name,)*),
                         Self::Transform(shape) => shape.$method($($arg_name,)*),
+                        Self::Union(shape) => shape.$method($($arg_name,)*),
                     }
                 }
             )*
@@ -72,6 +130,6 @@ dispatch! {
     to_shape(
         tolerance: Scalar,
         debug: &mut DebugInfo,
-    ) -> Shape;
+    ) -> Result<Shape, Error>;
     bounding_volume() -> Aabb<3>;
 }
@@ -0,0 +1,216 @@
+use std::cmp::Ordering;
+
+use fj_debug::DebugInfo;
+use fj_kernel::{algorithms::loft_shapes, shape::Shape};
+use fj_math::{Aabb, Point, Scalar, Transform, Vector};
+
+use super::{Error, ToShape};
+
+impl ToShape for fj::Loft {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let profiles = self.to_profiles();
+
+        // Offsets that are NaN can't be compared, and are rejected too.
+        if profiles.windows(2).any(|pair| {
+            pair[0].z.partial_cmp(&pair[1].z) != Some(Ordering::Less)
+        }) {
+            return Err(Error::UnorderedProfiles);
+        }
+
+        let profiles = profiles
+            .into_iter()
+            .map(|profile| {
+                let mut shape =
+                    profile.shape.to_shape(tolerance, debug_info)?;
+                shape.geometry().transform(&offset(&profile));
+                Ok(shape)
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(loft_shapes(profiles, tolerance, self.color())?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.to_profiles()
+            .iter()
+            .map(|profile| {
+                offset(profile).transform_aabb(&profile.shape.bounding_volume())
+            })
+            .reduce(|a, b| a.merged(&b))
+            .unwrap_or(Aabb {
+                min: Point::origin(),
+                max: Point::origin(),
+            })
+    }
+}
+
+fn offset(profile: &fj::Profile) -> Transform {
+    Transform::translation(Vector::from([0., 0., profile.z]))
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_debug::DebugInfo;
+    use fj_kernel::{algorithms::LoftError, shape::Shape, topology::Face};
+    use fj_math::{Point, Scalar};
+
+    use crate::{Error, ToShape as _};
+
+    #[test]
+    fn loft() {
+        let loft = fj::Loft::from_profiles(vec![
+            profile(square(2.), 0.),
+            profile(square(1.), 1.),
+        ]);
+        let mut shape = to_shape(&loft);
+
+        // Two caps, and a planar face for each pair of edges.
+        assert_eq!(shape.topology().faces().count(), 6);
+        assert!(shape.validate().is_ok());
+
+        let aabb = loft.bounding_volume();
+        assert_eq!(aabb.min, Point::from([-1., -1., 0.]));
+        assert_eq!(aabb.max, Point::from([1., 1., 1.]));
+    }
+
+    #[test]
+    fn vertex_count_mismatch() {
+        let loft = fj::Loft::from_profiles(vec![
+            profile(square(2.), 0.),
+            profile(triangle(), 1.),
+        ]);
+        let mut shape = to_shape(&loft);
+
+        // The cycles are connected as a whole, by a single side face, which
+        // is approximated by triangles.
+        let faces: Vec<_> = shape.topology().faces().values().collect();
+        assert_eq!(faces.len(), 3);
+        assert_eq!(
+            faces
+                .iter()
+                .filter(|face| matches!(face, Face::Triangles(_)))
+                .count(),
+            1
+        );
+        assert!(shape.validate().is_ok());
+    }
+
+    #[test]
+    fn face_count_mismatch() {
+        let loft = fj::Loft::from_profiles(vec![
+            profile(two_squares(), 0.),
+            profile(square(1.), 1.),
+        ]);
+        let result = try_to_shape(&loft);
+        assert!(matches!(
+            result,
+            Err(Error::Loft(LoftError::FaceCountMismatch(2, 1)))
+        ));
+
+        let aabb = loft.bounding_volume();
+        assert_eq!(aabb.min, Point::from([-3., -1., 0.]));
+        assert_eq!(aabb.max, Point::from([3., 1., 1.]));
+    }
+
+    #[test]
+    fn cycle_count_mismatch() {
+        let loft = fj::Loft::from_profiles(vec![
+            profile(square(2.), 0.),
+            profile(square_with_hole(), 1.),
+        ]);
+
+        let result = try_to_shape(&loft);
+        assert!(matches!(
+            result,
+            Err(Error::Loft(LoftError::CycleCountMismatch(1, 2)))
+        ));
+    }
+
+    #[test]
+    fn unordered_profiles() {
+        for z in [0., -1., f64::NAN] {
+            let loft = fj::Loft::from_profiles(vec![
+                profile(square(2.), 0.),
+                profile(square(1.), z),
+            ]);
+
+            let result = try_to_shape(&loft);
+            assert!(matches!(result, Err(Error::UnorderedProfiles)));
+        }
+    }
+
+    #[test]
+    fn single_profile() {
+        let loft = fj::Loft::from_profiles(vec![profile(square(2.), 1.)]);
+        let mut shape = to_shape(&loft);
+
+        // Both ends are closed off by the same face.
+        assert_eq!(shape.topology().faces().count(), 1);
+        assert!(shape
+            .geometry()
+            .points()
+            .all(|point| point.get().z == Scalar::ONE));
+    }
+
+    #[test]
+    fn no_profiles() {
+        let loft = fj::Loft::from_profiles(Vec::new());
+        let mut shape = to_shape(&loft);
+
+        assert_eq!(shape.topology().faces().count(), 0);
+
+        let aabb = loft.bounding_volume();
+        assert_eq!(aabb.min, aabb.max);
+    }
+
+    fn to_shape(loft: &fj::Loft) -> Shape {
+        try_to_shape(loft).unwrap()
+    }
+
+    fn try_to_shape(loft: &fj::Loft) -> Result<Shape, Error> {
+        loft.to_shape(Scalar::from_f64(0.001), &mut DebugInfo::new())
+    }
+
+    fn profile(shape: fj::Shape2d, z: f64) -> fj::Profile {
+        fj::Profile { shape, z }
+    }
+
+    fn square(size: f64) -> fj::Shape2d {
+        let half = size / 2.;
+        fj::Sketch::from_points(vec![
+            [-half, -half],
+            [half, -half],
+            [half, half],
+            [-half, half],
+        ])
+        .into()
+    }
+
+    fn triangle() -> fj::Shape2d {
+        fj::Sketch::from_points(vec![[-1., -1.], [1., -1.], [0., 1.]]).into()
+    }
+
+    fn square_with_hole() -> fj::Shape2d {
+        fj::Difference2d::from_objects(square(2.), square(1.)).into()
+    }
+
+    fn two_squares() -> fj::Shape2d {
+        let a = fj::Sketch::from_points(vec![
+            [-3., -1.],
+            [-1., -1.],
+            [-1., 1.],
+            [-3., 1.],
+        ]);
+        let b = fj::Sketch::from_points(vec![
+            [1., -1.],
+            [3., -1.],
+            [3., 1.],
+            [1., 1.],
+        ]);
+        fj::Union2d::from_objects(a.into(), b.into()).into()
+    }
+}
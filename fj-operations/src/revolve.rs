@@ -0,0 +1,122 @@
+use fj_debug::DebugInfo;
+use fj_kernel::{algorithms::revolve_shape, geometry::Line, shape::Shape};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use super::{Error, ToShape};
+
+impl ToShape for fj::Revolve {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        revolve_shape(
+            self.shape().to_shape(tolerance, debug_info)?,
+            axis(self),
+            Scalar::from_f64(self.angle()),
+            tolerance,
+            self.color(),
+        )
+        .map_err(Error::from)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let aabb = self.shape().bounding_volume();
+
+        // Without a valid axis, there's no revolution. Computing its shape
+        // fails, but the bounding volume must still be well-defined.
+        let direction = axis(self).direction;
+        let magnitude = direction.magnitude();
+        if magnitude == Scalar::ZERO || !magnitude.into_f64().is_finite() {
+            return aabb;
+        }
+        let direction = direction.normalize();
+
+        let corners = [
+            [aabb.min.x, aabb.min.y],
+            [aabb.max.x, aabb.min.y],
+            [aabb.max.x, aabb.max.y],
+            [aabb.min.x, aabb.max.y],
+        ]
+        .map(|[x, y]| Vector::from([x, y, Scalar::ZERO]));
+
+        // The revolved shape lies within a cylinder around the axis. Its radius
+        // is the largest distance of any corner from the axis.
+        let along_axis = corners.map(|corner| corner.dot(&direction));
+        let radius = corners
+            .iter()
+            .map(|corner| {
+                (*corner + direction * -corner.dot(&direction)).magnitude()
+            })
+            .max()
+            .unwrap_or(Scalar::ZERO);
+
+        let [a, b] = [
+            along_axis.iter().copied().min(),
+            along_axis.iter().copied().max(),
+        ]
+        .map(|t| Point::origin() + direction * t.unwrap_or(Scalar::ZERO));
+
+        Aabb {
+            min: Point::from([
+                a.x.min(b.x) - radius,
+                a.y.min(b.y) - radius,
+                -radius,
+            ]),
+            max: Point::from([
+                a.x.max(b.x) + radius,
+                a.y.max(b.y) + radius,
+                radius,
+            ]),
+        }
+    }
+}
+
+fn axis(revolve: &fj::Revolve) -> Line {
+    let [x, y] = revolve.axis();
+
+    Line {
+        origin: Point::origin(),
+        direction: Vector::from([x, y, 0.]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_debug::DebugInfo;
+    use fj_kernel::algorithms::RevolveError;
+    use fj_math::Scalar;
+
+    use crate::{Error, ToShape as _};
+
+    #[test]
+    fn zero_axis() {
+        let result = revolve([0., 0.], 1.);
+        assert!(matches!(
+            result,
+            Err(Error::Revolve(RevolveError::InvalidAxis))
+        ));
+    }
+
+    #[test]
+    fn zero_angle() {
+        let result = revolve([0., 1.], 0.);
+        assert!(matches!(
+            result,
+            Err(Error::Revolve(RevolveError::ZeroAngle))
+        ));
+    }
+
+    fn revolve(axis: [f64; 2], angle: f64) -> Result<(), Error> {
+        let sketch =
+            fj::Sketch::from_points(vec![[1., 0.], [2., 0.], [2., 1.]]);
+        let revolve =
+            fj::Revolve::from_shape_axis_and_angle(sketch.into(), axis, angle);
+
+        // Must not panic, even if there is no valid revolution.
+        revolve.bounding_volume();
+
+        revolve.to_shape(Scalar::from_f64(0.001), &mut DebugInfo::new())?;
+        Ok(())
+    }
+}
@@ -6,10 +6,10 @@ use fj_kernel::{
 };
 use fj_math::{Aabb, Point, Scalar};
 
-use super::ToShape;
+use super::{Error, ToShape};
 
 impl ToShape for fj::Sketch {
-    fn to_shape(&self, _: Scalar, _: &mut DebugInfo) -> Shape {
+    fn to_shape(&self, _: Scalar, _: &mut DebugInfo) -> Result<Shape, Error> {
         let mut shape = Shape::new();
         let mut vertices = Vec::new();
 
@@ -51,7 +51,7 @@ impl ToShape for fj::Sketch {
         };
         shape.topology().add_face(face).unwrap();
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
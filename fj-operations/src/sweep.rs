@@ -1,22 +1,294 @@
 use fj_debug::DebugInfo;
-use fj_kernel::{algorithms::sweep_shape, shape::Shape};
-use fj_math::{Aabb, Scalar, Vector};
+use fj_kernel::{
+    algorithms::{
+        blend_sweep, sweep_shape_tapered, validate_path, Blend, PathSegment,
+        SweepEdges,
+    },
+    geometry::Line,
+    shape::Shape,
+};
+use fj_math::{Aabb, Point, Scalar, Transform, Vector};
 
-use super::ToShape;
+use super::{Error, ToShape};
 
 impl ToShape for fj::Sweep {
-    fn to_shape(&self, tolerance: Scalar, debug_info: &mut DebugInfo) -> Shape {
-        sweep_shape(
-            self.shape().to_shape(tolerance, debug_info),
-            Vector::from([0., 0., self.length()]),
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let shape = sweep_shape_tapered(
+            self.shape().to_shape(tolerance, debug_info)?,
+            &path(self),
+            Scalar::from_f64(self.taper()),
+            Scalar::from_f64(self.twist()),
             tolerance,
             self.color(),
-        )
+        )?;
+
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
-        let mut aabb = self.shape().bounding_volume();
-        aabb.max.z = self.length().into();
+        let mut shape = self.shape().bounding_volume();
+
+        // Without a valid path, there's no sweep. Computing its shape fails,
+        // but the bounding volume must still be well-defined.
+        if validate_path(&path(self)).is_err() {
+            return shape;
+        }
+
+        // A positive taper scales the shape towards the origin. Every point of
+        // the shape stays between its original position and the origin.
+        if self.taper() > 0. {
+            let origin = Point::from([0., 0., shape.min.z.into_f64()]);
+            shape = shape.merged(&Aabb::<3>::from_points([origin]));
+        }
+
+        // A negative taper makes the shape grow. No point of the shape moves
+        // outward by more than the draft angle allows.
+        if self.taper() < 0. {
+            let growth = length(self) * self.taper().tan().abs();
+            let growth = Vector::from([growth, growth, Scalar::ZERO]);
+            shape = Aabb {
+                min: shape.min + growth * -Scalar::ONE,
+                max: shape.max + growth,
+            };
+        }
+
+        // A twist rotates the shape around the z-axis, within a circle around
+        // the origin.
+        if self.twist() != 0. {
+            let radius = shape
+                .vertices()
+                .iter()
+                .map(|corner| corner.coords.xy().magnitude())
+                .max()
+                .unwrap_or(Scalar::ZERO);
+            shape = Aabb {
+                min: Point::from([-radius, -radius, shape.min.z]),
+                max: Point::from([radius, radius, shape.max.z]),
+            };
+        }
+
+        let mut corners = shape.vertices();
+        let mut aabb = Aabb::<3>::from_points(corners);
+
+        // Follow the corners of the shape's bounding box along the path. For
+        // arcs, every point the corners pass lies within a sphere around the
+        // arc's center.
+        for segment in path(self) {
+            match segment {
+                PathSegment::Line(direction) => {
+                    corners = corners.map(|corner| corner + direction);
+                }
+                PathSegment::Arc { axis, angle } => {
+                    let radius = corners
+                        .iter()
+                        .map(|corner| (corner - axis.origin).magnitude())
+                        .max()
+                        .unwrap_or(Scalar::ZERO);
+                    let radius = Vector::from([radius, radius, radius]);
+
+                    aabb = aabb.merged(&Aabb {
+                        min: axis.origin + radius * -Scalar::ONE,
+                        max: axis.origin + radius,
+                    });
+
+                    let transform = Transform::rotation(
+                        axis.origin,
+                        axis.direction.normalize() * angle,
+                    );
+                    corners = corners
+                        .map(|corner| transform.transform_point(&corner));
+                }
+            }
+
+            aabb = aabb.merged(&Aabb::<3>::from_points(corners));
+        }
+
         aabb
     }
 }
+
+/// Compute the boundary representation of a sweep with blended edges
+///
+/// Blending is only supported for sweeps in a straight line, without taper or
+/// twist. Blending the edges of other sweeps results in an error, unless the
+/// blend has a size of zero, or no edges are selected.
+pub(crate) fn to_blended_shape(
+    sweep: &fj::Sweep,
+    edges: fj::SweepEdges,
+    blend: Blend,
+    tolerance: Scalar,
+    debug_info: &mut DebugInfo,
+) -> Result<Shape, Error> {
+    let (Blend::Fillet(size) | Blend::Chamfer(size)) = blend;
+    if size == Scalar::ZERO || !(edges.side || edges.top || edges.bottom) {
+        return sweep.to_shape(tolerance, debug_info);
+    }
+
+    let direction = match path(sweep).as_slice() {
+        [PathSegment::Line(direction)]
+            if sweep.taper() == 0. && sweep.twist() == 0. =>
+        {
+            *direction
+        }
+        _ => return Err(Error::UnsupportedBlend),
+    };
+
+    let edges = SweepEdges {
+        side: edges.side,
+        top: edges.top,
+        bottom: edges.bottom,
+    };
+
+    let shape = blend_sweep(
+        sweep.shape().to_shape(tolerance, debug_info)?,
+        direction,
+        edges,
+        blend,
+        tolerance,
+        sweep.color(),
+    )?;
+
+    Ok(shape)
+}
+
+/// The length of the path that the origin of the shape follows
+fn length(sweep: &fj::Sweep) -> Scalar {
+    let mut origin = Point::origin();
+    let mut length = Scalar::ZERO;
+
+    for segment in path(sweep) {
+        match segment {
+            PathSegment::Line(direction) => {
+                origin = origin + direction;
+                length += direction.magnitude();
+            }
+            PathSegment::Arc { axis, angle } => {
+                let normal = axis.direction.normalize();
+                let v = origin - axis.origin;
+                let radius = (v + normal * -v.dot(&normal)).magnitude();
+                length += radius * angle.abs();
+
+                origin = Transform::rotation(axis.origin, normal * angle)
+                    .transform_point(&origin);
+            }
+        }
+    }
+
+    length
+}
+
+fn path(sweep: &fj::Sweep) -> Vec<PathSegment> {
+    sweep
+        .path()
+        .to_segments()
+        .into_iter()
+        .map(|segment| match segment {
+            fj::PathSegment::Line(direction) => {
+                PathSegment::Line(Vector::from(direction))
+            }
+            fj::PathSegment::Arc {
+                center,
+                axis,
+                angle,
+            } => PathSegment::Arc {
+                axis: Line {
+                    origin: Point::from(center),
+                    direction: Vector::from(axis),
+                },
+                angle: Scalar::from_f64(angle),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_debug::DebugInfo;
+    use fj_kernel::algorithms::SweepError;
+    use fj_math::Scalar;
+
+    use crate::{Error, ToShape as _};
+
+    #[test]
+    fn empty_path() {
+        let result = sweep(fj::Path::from_segments(Vec::new()));
+        assert!(matches!(result, Err(Error::Sweep(SweepError::EmptyPath))));
+    }
+
+    #[test]
+    fn zero_length_line() {
+        let result = sweep([0., 0., 0.].into());
+        assert!(matches!(result, Err(Error::Sweep(SweepError::InvalidLine))));
+    }
+
+    #[test]
+    fn zero_axis_arc() {
+        let result = sweep(fj::Path::from_segments(vec![
+            fj::PathSegment::Line([0., 0., 1.]),
+            fj::PathSegment::Arc {
+                center: [3., 0., 1.],
+                axis: [0., 0., 0.],
+                angle: 1.,
+            },
+        ]));
+        assert!(matches!(
+            result,
+            Err(Error::Sweep(SweepError::InvalidArc(_)))
+        ));
+    }
+
+    #[test]
+    fn taper_too_large() {
+        let sweep = fj::Sweep::from_shape_and_length(triangle(), 2.)
+            .with_taper(std::f64::consts::FRAC_PI_4);
+
+        let result = to_shape(sweep);
+        assert!(matches!(
+            result,
+            Err(Error::Sweep(SweepError::TaperTooLarge))
+        ));
+    }
+
+    #[test]
+    fn taper_away_from_origin() {
+        // The shape shrinks towards the origin, out of its own bounding box.
+        let square = fj::Sketch::from_points(vec![
+            [2., 2.],
+            [4., 2.],
+            [4., 4.],
+            [2., 4.],
+        ]);
+        let sweep = fj::Sweep::from_shape_and_length(square.into(), 2.)
+            .with_taper(0.5_f64.atan());
+
+        let aabb = sweep.bounding_volume();
+        let mut shape = sweep
+            .to_shape(Scalar::from_f64(0.001), &mut DebugInfo::new())
+            .unwrap();
+
+        for vertex in shape.topology().vertices() {
+            let point = vertex.get().point().to_na();
+            assert!(aabb.to_parry().contains_local_point(&point));
+        }
+    }
+
+    fn sweep(path: fj::Path) -> Result<(), Error> {
+        to_shape(fj::Sweep::from_shape_and_path(triangle(), path))
+    }
+
+    fn to_shape(sweep: fj::Sweep) -> Result<(), Error> {
+        // Must not panic, even if there is no valid sweep.
+        sweep.bounding_volume();
+
+        sweep.to_shape(Scalar::from_f64(0.001), &mut DebugInfo::new())?;
+        Ok(())
+    }
+
+    fn triangle() -> fj::Shape2d {
+        fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]]).into()
+    }
+}
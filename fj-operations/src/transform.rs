@@ -3,16 +3,20 @@ use fj_kernel::shape::Shape;
 use fj_math::{Aabb, Scalar, Transform};
 use parry3d_f64::math::Isometry;
 
-use super::ToShape;
+use super::{Error, ToShape};
 
 impl ToShape for fj::Transform {
-    fn to_shape(&self, tolerance: Scalar, debug_info: &mut DebugInfo) -> Shape {
-        let mut shape = self.shape.to_shape(tolerance, debug_info);
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let mut shape = self.shape.to_shape(tolerance, debug_info)?;
         let transform = transform(self);
 
         shape.geometry().transform(&transform);
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -0,0 +1,28 @@
+use fj_debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{boolean, BooleanOp},
+    shape::Shape,
+};
+use fj_math::{Aabb, Scalar};
+
+use super::{Error, ToShape};
+
+impl ToShape for fj::Union {
+    fn to_shape(
+        &self,
+        tolerance: Scalar,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, Error> {
+        let a = self.a.to_shape(tolerance, debug_info)?;
+        let b = self.b.to_shape(tolerance, debug_info)?;
+
+        Ok(boolean(a, b, BooleanOp::Union, tolerance))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let a = self.a.bounding_volume();
+        let b = self.b.bounding_volume();
+
+        a.merged(&b)
+    }
+}
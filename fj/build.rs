@@ -0,0 +1,84 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+fn main() {
+    // The host and the model call each other's functions using the Rust ABI,
+    // which is only stable for a given version of the compiler. Record that
+    // version, so the host can check it before calling into a model.
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let output = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .expect("Failed to run `rustc --version`");
+    let rustc_version = String::from_utf8(output.stdout)
+        .expect("`rustc --version` returned invalid UTF-8");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(
+        out_dir.join("rustc_version.rs"),
+        format!("{:?}", rustc_version.trim()),
+    )
+    .expect("Failed to write compiler version");
+
+    // The layout of the types in this crate can change without the version of
+    // the crate changing (for example, between two releases). Record a hash of
+    // the source code, so the host can detect that.
+    let mut files = Vec::new();
+    collect_files(Path::new("src"), &mut files);
+    files.sort();
+
+    let mut hash = Fnv1a::new();
+    for file in files {
+        let source = fs::read(&file).expect("Failed to read source file");
+
+        hash.write(file.to_string_lossy().replace('\\', "/").as_bytes());
+        // Ignore line endings, as they depend on how the source was checked
+        // out.
+        hash.write(
+            &source
+                .into_iter()
+                .filter(|&byte| byte != b'\r')
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    fs::write(out_dir.join("source_hash.rs"), format!("{}", hash.0))
+        .expect("Failed to write source hash");
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src");
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).expect("Failed to read source directory") {
+        let path = entry.expect("Failed to read source directory").path();
+
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash
+///
+/// Unlike the hasher from the standard library, its output is guaranteed to be
+/// the same, regardless of the version of Rust the build script was built with.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
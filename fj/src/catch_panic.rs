@@ -0,0 +1,23 @@
+use std::panic::{self, AssertUnwindSafe};
+
+/// Call a function, catching any panic that occurs in it
+///
+/// A model library contains its own copy of the standard library, and a panic
+/// in the model is raised by that copy. The host's copy of the standard library
+/// can't catch such a panic, but the model's copy can. So the host calls into
+/// the model through this function, which every model exports, as it depends
+/// on this crate. Models don't need to do anything to make that happen.
+///
+/// Returns the panic message, if `f` panicked.
+#[no_mangle]
+pub fn fj_catch_panic(f: &mut dyn FnMut()) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            (*message).to_owned()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown panic payload".to_owned()
+        }
+    })
+}
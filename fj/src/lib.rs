@@ -9,22 +9,43 @@
 //! examples.
 //!
 //! [Fornjot repository]: https://github.com/hannobraun/Fornjot
+//!
+//! # Features
+//!
+//! - `serde`: Implements `Serialize` and `Deserialize` for [`Shape`] and all
+//!   the types it consists of. This can be used to save an evaluated model to
+//!   a file, or send it to another process.
+//! - `wasm`: Provides the glue code for running models compiled to
+//!   WebAssembly. This is enabled by the Fornjot app, when required.
 
+mod catch_panic;
+mod parameters;
+mod raw_vec;
 mod shape_2d;
 mod shape_3d;
 mod syntax;
+mod version;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
 
 pub mod prelude {
     pub use crate::syntax::{
-        Difference as _, Group as _, Rotate as _, Sketch as _, Sweep as _,
-        Translate as _,
+        Chamfer as _, Chamfer2d as _, Difference as _, Difference3d as _,
+        Fillet as _, Fillet2d as _, Group as _, Intersection as _,
+        Intersection2d as _, Revolve as _, Rotate as _, Sketch as _,
+        Sweep as _, Translate as _, Union as _, Union2d as _,
     };
 }
 
-pub use self::{shape_2d::*, shape_3d::*};
+pub use self::{
+    catch_panic::fj_catch_panic, parameters::*, shape_2d::*, shape_3d::*,
+    version::*,
+};
 
 /// A shape
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub enum Shape {
     Shape2d(Shape2d),
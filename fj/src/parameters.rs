@@ -0,0 +1,257 @@
+use std::{collections::HashMap, fmt};
+
+/// The parameters that a model accepts
+///
+/// Models can declare their parameters by exporting a function named
+/// `parameters`, in addition to the `model` function:
+///
+/// ``` rust
+/// #[no_mangle]
+/// pub fn parameters(parameters: &mut fj::Parameters) {
+///     parameters
+///         .add(fj::Parameter::float("radius", 1.0).with_min(0.))
+///         .add(fj::Parameter::integer("segments", 6).with_min(3.));
+/// }
+/// ```
+///
+/// The Fornjot app uses this to validate parameters before passing them to the
+/// model, and to list the parameters a model accepts. The model itself can use
+/// [`Parameters::value`] to read the validated arguments:
+///
+/// ``` rust
+/// # use std::collections::HashMap;
+/// # #[no_mangle]
+/// # pub fn parameters(parameters: &mut fj::Parameters) {
+/// #     parameters.add(fj::Parameter::float("radius", 1.0).with_min(0.));
+/// # }
+/// #[no_mangle]
+/// pub fn model(args: &HashMap<String, String>) -> fj::Shape {
+///     let mut declared = fj::Parameters::new();
+///     parameters(&mut declared);
+///
+///     let radius = declared.value(args, "radius").to_f64();
+///     fj::Circle::from_radius(radius).into()
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Parameters {
+    parameters: Vec<Parameter>,
+}
+
+impl Parameters {
+    /// Create an empty list of parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter
+    pub fn add(&mut self, parameter: Parameter) -> &mut Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Access the parameter with the given name
+    pub fn get(&self, name: &str) -> Option<&Parameter> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// Read the value of a parameter from the arguments passed to the model
+    ///
+    /// Falls back to the parameter's default value, if no argument was passed
+    /// for it. The Fornjot app validates the arguments against the declared
+    /// parameters before it calls the model, so the argument is expected to be
+    /// valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if no parameter with that name has been declared, or if the
+    /// argument is not a valid value for the parameter.
+    pub fn value(&self, args: &HashMap<String, String>, name: &str) -> Value {
+        let parameter = self.get(name).unwrap_or_else(|| {
+            panic!("Parameter `{}` has not been declared", name)
+        });
+
+        match args.get(name) {
+            Some(arg) => parameter.parse(arg).unwrap_or_else(|| {
+                panic!(
+                    "Invalid value `{}` for parameter `{}`: expected {}",
+                    arg,
+                    name,
+                    parameter.kind()
+                )
+            }),
+            None => parameter.default(),
+        }
+    }
+
+    /// Iterate over all parameters, in the order they were added in
+    pub fn iter(&self) -> impl Iterator<Item = &Parameter> {
+        self.parameters.iter()
+    }
+
+    /// Indicate whether there are no parameters
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+}
+
+/// A parameter that a model accepts
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    name: String,
+    default: Value,
+    min: Option<f64>,
+    max: Option<f64>,
+    description: Option<String>,
+}
+
+impl Parameter {
+    /// Create a floating-point parameter with a default value
+    pub fn float(name: impl Into<String>, default: f64) -> Self {
+        Self::new(name.into(), Value::Float(default))
+    }
+
+    /// Create an integer parameter with a default value
+    pub fn integer(name: impl Into<String>, default: i64) -> Self {
+        Self::new(name.into(), Value::Integer(default))
+    }
+
+    fn new(name: String, default: Value) -> Self {
+        Self {
+            name,
+            default,
+            min: None,
+            max: None,
+            description: None,
+        }
+    }
+
+    /// Set the minimum value of the parameter (inclusive)
+    pub fn with_min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the maximum value of the parameter (inclusive)
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Set the description of the parameter
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Access the name of the parameter
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Access the type of the parameter
+    pub fn kind(&self) -> ParameterKind {
+        match self.default {
+            Value::Float(_) => ParameterKind::Float,
+            Value::Integer(_) => ParameterKind::Integer,
+        }
+    }
+
+    /// Access the default value of the parameter
+    pub fn default(&self) -> Value {
+        self.default
+    }
+
+    /// Parse a value for this parameter
+    ///
+    /// Returns `None`, if the value is not of the parameter's type. Floats that
+    /// are not finite (like `NaN` or `inf`) are rejected too. Doesn't check
+    /// whether the value is within the parameter's range.
+    pub fn parse(&self, value: &str) -> Option<Value> {
+        match self.kind() {
+            ParameterKind::Float => value
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .map(Value::Float),
+            ParameterKind::Integer => {
+                value.parse::<i64>().ok().map(Value::Integer)
+            }
+        }
+    }
+
+    /// Access the minimum value of the parameter, if any
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    /// Access the maximum value of the parameter, if any
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// Access the description of the parameter, if any
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// The type of a parameter
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParameterKind {
+    /// A floating-point number
+    Float,
+
+    /// An integer
+    Integer,
+}
+
+impl fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Float => write!(f, "float"),
+            Self::Integer => write!(f, "integer"),
+        }
+    }
+}
+
+/// The value of a parameter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// A floating-point number
+    Float(f64),
+
+    /// An integer
+    Integer(i64),
+}
+
+impl Value {
+    /// Convert the value into a floating-point number
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Self::Float(value) => value,
+            Self::Integer(value) => value as f64,
+        }
+    }
+
+    /// Convert the value into an integer
+    ///
+    /// Floating-point numbers are rounded towards zero.
+    pub fn to_i64(self) -> i64 {
+        match self {
+            Self::Float(value) => value as i64,
+            Self::Integer(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Float(value) => write!(f, "{:?}", value),
+            Self::Integer(value) => write!(f, "{}", value),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::{fmt, mem};
+
+/// The raw parts of a `Vec`
+///
+/// Shapes need to be FFI-safe, meaning they can't store a `Vec` directly. They
+/// need to take this detour.
+#[repr(C)]
+pub struct RawVec<T> {
+    ptr: *mut T,
+    length: usize,
+    capacity: usize,
+}
+
+impl<T: Clone> RawVec<T> {
+    /// Take ownership of the memory of a `Vec`
+    pub fn from_vec(mut vec: Vec<T>) -> Self {
+        // This can be cleaned up, once `Vec::into_raw_parts` is stable.
+        let ptr = vec.as_mut_ptr();
+        let length = vec.len();
+        let capacity = vec.capacity();
+
+        // We're taking ownership of the memory here, so we can't allow `vec`
+        // to deallocate it.
+        mem::forget(vec);
+
+        Self {
+            ptr,
+            length,
+            capacity,
+        }
+    }
+
+    /// Return a copy of the elements
+    pub fn to_vec(&self) -> Vec<T> {
+        // This is sound. All invariants are automatically kept, as the raw
+        // parts come from an original `Vec` that is identical to the new one we
+        // create here, and aren't being modified anywhere.
+        let vec = unsafe {
+            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
+        };
+
+        // Ownership of the pointer transferred to `vec`. We work around that,
+        // by returning a clone of `vec` (hence not giving ownership to the
+        // caller).
+        let ret = vec.clone();
+
+        // Now we just need to forget that `vec` ever existed, and we keep
+        // ownership of the pointer.
+        mem::forget(vec);
+
+        ret
+    }
+}
+
+impl<T: Clone> Clone for RawVec<T> {
+    fn clone(&self) -> Self {
+        // Copying the raw parts would result in two owners of the same memory.
+        Self::from_vec(self.to_vec())
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for RawVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
+
+// `RawVec` can be `Send`, because it encapsulates the raw pointer it contains,
+// making sure memory ownership rules are observed.
+unsafe impl<T: Send> Send for RawVec<T> {}
@@ -1,34 +1,102 @@
-use std::mem;
-
-use crate::Shape;
+use crate::{raw_vec::RawVec, Shape};
 
 /// A 2-dimensional shape
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub enum Shape2d {
+    /// A shape with beveled corners
+    Chamfer(Box<Chamfer2d>),
+
     /// A circle
     Circle(Circle),
 
     /// A difference between two shapes
     Difference(Box<Difference2d>),
 
+    /// A shape with rounded corners
+    Fillet(Box<Fillet2d>),
+
+    /// An intersection between two shapes
+    Intersection(Box<Intersection2d>),
+
     /// A sketch
     Sketch(Sketch),
+
+    /// A union of two shapes
+    Union(Box<Union2d>),
 }
 
 impl Shape2d {
     /// Get the rendering color of the larger object in RGBA
     pub fn color(&self) -> [u8; 4] {
         match &self {
+            Shape2d::Chamfer(c) => c.color(),
             Shape2d::Circle(c) => c.color(),
             Shape2d::Sketch(s) => s.color(),
             Shape2d::Difference(d) => d.color(),
+            Shape2d::Fillet(f) => f.color(),
+            Shape2d::Intersection(i) => i.color(),
+            Shape2d::Union(u) => u.color(),
         }
     }
 }
 
+/// A shape with beveled corners
+///
+/// Each corner between two straight edges is cut off, `distance` from the
+/// corner along both edges. The distance must not be negative. A distance of
+/// zero leaves the shape as it is.
+///
+/// # Limitations
+///
+/// Corners that involve curved edges aren't beveled. Computing the shape fails,
+/// if the bevels at both ends of an edge don't fit into it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Chamfer2d {
+    /// The shape whose corners are beveled
+    shape: Shape2d,
+
+    /// The distance from each corner at which the bevel starts
+    distance: f64,
+}
+
+impl Chamfer2d {
+    pub fn from_shape_and_distance(shape: Shape2d, distance: f64) -> Self {
+        Self { shape, distance }
+    }
+
+    pub fn shape(&self) -> &Shape2d {
+        &self.shape
+    }
+
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// Get the rendering color of the shape in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.shape.color()
+    }
+}
+
+impl From<Chamfer2d> for Shape {
+    fn from(shape: Chamfer2d) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Chamfer2d> for Shape2d {
+    fn from(shape: Chamfer2d) -> Self {
+        Self::Chamfer(Box::new(shape))
+    }
+}
+
 /// A circle
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Circle {
     /// The radius of the circle
@@ -81,6 +149,7 @@ impl From<Circle> for Shape2d {
 
 /// A difference between two shapes
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Difference2d {
     /// The original shape
@@ -121,6 +190,101 @@ impl From<Difference2d> for Shape2d {
     }
 }
 
+/// A shape with rounded corners
+///
+/// Each corner between two straight edges is replaced by an arc of the given
+/// radius, which touches both edges. The radius must not be negative. A radius
+/// of zero leaves the shape as it is.
+///
+/// # Limitations
+///
+/// Corners that involve curved edges aren't rounded. Computing the shape fails,
+/// if the arcs at both ends of an edge don't fit into it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Fillet2d {
+    /// The shape whose corners are rounded
+    shape: Shape2d,
+
+    /// The radius of the rounded corners
+    radius: f64,
+}
+
+impl Fillet2d {
+    pub fn from_shape_and_radius(shape: Shape2d, radius: f64) -> Self {
+        Self { shape, radius }
+    }
+
+    pub fn shape(&self) -> &Shape2d {
+        &self.shape
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Get the rendering color of the shape in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.shape.color()
+    }
+}
+
+impl From<Fillet2d> for Shape {
+    fn from(shape: Fillet2d) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Fillet2d> for Shape2d {
+    fn from(shape: Fillet2d) -> Self {
+        Self::Fillet(Box::new(shape))
+    }
+}
+
+/// An intersection between two shapes
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Intersection2d {
+    /// The first of the intersected shapes
+    a: Shape2d,
+
+    /// The second of the intersected shapes
+    b: Shape2d,
+}
+
+impl Intersection2d {
+    pub fn from_objects(a: Shape2d, b: Shape2d) -> Self {
+        Self { a, b }
+    }
+
+    /// Get the rendering color of the first object in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.a.color()
+    }
+
+    pub fn a(&self) -> &Shape2d {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Shape2d {
+        &self.b
+    }
+}
+
+impl From<Intersection2d> for Shape {
+    fn from(shape: Intersection2d) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Intersection2d> for Shape2d {
+    fn from(shape: Intersection2d) -> Self {
+        Self::Intersection(Box::new(shape))
+    }
+}
+
 /// A sketch
 ///
 /// Sketches are currently limited to a single cycle of straight lines,
@@ -131,56 +295,30 @@ impl From<Difference2d> for Shape2d {
 /// that the edges are non-overlapping. If you create a `Sketch` with
 /// overlapping edges, you're on your own.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "SketchData", from = "SketchData")
+)]
 #[repr(C)]
 pub struct Sketch {
-    // The fields are the raw parts of a `Vec`. `Sketch` needs to be FFI-safe,
-    // meaning it can't store a `Vec` directly. It needs to take this detour.
-    ptr: *mut [f64; 2],
-    length: usize,
-    capacity: usize,
+    points: RawVec<[f64; 2]>,
     // The color of the sketch in RGBA
     color: [u8; 4],
 }
 
 impl Sketch {
     /// Create a sketch from a bunch of points
-    pub fn from_points(mut points: Vec<[f64; 2]>) -> Self {
-        // This can be cleaned up, once `Vec::into_raw_parts` is stable.
-        let ptr = points.as_mut_ptr();
-        let length = points.len();
-        let capacity = points.capacity();
-
-        // We're taking ownership of the memory here, so we can't allow `points`
-        // to deallocate it.
-        mem::forget(points);
-
+    pub fn from_points(points: Vec<[f64; 2]>) -> Self {
         Self {
-            ptr,
-            length,
-            capacity,
+            points: RawVec::from_vec(points),
             color: [255, 0, 0, 255],
         }
     }
 
     /// Return the points of the sketch
     pub fn to_points(&self) -> Vec<[f64; 2]> {
-        // This is sound. All invariants are automatically kept, as the raw
-        // parts come from an original `Vec` that is identical to the new one we
-        // create here, and aren't being modified anywhere.
-        let points = unsafe {
-            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
-        };
-
-        // Ownership of the pointer in `self.raw_parts` transferred to `points`.
-        // We work around that, by returning a clone of `points` (hence not
-        // giving ownership to the caller).
-        let ret = points.clone();
-
-        // Now we just need to forget that `points` ever existed, and we keep
-        // ownership of the pointer.
-        mem::forget(points);
-
-        ret
+        self.points.to_vec()
     }
 
     /// Set the rendering color of the sketch in RGBA
@@ -212,6 +350,73 @@ impl From<Sketch> for Shape2d {
     }
 }
 
-// `Sketch` can be `Send`, because it encapsulates the raw pointer it contains,
-// making sure memory ownership rules are observed.
-unsafe impl Send for Sketch {}
+/// A union of two shapes
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Union2d {
+    /// The first of the combined shapes
+    a: Shape2d,
+
+    /// The second of the combined shapes
+    b: Shape2d,
+}
+
+impl Union2d {
+    pub fn from_objects(a: Shape2d, b: Shape2d) -> Self {
+        Self { a, b }
+    }
+
+    /// Get the rendering color of the first object in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.a.color()
+    }
+
+    pub fn a(&self) -> &Shape2d {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Shape2d {
+        &self.b
+    }
+}
+
+impl From<Union2d> for Shape {
+    fn from(shape: Union2d) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Union2d> for Shape2d {
+    fn from(shape: Union2d) -> Self {
+        Self::Union(Box::new(shape))
+    }
+}
+
+/// The serialized form of [`Sketch`]
+///
+/// `Sketch` stores the raw parts of a `Vec`, which can't be serialized
+/// directly.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SketchData {
+    points: Vec<[f64; 2]>,
+    color: [u8; 4],
+}
+
+#[cfg(feature = "serde")]
+impl From<Sketch> for SketchData {
+    fn from(sketch: Sketch) -> Self {
+        Self {
+            points: sketch.to_points(),
+            color: sketch.color,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SketchData> for Sketch {
+    fn from(data: SketchData) -> Self {
+        Sketch::from_points(data.points).with_color(data.color)
+    }
+}
@@ -1,17 +1,39 @@
-use crate::{Shape, Shape2d};
+use crate::{raw_vec::RawVec, Shape, Shape2d};
 
 /// A 3-dimensional shape
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub enum Shape3d {
+    /// A sweep with beveled edges
+    Chamfer(Chamfer),
+
+    /// The difference of two 3-dimensional shapes
+    Difference(Box<Difference3d>),
+
+    /// A sweep with rounded edges
+    Fillet(Fillet),
+
     /// A group of two 3-dimensional shapes
     Group(Box<Group>),
 
-    /// A sweep of 2-dimensional shape along the z-axis
+    /// The intersection of two 3-dimensional shapes
+    Intersection(Box<Intersection>),
+
+    /// A loft between 2-dimensional profiles
+    Loft(Loft),
+
+    /// A revolution of a 2-dimensional shape around an axis
+    Revolve(Revolve),
+
+    /// A sweep of 2-dimensional shape along a path
     Sweep(Sweep),
 
     /// A transformed 3-dimensional shape
     Transform(Box<Transform>),
+
+    /// The union of two 3-dimensional shapes
+    Union(Box<Union>),
 }
 
 impl From<Shape3d> for Shape {
@@ -20,15 +42,126 @@ impl From<Shape3d> for Shape {
     }
 }
 
+/// A sweep with beveled edges
+///
+/// The edges selected by `edges` are cut off, `distance` from the edge along
+/// both adjacent faces. The distance must not be negative. A distance of zero
+/// leaves the sweep as it is.
+///
+/// # Limitations
+///
+/// Only sweeps along a straight line, without taper or twist, are supported.
+/// Computing the shape fails for other sweeps, unless the distance is zero, or
+/// no edges are selected. Corners of the shape that involve curved edges
+/// aren't beveled. Computing the shape also fails, if the distance is too large
+/// for the shape.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Chamfer {
+    /// The sweep whose edges are beveled
+    pub sweep: Sweep,
+
+    /// The distance from each edge at which the bevel starts
+    pub distance: f64,
+
+    /// The edges that are beveled
+    pub edges: SweepEdges,
+}
+
+impl From<Chamfer> for Shape {
+    fn from(shape: Chamfer) -> Self {
+        Self::Shape3d(shape.into())
+    }
+}
+
+impl From<Chamfer> for Shape3d {
+    fn from(shape: Chamfer) -> Self {
+        Self::Chamfer(shape)
+    }
+}
+
+/// The difference of two 3-dimensional shapes
+///
+/// Everything that is part of `a`, but not part of `b`.
+///
+/// Faces that are cut by the other shape are approximated by triangles, as the
+/// kernel can't compute exact intersections between surfaces yet. This
+/// applies to the exact geometry of the result too, as it is exported to STEP,
+/// for example. Faces that aren't cut keep their exact geometry.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Difference3d {
+    /// The shape that is subtracted from
+    pub a: Shape3d,
+
+    /// The shape that is subtracted
+    pub b: Shape3d,
+}
+
+impl From<Difference3d> for Shape {
+    fn from(shape: Difference3d) -> Self {
+        Self::Shape3d(Shape3d::Difference(Box::new(shape)))
+    }
+}
+
+impl From<Difference3d> for Shape3d {
+    fn from(shape: Difference3d) -> Self {
+        Self::Difference(Box::new(shape))
+    }
+}
+
+/// A sweep with rounded edges
+///
+/// The edges selected by `edges` are replaced by rounded faces of the given
+/// radius. The radius must not be negative. A radius of zero leaves the sweep
+/// as it is.
+///
+/// # Limitations
+///
+/// Only sweeps along a straight line, without taper or twist, are supported.
+/// Computing the shape fails for other sweeps, unless the radius is zero, or
+/// no edges are selected. Corners of the shape that involve curved edges
+/// aren't rounded. Computing the shape also fails, if the radius is too large
+/// for the shape.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Fillet {
+    /// The sweep whose edges are rounded
+    pub sweep: Sweep,
+
+    /// The radius of the rounded edges
+    pub radius: f64,
+
+    /// The edges that are rounded
+    pub edges: SweepEdges,
+}
+
+impl From<Fillet> for Shape {
+    fn from(shape: Fillet) -> Self {
+        Self::Shape3d(shape.into())
+    }
+}
+
+impl From<Fillet> for Shape3d {
+    fn from(shape: Fillet) -> Self {
+        Self::Fillet(shape)
+    }
+}
+
 /// A group of two 3-dimensional shapes
 ///
 /// A group is a collection of disjoint shapes. It is not a union, in that the
-/// shapes in the group are not allowed to touch or overlap.
+/// shapes in the group are not allowed to touch or overlap. Use [`Union`] for
+/// shapes that do.
 ///
 /// # Limitations
 ///
 /// Whether the shapes in the group touch or overlap is not currently checked.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Group {
     /// The first of the shapes
@@ -50,6 +183,197 @@ impl From<Group> for Shape3d {
     }
 }
 
+/// The intersection of two 3-dimensional shapes
+///
+/// Everything that is part of both shapes. Faces that are cut are approximated
+/// by triangles, like with [`Difference3d`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Intersection {
+    /// The first of the shapes
+    pub a: Shape3d,
+
+    /// The second of the shapes
+    pub b: Shape3d,
+}
+
+impl From<Intersection> for Shape {
+    fn from(shape: Intersection) -> Self {
+        Self::Shape3d(Shape3d::Intersection(Box::new(shape)))
+    }
+}
+
+impl From<Intersection> for Shape3d {
+    fn from(shape: Intersection) -> Self {
+        Self::Intersection(Box::new(shape))
+    }
+}
+
+/// A loft between 2-dimensional profiles
+///
+/// The profiles are placed at their respective offsets along the z-axis, and
+/// each profile is connected to the next one. The first and the last profile
+/// close off the shape.
+///
+/// The profiles must be ordered by strictly increasing offsets. Consecutive
+/// profiles must consist of the same number of faces, with the same number of
+/// cycles each. Computing the shape fails otherwise.
+///
+/// # Limitations
+///
+/// Edges are connected to the corresponding edges of the next profile, if both
+/// cycles have the same number of edges. Otherwise, the cycles are connected as
+/// a whole, starting at their closest points.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "LoftData", from = "LoftData")
+)]
+#[repr(C)]
+pub struct Loft {
+    profiles: RawVec<Profile>,
+}
+
+impl Loft {
+    /// Create a loft from a bunch of profiles
+    ///
+    /// The profiles must be ordered by strictly increasing offsets.
+    pub fn from_profiles(profiles: Vec<Profile>) -> Self {
+        Self {
+            profiles: RawVec::from_vec(profiles),
+        }
+    }
+
+    /// Return the profiles of the loft
+    pub fn to_profiles(&self) -> Vec<Profile> {
+        self.profiles.to_vec()
+    }
+
+    /// The color of the loft, as defined by its first profile
+    pub fn color(&self) -> [u8; 4] {
+        self.to_profiles()
+            .first()
+            .map(|profile| profile.shape.color())
+            .unwrap_or([255, 0, 0, 255])
+    }
+}
+
+impl From<Loft> for Shape {
+    fn from(shape: Loft) -> Self {
+        Self::Shape3d(shape.into())
+    }
+}
+
+impl From<Loft> for Shape3d {
+    fn from(shape: Loft) -> Self {
+        Self::Loft(shape)
+    }
+}
+
+/// The serialized form of [`Loft`]
+///
+/// `Loft` stores the raw parts of a `Vec`, which can't be serialized directly.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LoftData {
+    profiles: Vec<Profile>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Loft> for LoftData {
+    fn from(loft: Loft) -> Self {
+        Self {
+            profiles: loft.to_profiles(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<LoftData> for Loft {
+    fn from(data: LoftData) -> Self {
+        Loft::from_profiles(data.profiles)
+    }
+}
+
+/// A profile of a [`Loft`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Profile {
+    /// The 2-dimensional shape of the profile
+    pub shape: Shape2d,
+
+    /// The offset of the profile along the z-axis
+    pub z: f64,
+}
+
+/// A revolution of a 2-dimensional shape around an axis
+///
+/// The axis goes through the origin, in the direction of `axis`, and lies in
+/// the x-y plane, like the shape itself. The shape revolves counter-clockwise
+/// around the axis, by `angle` (in radians). Revolving by a full turn creates
+/// a closed shape.
+///
+/// The axis must have a direction, and the angle must not be zero. Otherwise,
+/// computing the revolution fails.
+///
+/// # Limitations
+///
+/// The shape must not cross the axis, but it may touch it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Revolve {
+    /// The 2-dimensional shape being revolved
+    shape: Shape2d,
+
+    /// The direction of the axis that the shape is revolved around
+    axis: [f64; 2],
+
+    /// The angle of the revolution
+    angle: f64,
+}
+
+impl Revolve {
+    pub fn from_shape_axis_and_angle(
+        shape: Shape2d,
+        axis: [f64; 2],
+        angle: f64,
+    ) -> Self {
+        Self { shape, axis, angle }
+    }
+
+    pub fn shape(&self) -> &Shape2d {
+        &self.shape
+    }
+
+    pub fn axis(&self) -> [f64; 2] {
+        self.axis
+    }
+
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
+
+    pub fn color(&self) -> [u8; 4] {
+        self.shape().color()
+    }
+}
+
+impl From<Revolve> for Shape {
+    fn from(shape: Revolve) -> Self {
+        Self::Shape3d(shape.into())
+    }
+}
+
+impl From<Revolve> for Shape3d {
+    fn from(shape: Revolve) -> Self {
+        Self::Revolve(shape)
+    }
+}
+
 /// A transformed 3-dimensional shape
 ///
 /// # Limitations
@@ -60,6 +384,7 @@ impl From<Group> for Shape3d {
 /// See issue:
 /// <https://github.com/hannobraun/Fornjot/issues/101>
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Transform {
     /// The shape being rotated
@@ -87,28 +412,103 @@ impl From<Transform> for Shape3d {
     }
 }
 
-/// A sweep of a 2-dimensional shape along the z-axis
+/// A sweep of a 2-dimensional shape along a path
+///
+/// The path starts at the origin, where the shape is.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Sweep {
     /// The 2-dimensional shape being swept
     shape: Shape2d,
 
-    /// The length of the sweep
-    length: f64,
+    /// The path that the shape is swept along
+    path: Path,
+
+    /// The draft angle of the sweep, in radians
+    #[cfg_attr(feature = "serde", serde(default))]
+    taper: f64,
+
+    /// The angle that the shape is twisted by, in radians
+    #[cfg_attr(feature = "serde", serde(default))]
+    twist: f64,
 }
 
 impl Sweep {
+    /// Sweep a shape along the z-axis
     pub fn from_shape_and_length(shape: Shape2d, length: f64) -> Self {
-        Self { shape, length }
+        Self::from_shape_and_direction(shape, [0., 0., length])
+    }
+
+    /// Sweep a shape in a straight line
+    ///
+    /// The length of `direction` defines the length of the sweep.
+    pub fn from_shape_and_direction(
+        shape: Shape2d,
+        direction: [f64; 3],
+    ) -> Self {
+        Self::from_shape_and_path(shape, direction.into())
+    }
+
+    /// Sweep a shape along a path
+    pub fn from_shape_and_path(shape: Shape2d, path: Path) -> Self {
+        Self {
+            shape,
+            path,
+            taper: 0.,
+            twist: 0.,
+        }
+    }
+
+    /// Taper the sweep by the given draft angle, in radians
+    ///
+    /// The shape is scaled towards the origin, as it moves along the path. The
+    /// point of the shape that is farthest from the origin moves inward at the
+    /// draft angle. A negative angle makes the shape grow instead.
+    ///
+    /// The shape must not shrink to a point before the end of the path. Along
+    /// a path of length `l`, the farthest point moves inward by
+    /// `l * angle.tan()`, which must be less than its distance from the
+    /// origin. Otherwise, computing the sweep fails.
+    pub fn with_taper(mut self, angle: f64) -> Self {
+        self.taper = angle;
+        self
+    }
+
+    /// Twist the sweep by the given angle, in radians
+    ///
+    /// The shape rotates counter-clockwise around its z-axis, as it moves
+    /// along the path, reaching the full angle at the end.
+    pub fn with_twist(mut self, angle: f64) -> Self {
+        self.twist = angle;
+        self
     }
 
     pub fn shape(&self) -> &Shape2d {
         &self.shape
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The length of the sweep
+    ///
+    /// This is the distance between the start and the end of the path, in a
+    /// straight line. For sweeps along the z-axis, this is the length they
+    /// were created with, but it doesn't fully describe any other sweep.
+    #[deprecated(note = "Sweeps can follow any path. Use `Sweep::path`.")]
     pub fn length(&self) -> f64 {
-        self.length
+        let [x, y, z] = self.path.end();
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    pub fn taper(&self) -> f64 {
+        self.taper
+    }
+
+    pub fn twist(&self) -> f64 {
+        self.twist
     }
 
     pub fn color(&self) -> [u8; 4] {
@@ -127,3 +527,220 @@ impl From<Sweep> for Shape3d {
         Self::Sweep(shape)
     }
 }
+
+/// A path that a shape can be swept along
+///
+/// A path consists of segments, each of which starts where the previous one
+/// ended.
+///
+/// A path needs at least one segment. Lines must not have a length of zero,
+/// and arcs must have an axis and an angle that aren't zero. Otherwise,
+/// computing the sweep fails.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "PathData", from = "PathData")
+)]
+#[repr(C)]
+pub struct Path {
+    segments: RawVec<PathSegment>,
+}
+
+impl Path {
+    /// Create a path from a bunch of segments
+    pub fn from_segments(segments: Vec<PathSegment>) -> Self {
+        Self {
+            segments: RawVec::from_vec(segments),
+        }
+    }
+
+    /// Return the segments of the path
+    pub fn to_segments(&self) -> Vec<PathSegment> {
+        self.segments.to_vec()
+    }
+
+    /// The point where the path ends, if it starts at the origin
+    fn end(&self) -> [f64; 3] {
+        let mut point = [0.; 3];
+
+        for segment in self.to_segments() {
+            match segment {
+                PathSegment::Line(direction) => {
+                    point = add(point, direction);
+                }
+                PathSegment::Arc {
+                    center,
+                    axis,
+                    angle,
+                } => {
+                    let magnitude = dot(axis, axis).sqrt();
+                    if magnitude == 0. {
+                        continue;
+                    }
+                    let k = scale(axis, 1. / magnitude);
+
+                    // Rodrigues' rotation formula
+                    let v = add(point, scale(center, -1.));
+                    let (sin, cos) = angle.sin_cos();
+                    let v = add(
+                        add(scale(v, cos), scale(cross(k, v), sin)),
+                        scale(k, dot(k, v) * (1. - cos)),
+                    );
+
+                    point = add(center, v);
+                }
+            }
+        }
+
+        point
+    }
+}
+
+fn add([ax, ay, az]: [f64; 3], [bx, by, bz]: [f64; 3]) -> [f64; 3] {
+    [ax + bx, ay + by, az + bz]
+}
+
+fn scale([x, y, z]: [f64; 3], s: f64) -> [f64; 3] {
+    [x * s, y * s, z * s]
+}
+
+fn dot([ax, ay, az]: [f64; 3], [bx, by, bz]: [f64; 3]) -> f64 {
+    ax * bx + ay * by + az * bz
+}
+
+fn cross([ax, ay, az]: [f64; 3], [bx, by, bz]: [f64; 3]) -> [f64; 3] {
+    [ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx]
+}
+
+impl From<[f64; 3]> for Path {
+    fn from(direction: [f64; 3]) -> Self {
+        Self::from_segments(vec![PathSegment::Line(direction)])
+    }
+}
+
+/// The serialized form of [`Path`]
+///
+/// `Path` stores the raw parts of a `Vec`, which can't be serialized directly.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PathData {
+    segments: Vec<PathSegment>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Path> for PathData {
+    fn from(path: Path) -> Self {
+        Self {
+            segments: path.to_segments(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<PathData> for Path {
+    fn from(data: PathData) -> Self {
+        Path::from_segments(data.segments)
+    }
+}
+
+/// A segment of a [`Path`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum PathSegment {
+    /// A straight line
+    ///
+    /// The shape is moved by the given vector, without being rotated.
+    Line([f64; 3]),
+
+    /// An arc around an axis
+    ///
+    /// The shape is rotated counter-clockwise around an axis that goes through
+    /// `center`, in the direction of `axis`, by `angle` (in radians).
+    Arc {
+        /// A point on the axis
+        center: [f64; 3],
+
+        /// The direction of the axis
+        axis: [f64; 3],
+
+        /// The angle of the rotation
+        angle: f64,
+    },
+}
+
+/// A selection of the edges of a [`Sweep`]
+///
+/// Used by [`Fillet`] and [`Chamfer`], to select the edges they apply to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct SweepEdges {
+    /// The edges along the path, created by the corners of the shape
+    pub side: bool,
+
+    /// The edges around the end of the sweep
+    pub top: bool,
+
+    /// The edges around the start of the sweep
+    pub bottom: bool,
+}
+
+impl SweepEdges {
+    /// All edges of the sweep
+    pub const ALL: Self = Self {
+        side: true,
+        top: true,
+        bottom: true,
+    };
+
+    /// The edges along the path, created by the corners of the shape
+    pub const SIDE: Self = Self {
+        side: true,
+        top: false,
+        bottom: false,
+    };
+
+    /// The edges around the end of the sweep
+    pub const TOP: Self = Self {
+        side: false,
+        top: true,
+        bottom: false,
+    };
+
+    /// The edges around the start of the sweep
+    pub const BOTTOM: Self = Self {
+        side: false,
+        top: false,
+        bottom: true,
+    };
+}
+
+/// The union of two 3-dimensional shapes
+///
+/// Everything that is part of either shape. Unlike a [`Group`], the shapes may
+/// touch or overlap. Faces that are cut are approximated by triangles, like
+/// with [`Difference3d`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Union {
+    /// The first of the shapes
+    pub a: Shape3d,
+
+    /// The second of the shapes
+    pub b: Shape3d,
+}
+
+impl From<Union> for Shape {
+    fn from(shape: Union) -> Self {
+        Self::Shape3d(Shape3d::Union(Box::new(shape)))
+    }
+}
+
+impl From<Union> for Shape3d {
+    fn from(shape: Union) -> Self {
+        Self::Union(Box::new(shape))
+    }
+}
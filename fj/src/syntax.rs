@@ -1,3 +1,46 @@
+pub trait Chamfer {
+    /// Bevel the edges of a sweep
+    ///
+    /// Create a bevel that cuts off the edges selected by `edges`, `distance`
+    /// from each edge.
+    fn chamfer(
+        &self,
+        distance: f64,
+        edges: crate::SweepEdges,
+    ) -> crate::Chamfer;
+}
+
+impl Chamfer for crate::Sweep {
+    fn chamfer(
+        &self,
+        distance: f64,
+        edges: crate::SweepEdges,
+    ) -> crate::Chamfer {
+        crate::Chamfer {
+            sweep: self.clone(),
+            distance,
+            edges,
+        }
+    }
+}
+
+pub trait Chamfer2d {
+    /// Bevel the corners of a shape
+    ///
+    /// Create a bevel that cuts off each corner, `distance` from the corner.
+    fn chamfer(&self, distance: f64) -> crate::Chamfer2d;
+}
+
+impl<T> Chamfer2d for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn chamfer(&self, distance: f64) -> crate::Chamfer2d {
+        let shape = self.clone().into();
+        crate::Chamfer2d::from_shape_and_distance(shape, distance)
+    }
+}
+
 pub trait Difference {
     fn difference<Other>(&self, other: &Other) -> crate::Difference2d
     where
@@ -19,6 +62,62 @@ where
     }
 }
 
+pub trait Difference3d {
+    fn difference<Other>(&self, other: &Other) -> crate::Difference3d
+    where
+        Other: Clone + Into<crate::Shape3d>;
+}
+
+impl<T> Difference3d for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn difference<Other>(&self, other: &Other) -> crate::Difference3d
+    where
+        Other: Clone + Into<crate::Shape3d>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Difference3d { a, b }
+    }
+}
+
+pub trait Fillet {
+    /// Round the edges of a sweep
+    ///
+    /// Create a fillet that rounds the edges selected by `edges`, using
+    /// `radius`.
+    fn fillet(&self, radius: f64, edges: crate::SweepEdges) -> crate::Fillet;
+}
+
+impl Fillet for crate::Sweep {
+    fn fillet(&self, radius: f64, edges: crate::SweepEdges) -> crate::Fillet {
+        crate::Fillet {
+            sweep: self.clone(),
+            radius,
+            edges,
+        }
+    }
+}
+
+pub trait Fillet2d {
+    /// Round the corners of a shape
+    ///
+    /// Create a fillet that replaces each corner with an arc of `radius`.
+    fn fillet(&self, radius: f64) -> crate::Fillet2d;
+}
+
+impl<T> Fillet2d for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn fillet(&self, radius: f64) -> crate::Fillet2d {
+        let shape = self.clone().into();
+        crate::Fillet2d::from_shape_and_radius(shape, radius)
+    }
+}
+
 pub trait Group {
     fn group<Other>(&self, other: &Other) -> crate::Group
     where
@@ -40,6 +139,66 @@ where
     }
 }
 
+pub trait Intersection {
+    fn intersection<Other>(&self, other: &Other) -> crate::Intersection
+    where
+        Other: Clone + Into<crate::Shape3d>;
+}
+
+impl<T> Intersection for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn intersection<Other>(&self, other: &Other) -> crate::Intersection
+    where
+        Other: Clone + Into<crate::Shape3d>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Intersection { a, b }
+    }
+}
+
+pub trait Intersection2d {
+    fn intersection<Other>(&self, other: &Other) -> crate::Intersection2d
+    where
+        Other: Clone + Into<crate::Shape2d>;
+}
+
+impl<T> Intersection2d for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn intersection<Other>(&self, other: &Other) -> crate::Intersection2d
+    where
+        Other: Clone + Into<crate::Shape2d>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Intersection2d::from_objects(a, b)
+    }
+}
+
+pub trait Revolve {
+    /// Create a revolution
+    ///
+    /// Create a revolution that revolves `shape` by `angle` around an axis
+    /// through the origin, in the direction of `axis`.
+    fn revolve(&self, axis: [f64; 2], angle: f64) -> crate::Revolve;
+}
+
+impl<T> Revolve for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn revolve(&self, axis: [f64; 2], angle: f64) -> crate::Revolve {
+        let shape = self.clone().into();
+        crate::Revolve::from_shape_axis_and_angle(shape, axis, angle)
+    }
+}
+
 pub trait Rotate {
     /// Create a rotation
     ///
@@ -78,6 +237,12 @@ where
 
 pub trait Sweep {
     fn sweep(&self, length: f64) -> crate::Sweep;
+
+    /// Create a sweep along a path
+    ///
+    /// `path` can be a [`crate::Path`], or a direction (`[f64; 3]`), for a
+    /// sweep in a straight line.
+    fn sweep_along(&self, path: impl Into<crate::Path>) -> crate::Sweep;
 }
 
 impl<T> Sweep for T
@@ -88,6 +253,11 @@ where
         let shape = self.clone().into();
         crate::Sweep::from_shape_and_length(shape, length)
     }
+
+    fn sweep_along(&self, path: impl Into<crate::Path>) -> crate::Sweep {
+        let shape = self.clone().into();
+        crate::Sweep::from_shape_and_path(shape, path.into())
+    }
 }
 
 pub trait Translate {
@@ -111,3 +281,45 @@ where
         }
     }
 }
+
+pub trait Union {
+    fn union<Other>(&self, other: &Other) -> crate::Union
+    where
+        Other: Clone + Into<crate::Shape3d>;
+}
+
+impl<T> Union for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn union<Other>(&self, other: &Other) -> crate::Union
+    where
+        Other: Clone + Into<crate::Shape3d>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Union { a, b }
+    }
+}
+
+pub trait Union2d {
+    fn union<Other>(&self, other: &Other) -> crate::Union2d
+    where
+        Other: Clone + Into<crate::Shape2d>;
+}
+
+impl<T> Union2d for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn union<Other>(&self, other: &Other) -> crate::Union2d
+    where
+        Other: Clone + Into<crate::Shape2d>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Union2d::from_objects(a, b)
+    }
+}
@@ -0,0 +1,105 @@
+use std::{fmt, os::raw::c_char};
+
+/// The version of this crate
+///
+/// Models and the Fornjot host pass types from this crate (like [`Shape`]) to
+/// each other directly. This only works, if both have been built against the
+/// same version of this crate. The host calls [`fj_abi_version`] to check that,
+/// before calling into a model. As the layout of the types can change between
+/// releases, it also calls [`fj_source_hash`]. And since both need to be built
+/// by the same compiler too, it calls [`fj_rustc_version`].
+///
+/// [`Shape`]: crate::Shape
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct Version {
+    /// The major version
+    pub major: u32,
+
+    /// The minor version
+    pub minor: u32,
+
+    /// The patch version
+    pub patch: u32,
+}
+
+impl Version {
+    /// The version of this crate, as it was compiled
+    pub const CURRENT: Self = Self {
+        major: parse(env!("CARGO_PKG_VERSION_MAJOR")),
+        minor: parse(env!("CARGO_PKG_VERSION_MINOR")),
+        patch: parse(env!("CARGO_PKG_VERSION_PATCH")),
+    };
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Return the version of `fj` that the model was built against
+///
+/// This function is exported by every model, as every model depends on this
+/// crate. Models don't need to do anything to make that happen.
+#[no_mangle]
+pub extern "C" fn fj_abi_version() -> Version {
+    Version::CURRENT
+}
+
+/// A hash of the source code of this crate
+///
+/// Two builds of the same version of this crate can be incompatible, if that
+/// version was not released (for example, when using the crate from Git). The
+/// host compares this hash, in addition to the version.
+pub const SOURCE_HASH: u64 =
+    include!(concat!(env!("OUT_DIR"), "/source_hash.rs"));
+
+/// Return the hash of the source code of `fj` that the model was built against
+///
+/// See [`SOURCE_HASH`]. Like [`fj_abi_version`], this function is exported by
+/// every model.
+#[no_mangle]
+pub extern "C" fn fj_source_hash() -> u64 {
+    SOURCE_HASH
+}
+
+/// The version of the compiler that built this crate
+///
+/// As reported by `rustc --version`.
+pub const RUSTC_VERSION: &str =
+    include!(concat!(env!("OUT_DIR"), "/rustc_version.rs"));
+
+// Has a trailing null byte, so it can be passed across the FFI boundary.
+const RUSTC_VERSION_NUL: &str = concat!(
+    include!(concat!(env!("OUT_DIR"), "/rustc_version.rs")),
+    "\0"
+);
+
+/// Return the version of the compiler that built the model
+///
+/// The host and the model call each other's functions using the Rust ABI,
+/// which is only stable for a given version of the compiler. The host calls
+/// this function to check that it was built by the same compiler as the model.
+///
+/// Returns a pointer to a null-terminated string, which stays valid as long as
+/// the model library is loaded. Like [`fj_abi_version`], this function is
+/// exported by every model.
+#[no_mangle]
+pub extern "C" fn fj_rustc_version() -> *const c_char {
+    RUSTC_VERSION_NUL.as_ptr() as *const c_char
+}
+
+// `str::parse` isn't available in const contexts.
+const fn parse(number: &str) -> u32 {
+    let bytes = number.as_bytes();
+
+    let mut value = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
+    }
+
+    value
+}
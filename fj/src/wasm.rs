@@ -0,0 +1,55 @@
+//! Glue code for models that are compiled to WebAssembly
+//!
+//! A WebAssembly module can't exchange Rust types with the host directly, so
+//! the Fornjot host can't call the `model` function itself. Instead, it calls
+//! the functions in this module, which pass the arguments and the resulting
+//! shape across the boundary in serialized form (as JSON).
+//!
+//! This module is only compiled for `wasm32` targets with the `wasm` feature
+//! enabled. The Fornjot host enables that feature, when it compiles a model to
+//! WebAssembly, so model code doesn't need to change.
+
+use std::{collections::HashMap, slice};
+
+use crate::Shape;
+
+extern "Rust" {
+    // The `model` function that every model exports.
+    fn model(args: &HashMap<String, String>) -> Shape;
+}
+
+/// Allocate a buffer of `len` bytes in the module's memory
+///
+/// Used by the host to pass the arguments to [`fj_model`]. The buffer is never
+/// freed, as the host discards the whole module instance after calling the
+/// model once.
+#[no_mangle]
+pub extern "C" fn fj_alloc(len: usize) -> *mut u8 {
+    Box::into_raw(vec![0; len].into_boxed_slice()) as *mut u8
+}
+
+/// Call the model with serialized arguments
+///
+/// Expects the arguments as a JSON object of strings. Returns the location of
+/// the serialized shape, with the pointer in the upper 32 bits, and the length
+/// in the lower 32 bits.
+///
+/// # Safety
+///
+/// `args_ptr` and `args_len` must describe a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn fj_model(args_ptr: *const u8, args_len: usize) -> u64 {
+    let args = slice::from_raw_parts(args_ptr, args_len);
+    let args: HashMap<String, String> =
+        serde_json::from_slice(args).expect("Failed to deserialize arguments");
+
+    let shape = model(&args);
+
+    let shape = serde_json::to_vec(&shape)
+        .expect("Failed to serialize shape")
+        .into_boxed_slice();
+    let len = shape.len() as u64;
+    let ptr = Box::into_raw(shape) as *mut u8 as u64;
+
+    ptr << 32 | len
+}
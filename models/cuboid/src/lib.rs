@@ -1,10 +1,21 @@
 use std::collections::HashMap;
 
 #[no_mangle]
-pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
-    let x: f64 = args.get("x").unwrap_or(&"3.0".to_owned()).parse().unwrap();
-    let y: f64 = args.get("y").unwrap_or(&"2.0".to_owned()).parse().unwrap();
-    let z: f64 = args.get("z").unwrap_or(&"1.0".to_owned()).parse().unwrap();
+pub fn parameters(parameters: &mut fj::Parameters) {
+    parameters
+        .add(fj::Parameter::float("x", 3.0).with_min(0.))
+        .add(fj::Parameter::float("y", 2.0).with_min(0.))
+        .add(fj::Parameter::float("z", 1.0).with_min(0.));
+}
+
+#[no_mangle]
+pub fn model(args: &HashMap<String, String>) -> fj::Shape {
+    let mut declared = fj::Parameters::new();
+    parameters(&mut declared);
+
+    let x = declared.value(args, "x").to_f64();
+    let y = declared.value(args, "y").to_f64();
+    let z = declared.value(args, "z").to_f64();
 
     #[rustfmt::skip]
     let rectangle = fj::Sketch::from_points(vec![
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use fj::prelude::*;
 
 #[no_mangle]
-pub extern "C" fn model(_: &HashMap<String, String>) -> fj::Shape {
+pub fn model(_: &HashMap<String, String>) -> fj::Shape {
     #[rustfmt::skip]
     let vertices = vec![
         [-0.5, -0.5],
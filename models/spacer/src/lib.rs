@@ -3,22 +3,33 @@ use std::collections::HashMap;
 use fj::prelude::*;
 
 #[no_mangle]
-pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
-    let outer = args
-        .get("outer")
-        .unwrap_or(&"1.0".to_owned())
-        .parse()
-        .unwrap();
-    let inner = args
-        .get("inner")
-        .unwrap_or(&"0.5".to_owned())
-        .parse()
-        .unwrap();
-    let height: f64 = args
-        .get("height")
-        .unwrap_or(&"1.0".to_owned())
-        .parse()
-        .unwrap();
+pub fn parameters(parameters: &mut fj::Parameters) {
+    parameters
+        .add(
+            fj::Parameter::float("outer", 1.0)
+                .with_min(0.)
+                .with_description("Outer radius"),
+        )
+        .add(
+            fj::Parameter::float("inner", 0.5)
+                .with_min(0.)
+                .with_description("Inner radius, must be less than `outer`"),
+        )
+        .add(
+            fj::Parameter::float("height", 1.0)
+                .with_min(0.)
+                .with_description("Height of the spacer"),
+        );
+}
+
+#[no_mangle]
+pub fn model(args: &HashMap<String, String>) -> fj::Shape {
+    let mut declared = fj::Parameters::new();
+    parameters(&mut declared);
+
+    let outer = declared.value(args, "outer").to_f64();
+    let inner = declared.value(args, "inner").to_f64();
+    let height = declared.value(args, "height").to_f64();
 
     let outer_edge =
         fj::Circle::from_radius(outer).with_color([0, 0, 255, 255]);
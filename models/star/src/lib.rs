@@ -1,30 +1,49 @@
 use std::{collections::HashMap, f64::consts::PI};
 
 #[no_mangle]
-pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
+pub fn parameters(parameters: &mut fj::Parameters) {
+    parameters
+        .add(
+            fj::Parameter::integer("num_points", 5)
+                .with_min(3.)
+                .with_description("Number of points of the star"),
+        )
+        .add(
+            fj::Parameter::float("r1", 1.0)
+                .with_min(0.)
+                .with_description("Radius of the inner vertices"),
+        )
+        .add(
+            fj::Parameter::float("r2", 2.0)
+                .with_min(0.)
+                .with_description("Radius of the pointy ends"),
+        )
+        .add(
+            fj::Parameter::float("h", 1.0)
+                .with_min(0.)
+                .with_description("Height of the star"),
+        );
+}
+
+#[no_mangle]
+pub fn model(args: &HashMap<String, String>) -> fj::Shape {
+    let mut declared = fj::Parameters::new();
+    parameters(&mut declared);
+
     // Number of points of the star
     //
     // "Points" in the sense of "pointy ends", not in the sense of geometrical
     // points, or vertices.
-    let num_points: u64 = args
-        .get("num_points")
-        .map(|arg| arg.parse().unwrap())
-        .unwrap_or(5);
+    let num_points = declared.value(args, "num_points").to_i64() as u64;
 
     // Radius of the circle that all the vertices between the pointy ends are on
-    let r1: f64 = args
-        .get("r1")
-        .map(|arg| arg.parse().unwrap())
-        .unwrap_or(1.0);
+    let r1 = declared.value(args, "r1").to_f64();
 
     // Radius of the circle that all the pointy ends are on
-    let r2: f64 = args
-        .get("r2")
-        .map(|arg| arg.parse().unwrap())
-        .unwrap_or(2.0);
+    let r2 = declared.value(args, "r2").to_f64();
 
     // The height of the star
-    let h: f64 = args.get("h").map(|arg| arg.parse().unwrap()).unwrap_or(1.0);
+    let h = declared.value(args, "h").to_f64();
 
     // We need to figure out where to generate vertices, depending on the number
     // of points the star is supposed to have. Let's generate an iterator that